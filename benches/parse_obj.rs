@@ -0,0 +1,56 @@
+// Compara la lectura en serie y en paralelo de person.obj: cargo bench --bench parse_obj
+use std::{
+    hint::black_box,
+    thread,
+    time::{Duration, Instant},
+};
+
+use graphics_playground::obj_parser::{parse_obj_from_str, parse_obj_from_str_parallel, FileResolver, ObjOptions};
+
+const FILE: &str = "person.obj";
+const ITERATIONS: usize = 20;
+
+// Mejor tiempo de varias ejecuciones, para que el ruido del sistema afecte lo menos posible
+fn best_of<F: FnMut()>(mut f: F) -> Duration {
+    (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let source = std::fs::read_to_string(FILE).unwrap();
+    let options = ObjOptions::default();
+    let resolver = FileResolver::for_file(FILE);
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+
+    let serial = parse_obj_from_str(&source, &options).unwrap();
+    let parallel = parse_obj_from_str_parallel(&source, &resolver, &options, threads).unwrap();
+    assert_eq!(serial.mesh, parallel.mesh, "parallel parser produced a different mesh");
+
+    let serial_time = best_of(|| {
+        black_box(parse_obj_from_str(black_box(&source), &options).unwrap());
+    });
+
+    println!("{} ({} bytes, {} vertices, {} cores)", FILE, source.len(), serial.mesh.vertices.len(), threads);
+    println!("serial:              {:>8.2} ms", serial_time.as_secs_f64() * 1000.0);
+
+    let mut counts = vec![2, 4, 8, threads];
+    counts.sort_unstable();
+    counts.dedup();
+    for count in counts {
+        let parallel_time = best_of(|| {
+            black_box(parse_obj_from_str_parallel(black_box(&source), &resolver, &options, count).unwrap());
+        });
+        println!(
+            "parallel, {:>2} threads: {:>8.2} ms ({:.2}x)",
+            count,
+            parallel_time.as_secs_f64() * 1000.0,
+            serial_time.as_secs_f64() / parallel_time.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use super::GltfError;
+
+const GLB_MAGIC: &[u8; 4] = b"glTF";
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+const CHUNK_BIN: u32 = 0x004E_4942;
+
+// Contenido de un .glb: el documento JSON y, opcionalmente, el buffer binario incrustado
+pub(super) struct Glb<'a>
+{
+    pub json: &'a [u8],
+    pub bin: Option<&'a [u8]>,
+}
+
+pub(super) fn is_glb(bytes: &[u8]) -> bool {
+    bytes.starts_with(GLB_MAGIC)
+}
+
+pub(super) fn parse_glb(bytes: &[u8]) -> Result<Glb<'_>, GltfError> {
+    let u32_at = |offset: usize| {
+        bytes
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+            .ok_or(GltfError::InvalidGlb("truncated file"))
+    };
+
+    let version = u32_at(4)?;
+    if version != 2 {
+        return Err(GltfError::UnsupportedVersion(version.to_string()));
+    }
+    let length = (u32_at(8)? as usize).min(bytes.len());
+
+    let mut json: Option<&[u8]> = None;
+    let mut bin: Option<&[u8]> = None;
+    let mut offset = 12;
+    while offset + 8 <= length {
+        let chunk_length = u32_at(offset)? as usize;
+        let chunk_type = u32_at(offset + 4)?;
+        let data = bytes
+            .get(offset + 8..offset + 8 + chunk_length)
+            .ok_or(GltfError::InvalidGlb("chunk extends past the end of the file"))?;
+
+        match chunk_type {
+            CHUNK_JSON if json.is_none() => json = Some(data),
+            CHUNK_BIN if bin.is_none() => bin = Some(data),
+            // Los bloques desconocidos se deben ignorar
+            _ => (),
+        }
+        // Los bloques están alineados a 4 bytes
+        offset += 8 + chunk_length.div_ceil(4) * 4;
+    }
+
+    Ok(Glb {
+        json: json.ok_or(GltfError::InvalidGlb("missing JSON chunk"))?,
+        bin,
+    })
+}
+
+// Datos de un uri "data:...;base64,..."; None si el uri no es de ese tipo
+pub(super) fn decode_data_uri(uri: &str, path: &str) -> Option<Result<Vec<u8>, GltfError>> {
+    let rest = uri.strip_prefix("data:")?;
+    let (header, data) = match rest.split_once(',') {
+        Some(parts) => parts,
+        None => return Some(Err(GltfError::invalid(path, "malformed data URI"))),
+    };
+    if !header.ends_with(";base64") {
+        return Some(Err(GltfError::unsupported(path, "data URI without base64 encoding")));
+    }
+
+    Some(decode_base64(data).ok_or_else(|| GltfError::invalid(path, "invalid base64 data")))
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let value = |c: u8| match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    };
+
+    let text = text.trim_end_matches('=').as_bytes();
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let mut bits: u32 = 0;
+    let mut bit_count = 0;
+    for &c in text {
+        bits = bits << 6 | value(c)? as u32;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            bytes.push((bits >> bit_count) as u8);
+        }
+    }
+
+    Some(bytes)
+}
+
+// Los uri relativos pueden llevar caracteres escapados (%20...)
+pub(super) fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// Vista de un accessor sobre los datos de su buffer
+pub(super) struct Accessor<'a>
+{
+    // None si el accessor no tiene bufferView (todos sus valores son 0)
+    pub data: Option<&'a [u8]>,
+    pub stride: usize,
+    pub count: usize,
+    pub component_type: u32,
+    pub components: usize,
+    pub normalized: bool,
+}
+
+pub(super) fn component_size(component_type: u32) -> Option<usize> {
+    match component_type {
+        5120 | 5121 => Some(1),
+        5122 | 5123 => Some(2),
+        5125 | 5126 => Some(4),
+        _ => None,
+    }
+}
+
+impl Accessor<'_> {
+    fn component(&self, element: usize, component: usize) -> f32
+    {
+        let data = match self.data {
+            Some(data) => data,
+            None => return 0.0,
+        };
+
+        let size = component_size(self.component_type).unwrap();
+        let offset = element * self.stride + component * size;
+        let bytes = &data[offset..offset + size];
+        match (self.component_type, self.normalized) {
+            (5126, _) => f32::from_le_bytes(bytes.try_into().unwrap()),
+            (5120, true) => (bytes[0] as i8 as f32 / 127.0).max(-1.0),
+            (5120, false) => bytes[0] as i8 as f32,
+            (5121, true) => bytes[0] as f32 / 255.0,
+            (5121, false) => bytes[0] as f32,
+            (5122, true) => (i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32767.0).max(-1.0),
+            (5122, false) => i16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+            (5123, true) => u16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 65535.0,
+            (5123, false) => u16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+            _ => u32::from_le_bytes(bytes.try_into().unwrap()) as f32,
+        }
+    }
+
+    pub fn read_floats<const N: usize>(&self, path: &str) -> Result<Vec<[f32; N]>, GltfError>
+    {
+        if self.components != N {
+            return Err(GltfError::invalid(path, &format!("expected an accessor with {} components", N)));
+        }
+
+        Ok((0..self.count)
+            .map(|element| {
+                let mut values = [0.0; N];
+                for (component, value) in values.iter_mut().enumerate() {
+                    *value = self.component(element, component);
+                }
+                values
+            })
+            .collect())
+    }
+
+    pub fn read_indices(&self, path: &str) -> Result<Vec<u32>, GltfError>
+    {
+        if self.components != 1 || !matches!(self.component_type, 5121 | 5123 | 5125) {
+            return Err(GltfError::invalid(path, "indices must be unsigned integer scalars"));
+        }
+
+        let data = match self.data {
+            Some(data) => data,
+            None => return Ok(vec![0; self.count]),
+        };
+        let size = component_size(self.component_type).unwrap();
+        Ok((0..self.count)
+            .map(|element| {
+                let bytes = &data[element * self.stride..element * self.stride + size];
+                match size {
+                    1 => bytes[0] as u32,
+                    2 => u16::from_le_bytes([bytes[0], bytes[1]]) as u32,
+                    _ => u32::from_le_bytes(bytes.try_into().unwrap()),
+                }
+            })
+            .collect())
+    }
+}
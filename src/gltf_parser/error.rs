@@ -0,0 +1,74 @@
+use std::{error::Error, fmt, io};
+
+#[derive(Debug)]
+pub enum GltfError
+{
+    Io(io::Error),
+    Json(serde_json::Error),
+    // Cabecera o bloques de un .glb mal formados
+    InvalidGlb(&'static str),
+    UnsupportedVersion(String),
+    // El documento no cumple la especificación; `path` indica dónde, p. ej. "meshes[0].primitives[1]"
+    Invalid { path: String, message: String },
+    Unsupported { path: String, feature: String },
+    // Archivo externo (buffer) referenciado con uri que no se ha podido leer
+    Resource { uri: String, source: io::Error },
+}
+
+impl GltfError {
+    pub(crate) fn invalid(path: &str, message: &str) -> GltfError
+    {
+        GltfError::Invalid {
+            path: path.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    pub(crate) fn unsupported(path: &str, feature: &str) -> GltfError
+    {
+        GltfError::Unsupported {
+            path: path.to_string(),
+            feature: feature.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for GltfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            GltfError::Io(e) => write!(f, "I/O error: {}", e),
+            GltfError::Json(e) => write!(f, "invalid JSON: {}", e),
+            GltfError::InvalidGlb(message) => write!(f, "invalid GLB container: {}", message),
+            GltfError::UnsupportedVersion(version) => write!(f, "unsupported glTF version '{}'", version),
+            GltfError::Invalid { path, message } => write!(f, "{}: {}", path, message),
+            GltfError::Unsupported { path, feature } => write!(f, "{}: {} is not supported", path, feature),
+            GltfError::Resource { uri, source } => write!(f, "cannot read '{}': {}", uri, source),
+        }
+    }
+}
+
+impl Error for GltfError {
+    fn source(&self) -> Option<&(dyn Error + 'static)>
+    {
+        match self {
+            GltfError::Io(e) | GltfError::Resource { source: e, .. } => Some(e),
+            GltfError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for GltfError {
+    fn from(e: io::Error) -> GltfError
+    {
+        GltfError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for GltfError {
+    fn from(e: serde_json::Error) -> GltfError
+    {
+        GltfError::Json(e)
+    }
+}
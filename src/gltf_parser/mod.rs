@@ -0,0 +1,502 @@
+use std::{fs, path::Path};
+
+use cgmath::{Matrix4, Quaternion, Vector3};
+use serde_json::Value;
+
+use crate::{
+    material::{AlphaMode, Material, PbrMaterial},
+    mesh::{Mesh, Vertex},
+    model::{Model, SubMesh},
+    scene::{Node, Scene},
+};
+
+mod buffers;
+pub mod error;
+
+pub use error::GltfError;
+
+use buffers::{component_size, decode_data_uri, is_glb, parse_glb, percent_decode, Accessor};
+
+// Modos de dibujo de una primitiva (GL_TRIANGLES, GL_TRIANGLE_STRIP, GL_TRIANGLE_FAN)
+const TRIANGLES: u64 = 4;
+const TRIANGLE_STRIP: u64 = 5;
+const TRIANGLE_FAN: u64 = 6;
+
+// Lee un .gltf (con buffers externos o incrustados en base64) o un .glb
+pub fn load_gltf(file_path: &str) -> Result<Scene, GltfError> {
+    let bytes = fs::read(file_path)?;
+    // Los buffers e imágenes externos son relativos al archivo
+    load_gltf_from_slice(&bytes, Some(Path::new(file_path).parent().unwrap_or(Path::new(""))))
+}
+
+// Sin `base_dir` solo se pueden leer documentos cuyos buffers estén incrustados
+pub fn load_gltf_from_slice(bytes: &[u8], base_dir: Option<&Path>) -> Result<Scene, GltfError> {
+    let (json, bin) = if is_glb(bytes) {
+        let glb = parse_glb(bytes)?;
+        (glb.json, glb.bin)
+    } else {
+        (bytes, None)
+    };
+
+    let root: Value = serde_json::from_slice(json)?;
+    let document = Document::new(&root, bin, base_dir)?;
+    document.scene()
+}
+
+struct Document<'a>
+{
+    root: &'a Value,
+    buffers: Vec<Vec<u8>>,
+    base_dir: Option<&'a Path>,
+}
+
+impl<'a> Document<'a> {
+    fn new(root: &'a Value, bin: Option<&[u8]>, base_dir: Option<&'a Path>) -> Result<Document<'a>, GltfError>
+    {
+        let version = root
+            .get("asset")
+            .and_then(|asset| asset.get("version"))
+            .and_then(Value::as_str)
+            .ok_or_else(|| GltfError::invalid("asset", "missing version"))?;
+        if !version.starts_with("2.") {
+            return Err(GltfError::UnsupportedVersion(version.to_string()));
+        }
+
+        // Ninguna extensión está implementada; las opcionales se ignoran sin más
+        if let Some(extension) = array(root, "extensionsRequired").first() {
+            return Err(GltfError::unsupported("extensionsRequired", extension.as_str().unwrap_or("extension")));
+        }
+
+        let mut document = Document {
+            root,
+            buffers: Vec::new(),
+            base_dir,
+        };
+
+        for (index, buffer) in array(root, "buffers").iter().enumerate() {
+            let path = format!("buffers[{}]", index);
+            let data = match buffer.get("uri").and_then(Value::as_str) {
+                Some(uri) => document.read_uri(uri, &path)?,
+                // Solo el primer buffer de un .glb puede no tener uri
+                None if index == 0 && bin.is_some() => bin.unwrap().to_vec(),
+                None => return Err(GltfError::invalid(&path, "buffer has no data")),
+            };
+
+            let length = usize_field(buffer, "byteLength", &path)?.unwrap_or(0);
+            if data.len() < length {
+                return Err(GltfError::invalid(&path, "buffer is shorter than its byteLength"));
+            }
+            document.buffers.push(data);
+        }
+
+        Ok(document)
+    }
+
+    fn read_uri(&self, uri: &str, path: &str) -> Result<Vec<u8>, GltfError>
+    {
+        if let Some(data) = decode_data_uri(uri, path) {
+            return data;
+        }
+
+        let resource_error = |source| GltfError::Resource {
+            uri: uri.to_string(),
+            source,
+        };
+        let base_dir = self.base_dir.ok_or_else(|| {
+            resource_error(std::io::Error::new(std::io::ErrorKind::NotFound, "no base directory for external files"))
+        })?;
+        fs::read(base_dir.join(percent_decode(uri))).map_err(resource_error)
+    }
+
+    fn scene(&self) -> Result<Scene, GltfError>
+    {
+        let materials = array(self.root, "materials")
+            .iter()
+            .enumerate()
+            .map(|(index, material)| self.material(material, index))
+            .collect::<Result<Vec<Material>, GltfError>>()?;
+
+        let meshes = array(self.root, "meshes")
+            .iter()
+            .enumerate()
+            .map(|(index, mesh)| self.model(mesh, index, &materials))
+            .collect::<Result<Vec<Model>, GltfError>>()?;
+
+        let node_count = array(self.root, "nodes").len();
+        let mut nodes: Vec<Node> = Vec::with_capacity(node_count);
+        for (index, node) in array(self.root, "nodes").iter().enumerate() {
+            nodes.push(self.node(node, index, node_count, meshes.len())?);
+        }
+
+        // La escena por defecto, o la primera si el documento no indica ninguna
+        let scene = usize_field(self.root, "scene", "scene")?.or(Some(0).filter(|_| !array(self.root, "scenes").is_empty()));
+        let roots = match scene {
+            Some(scene) => {
+                let path = format!("scenes[{}]", scene);
+                let scene = element(self.root, "scenes", scene, "scene")?;
+                indices(scene, "nodes", node_count, &path)?
+            }
+            // Sin escenas se dibujan todos los nodos que no son hijos de otro
+            None => (0..node_count).filter(|&index| !nodes.iter().any(|node| node.children.contains(&index))).collect(),
+        };
+
+        Ok(Scene { meshes, nodes, roots })
+    }
+
+    fn node(&self, value: &Value, index: usize, node_count: usize, mesh_count: usize) -> Result<Node, GltfError>
+    {
+        let path = format!("nodes[{}]", index);
+        let mut node = Node {
+            name: value.get("name").and_then(Value::as_str).map(str::to_string),
+            children: indices(value, "children", node_count, &path)?,
+            ..Node::default()
+        };
+
+        node.mesh = usize_field(value, "mesh", &path)?;
+        if node.mesh.is_some_and(|mesh| mesh >= mesh_count) {
+            return Err(GltfError::invalid(&path, "mesh index out of range"));
+        }
+
+        if value.get("matrix").is_some() {
+            let m: [f32; 16] = floats(value, "matrix", [0.0; 16], &path)?;
+            // glTF guarda las matrices por columnas, igual que Matrix4::new
+            node.set_transform(Matrix4::new(
+                m[0], m[1], m[2], m[3], m[4], m[5], m[6], m[7], m[8], m[9], m[10], m[11], m[12], m[13], m[14], m[15],
+            ));
+        } else {
+            node.translation = Vector3::from(floats(value, "translation", [0.0; 3], &path)?);
+            let [x, y, z, w] = floats(value, "rotation", [0.0, 0.0, 0.0, 1.0], &path)?;
+            node.rotation = Quaternion::new(w, x, y, z);
+            node.scale = Vector3::from(floats(value, "scale", [1.0; 3], &path)?);
+        }
+
+        Ok(node)
+    }
+
+    fn material(&self, value: &Value, index: usize) -> Result<Material, GltfError>
+    {
+        let path = format!("materials[{}]", index);
+        let name = value.get("name").and_then(Value::as_str).map_or_else(|| format!("material{}", index), str::to_string);
+
+        let mut pbr = PbrMaterial::default();
+        if let Some(metallic_roughness) = value.get("pbrMetallicRoughness") {
+            let path = format!("{}.pbrMetallicRoughness", path);
+            pbr.base_color = floats(metallic_roughness, "baseColorFactor", [1.0; 4], &path)?;
+            pbr.metallic = number(metallic_roughness, "metallicFactor", 1.0, &path)?;
+            pbr.roughness = number(metallic_roughness, "roughnessFactor", 1.0, &path)?;
+            pbr.base_color_map = self.texture(metallic_roughness, "baseColorTexture", &path)?;
+            pbr.metallic_roughness_map = self.texture(metallic_roughness, "metallicRoughnessTexture", &path)?;
+        }
+        pbr.emissive = floats(value, "emissiveFactor", [0.0; 3], &path)?;
+        pbr.normal_map = self.texture(value, "normalTexture", &path)?;
+        pbr.occlusion_map = self.texture(value, "occlusionTexture", &path)?;
+        pbr.emissive_map = self.texture(value, "emissiveTexture", &path)?;
+        pbr.double_sided = value.get("doubleSided").and_then(Value::as_bool).unwrap_or(false);
+        pbr.alpha_mode = match value.get("alphaMode").and_then(Value::as_str).unwrap_or("OPAQUE") {
+            "OPAQUE" => AlphaMode::Opaque,
+            "MASK" => AlphaMode::Mask(number(value, "alphaCutoff", 0.5, &path)?),
+            "BLEND" => AlphaMode::Blend,
+            _ => return Err(GltfError::invalid(&path, "unknown alphaMode")),
+        };
+
+        // Los campos clásicos se rellenan para poder dibujarlo con los mismos shaders que un .mtl
+        let mut material = Material::new(&name);
+        material.diffuse = [pbr.base_color[0], pbr.base_color[1], pbr.base_color[2]];
+        material.dissolve = pbr.base_color[3];
+        material.diffuse_map = pbr.base_color_map.clone();
+        material.bump_map = pbr.normal_map.clone();
+        material.pbr = Some(pbr);
+        Ok(material)
+    }
+
+    // Ruta de la imagen de una textura; None si no la hay o si la imagen va incrustada
+    fn texture(&self, value: &Value, key: &str, path: &str) -> Result<Option<String>, GltfError>
+    {
+        let path = format!("{}.{}", path, key);
+        let texture = match value.get(key) {
+            Some(info) => match usize_field(info, "index", &path)? {
+                Some(texture) => element(self.root, "textures", texture, &path)?,
+                None => return Err(GltfError::invalid(&path, "missing texture index")),
+            },
+            None => return Ok(None),
+        };
+
+        let image = match usize_field(texture, "source", &path)? {
+            Some(image) => element(self.root, "images", image, &path)?,
+            None => return Ok(None),
+        };
+
+        Ok(image
+            .get("uri")
+            .and_then(Value::as_str)
+            .filter(|uri| !uri.starts_with("data:"))
+            .map(|uri| {
+                let uri = percent_decode(uri);
+                match self.base_dir {
+                    Some(base_dir) => base_dir.join(uri).to_string_lossy().into_owned(),
+                    None => uri,
+                }
+            }))
+    }
+
+    // Cada primitiva de la malla se convierte en una submalla del modelo
+    fn model(&self, value: &Value, index: usize, materials: &[Material]) -> Result<Model, GltfError>
+    {
+        let path = format!("meshes[{}]", index);
+        let name = value.get("name").and_then(Value::as_str).map_or_else(|| format!("mesh{}", index), str::to_string);
+
+        let mut model = Model {
+            mesh: Mesh::default(),
+            materials: Vec::new(),
+            submeshes: Vec::new(),
+        };
+        let mut tangents: Option<Vec<[f32; 4]>> = Some(Vec::new());
+        let mut colors: Vec<[f32; 4]> = Vec::new();
+        let mut has_colors = false;
+        // Índice de cada material del documento dentro de model.materials
+        let mut used_materials: Vec<Option<usize>> = vec![None; materials.len()];
+
+        for (primitive_index, primitive) in array(value, "primitives").iter().enumerate() {
+            let path = format!("{}.primitives[{}]", path, primitive_index);
+            let mesh = self.primitive(primitive, &path)?;
+
+            let material = match usize_field(primitive, "material", &path)? {
+                Some(material) if material >= materials.len() => {
+                    return Err(GltfError::invalid(&path, "material index out of range"))
+                }
+                Some(material) => Some(*used_materials[material].get_or_insert_with(|| {
+                    model.materials.push(materials[material].clone());
+                    model.materials.len() - 1
+                })),
+                None => None,
+            };
+
+            model.submeshes.push(SubMesh {
+                name: name.clone(),
+                object: None,
+                material,
+                first_triangle: model.mesh.triangle_count(),
+                triangle_count: mesh.triangle_count(),
+            });
+
+            // Los tangentes solo se conservan si todas las primitivas los traen
+            match (tangents.as_mut(), mesh.tangents) {
+                (Some(tangents), Some(primitive_tangents)) => tangents.extend(primitive_tangents),
+                _ => tangents = None,
+            }
+            // Sin COLOR_0 el color es blanco, que no altera el del material
+            has_colors |= mesh.colors.is_some();
+            colors.extend(mesh.colors.unwrap_or_else(|| vec![[1.0; 4]; mesh.vertices.len()]));
+
+            let offset = model.mesh.vertices.len() as u32;
+            model.mesh.vertices.extend(mesh.vertices);
+            model.mesh.indices.extend(mesh.indices.iter().map(|index| index + offset));
+        }
+
+        model.mesh.tangents = tangents.filter(|tangents| !tangents.is_empty());
+        model.mesh.colors = Some(colors).filter(|_| has_colors);
+        Ok(model)
+    }
+
+    fn primitive(&self, value: &Value, path: &str) -> Result<Mesh, GltfError>
+    {
+        let attributes = value
+            .get("attributes")
+            .filter(|attributes| attributes.is_object())
+            .ok_or_else(|| GltfError::invalid(path, "missing attributes"))?;
+        let attribute = |name: &str| -> Result<Option<Accessor>, GltfError> {
+            let path = format!("{}.attributes.{}", path, name);
+            usize_field(attributes, name, &path)?.map(|accessor| self.accessor(accessor, &path)).transpose()
+        };
+
+        let positions: Vec<[f32; 3]> = match attribute("POSITION")? {
+            Some(accessor) => accessor.read_floats(path)?,
+            None => return Err(GltfError::invalid(path, "missing POSITION attribute")),
+        };
+        let normals: Option<Vec<[f32; 3]>> = attribute("NORMAL")?.map(|accessor| accessor.read_floats(path)).transpose()?;
+        let uvs: Option<Vec<[f32; 2]>> = attribute("TEXCOORD_0")?.map(|accessor| accessor.read_floats(path)).transpose()?;
+        let tangents: Option<Vec<[f32; 4]>> = attribute("TANGENT")?.map(|accessor| accessor.read_floats(path)).transpose()?;
+        let colors: Option<Vec<[f32; 4]>> = match attribute("COLOR_0")? {
+            Some(accessor) if accessor.components == 3 => {
+                Some(accessor.read_floats(path)?.into_iter().map(|[r, g, b]| [r, g, b, 1.0]).collect())
+            }
+            Some(accessor) => Some(accessor.read_floats(path)?),
+            None => None,
+        };
+
+        let count = positions.len();
+        let lengths = [
+            normals.as_ref().map(Vec::len),
+            uvs.as_ref().map(Vec::len),
+            tangents.as_ref().map(Vec::len),
+            colors.as_ref().map(Vec::len),
+        ];
+        if lengths.iter().flatten().any(|&length| length != count) {
+            return Err(GltfError::invalid(path, "attributes have different element counts"));
+        }
+
+        let order: Vec<u32> = match usize_field(value, "indices", path)? {
+            Some(accessor) => self.accessor(accessor, path)?.read_indices(path)?,
+            None => (0..count as u32).collect(),
+        };
+        if order.iter().any(|&index| index as usize >= count) {
+            return Err(GltfError::invalid(path, "vertex index out of range"));
+        }
+
+        let indices: Vec<u32> = match value.get("mode").and_then(Value::as_u64).unwrap_or(TRIANGLES) {
+            TRIANGLES => order[..order.len() / 3 * 3].to_vec(),
+            // Se alterna el orden de los triángulos pares para mantener el sentido de giro
+            TRIANGLE_STRIP => (0..order.len().saturating_sub(2))
+                .flat_map(|i| match i % 2 {
+                    0 => [order[i], order[i + 1], order[i + 2]],
+                    _ => [order[i + 1], order[i], order[i + 2]],
+                })
+                .collect(),
+            TRIANGLE_FAN => (1..order.len().saturating_sub(1)).flat_map(|i| [order[i], order[i + 1], order[0]]).collect(),
+            _ => return Err(GltfError::unsupported(path, "drawing points or lines")),
+        };
+
+        let vertices = (0..count)
+            .map(|i| {
+                let normal = normals.as_ref().map_or([0.0; 3], |normals| normals[i]);
+                // glTF pone el origen de las coordenadas de textura arriba; el resto del crate abajo, como OBJ
+                let [u, v] = uvs.as_ref().map_or([0.0; 2], |uvs| uvs[i]);
+                Vertex::new(positions[i], normal, [u, 1.0 - v])
+            })
+            .collect();
+
+        let mut mesh = Mesh::new(vertices, indices);
+        mesh.colors = colors;
+        match normals {
+            // Al invertir v la bitangente cambia de sentido
+            Some(_) => mesh.tangents = tangents.map(|tangents| tangents.into_iter().map(|[x, y, z, w]| [x, y, z, -w]).collect()),
+            // La especificación pide normales planas cuando no vienen en el archivo
+            None => mesh.compute_flat_normals(),
+        }
+        Ok(mesh)
+    }
+
+    fn accessor(&self, index: usize, path: &str) -> Result<Accessor<'_>, GltfError>
+    {
+        let value = element(self.root, "accessors", index, path)?;
+        let path = format!("accessors[{}]", index);
+        if value.get("sparse").is_some() {
+            return Err(GltfError::unsupported(&path, "sparse accessors"));
+        }
+
+        let count = usize_field(value, "count", &path)?.ok_or_else(|| GltfError::invalid(&path, "missing count"))?;
+        let component_type = value.get("componentType").and_then(Value::as_u64).unwrap_or(0) as u32;
+        let size = component_size(component_type).ok_or_else(|| GltfError::invalid(&path, "invalid componentType"))?;
+        let components = match value.get("type").and_then(Value::as_str) {
+            Some("SCALAR") => 1,
+            Some("VEC2") => 2,
+            Some("VEC3") => 3,
+            Some("VEC4") => 4,
+            Some("MAT2") => 4,
+            Some("MAT3") => 9,
+            Some("MAT4") => 16,
+            _ => return Err(GltfError::invalid(&path, "invalid type")),
+        };
+
+        // Con un count enorme las cuentas de bytes no deben desbordarse
+        let overflow = || GltfError::invalid(&path, "accessor size overflows");
+        count.checked_mul(size * components).ok_or_else(overflow)?;
+
+        let mut accessor = Accessor {
+            data: None,
+            stride: size * components,
+            count,
+            component_type,
+            components,
+            normalized: value.get("normalized").and_then(Value::as_bool).unwrap_or(false),
+        };
+
+        if let Some(view_index) = usize_field(value, "bufferView", &path)? {
+            let view = element(self.root, "bufferViews", view_index, &path)?;
+            let view_path = format!("bufferViews[{}]", view_index);
+            let buffer = usize_field(view, "buffer", &view_path)?
+                .and_then(|buffer| self.buffers.get(buffer))
+                .ok_or_else(|| GltfError::invalid(&view_path, "buffer index out of range"))?;
+            let view_offset = usize_field(view, "byteOffset", &view_path)?.unwrap_or(0);
+            let view_length = usize_field(view, "byteLength", &view_path)?.unwrap_or(0);
+            let data = buffer
+                .get(view_offset..view_offset.saturating_add(view_length))
+                .ok_or_else(|| GltfError::invalid(&view_path, "view extends past the end of its buffer"))?;
+
+            let offset = usize_field(value, "byteOffset", &path)?.unwrap_or(0);
+            accessor.stride = usize_field(view, "byteStride", &view_path)?.unwrap_or(accessor.stride);
+            let needed = match count {
+                0 => 0,
+                _ => accessor
+                    .stride
+                    .checked_mul(count - 1)
+                    .and_then(|bytes| bytes.checked_add(offset))
+                    .and_then(|bytes| bytes.checked_add(size * components))
+                    .ok_or_else(overflow)?,
+            };
+            if needed > data.len() {
+                return Err(GltfError::invalid(&path, "accessor extends past the end of its buffer view"));
+            }
+            accessor.data = Some(&data[offset.min(data.len())..]);
+        }
+
+        Ok(accessor)
+    }
+}
+
+fn array<'v>(value: &'v Value, key: &str) -> &'v [Value] {
+    value.get(key).and_then(Value::as_array).map_or(&[], Vec::as_slice)
+}
+
+// Elemento `index` de una de las listas de primer nivel (accessors, textures...)
+fn element<'v>(root: &'v Value, collection: &str, index: usize, path: &str) -> Result<&'v Value, GltfError> {
+    array(root, collection)
+        .get(index)
+        .ok_or_else(|| GltfError::invalid(path, &format!("{} index {} out of range", collection, index)))
+}
+
+fn usize_field(value: &Value, key: &str, path: &str) -> Result<Option<usize>, GltfError> {
+    match value.get(key) {
+        Some(field) => match field.as_u64() {
+            Some(field) => Ok(Some(field as usize)),
+            None => Err(GltfError::invalid(path, &format!("'{}' must be a non-negative integer", key))),
+        },
+        None => Ok(None),
+    }
+}
+
+fn indices(value: &Value, key: &str, count: usize, path: &str) -> Result<Vec<usize>, GltfError> {
+    array(value, key)
+        .iter()
+        .map(|index| match index.as_u64() {
+            Some(index) if (index as usize) < count => Ok(index as usize),
+            _ => Err(GltfError::invalid(path, &format!("invalid index in '{}'", key))),
+        })
+        .collect()
+}
+
+fn number(value: &Value, key: &str, default: f32, path: &str) -> Result<f32, GltfError> {
+    match value.get(key) {
+        Some(field) => field
+            .as_f64()
+            .map(|field| field as f32)
+            .ok_or_else(|| GltfError::invalid(path, &format!("'{}' must be a number", key))),
+        None => Ok(default),
+    }
+}
+
+fn floats<const N: usize>(value: &Value, key: &str, default: [f32; N], path: &str) -> Result<[f32; N], GltfError> {
+    let field = match value.get(key) {
+        Some(field) => field,
+        None => return Ok(default),
+    };
+
+    let invalid = || GltfError::invalid(path, &format!("'{}' must be an array of {} numbers", key, N));
+    let values = field.as_array().filter(|values| values.len() == N).ok_or_else(invalid)?;
+    let mut result = [0.0; N];
+    for (result, value) in result.iter_mut().zip(values) {
+        *result = value.as_f64().ok_or_else(invalid)? as f32;
+    }
+    Ok(result)
+}
+
@@ -1,4 +1,13 @@
 pub mod text;
 pub mod fps_counter;
 pub mod object;
-pub mod obj_parser;
\ No newline at end of file
+pub mod obj_parser;
+pub mod material;
+pub mod model;
+pub mod mesh;
+pub mod mesh_cache;
+pub mod scene;
+pub mod gltf_parser;
+pub mod stl_parser;
+pub mod ply_parser;
+pub mod vertex_layout;
@@ -3,10 +3,12 @@ extern crate gl;
 
 use std::{ffi::CString, fs::{self}, io::{self}, time::Instant};
 
-use cgmath::{perspective, Array, Deg, Matrix, Matrix4, Point3, Rad, Vector3};
-use graphics_playground::{fps_counter::FpsCounter, obj_parser::parse_obj};
-use sdl2::{event::Event, keyboard::Keycode, video::GLProfile};
+use cgmath::{perspective, Array, Deg, InnerSpace, Matrix, Matrix4, Point3, Rad, SquareMatrix, Vector3, Vector4};
+use graphics_playground::{fps_counter::FpsCounter, mesh::{bvh::Ray, validate::RepairOptions}, mesh_cache::MeshCache, obj_parser::ObjOptions};
+use sdl2::{event::{Event, WindowEvent}, keyboard::Keycode, video::GLProfile};
 
+// Campo de visión vertical de la cámara, en grados
+const FIELD_OF_VIEW: f32 = 45.0;
 
 fn main()
 {
@@ -20,7 +22,7 @@ fn main()
     gl_attr.set_context_version(3, 3); // OpenGL 3.3
     gl_attr.set_depth_size(24);
 
-    let window = video_subsystem
+    let mut window = video_subsystem
         .window("Ventana OpenGL", 800, 600)
         .opengl()
         .resizable()
@@ -30,29 +32,64 @@ fn main()
     let _gl_context = window.gl_create_context().unwrap();
     gl::load_with(|s| video_subsystem.gl_get_proc_address(s) as *const _);
 
-    // Vértices de un cubo centrado en (0.0, 0.0, 0.0)
-    let vertices = parse_obj("flower.obj").unwrap();
+    // La caché evita volver a parsear el .obj mientras no cambie
+    let mesh_cache = MeshCache::new(".mesh_cache");
+    let mut mesh = mesh_cache.load_obj("flower.obj", &ObjOptions::default()).unwrap().mesh;
 
+    // Un modelo roto (índices fuera de rango, triángulos degenerados...) se arregla antes de
+    // mandarlo a la GPU
+    let report = mesh.validate();
+    if !report.is_valid() {
+        println!("flower.obj: {}", report);
+        mesh.repair(&RepairOptions::default());
+    }
+    // Orden de triángulos y vértices pensado para la caché de la GPU
+    mesh.optimize();
+
+    // El modelo se centra en el origen para que gire sobre sí mismo, y la cámara se aleja lo
+    // justo para que la esfera que lo envuelve (también al girar) quepa en el campo de visión
+    mesh.recenter();
+    let radius = mesh
+        .bounding_sphere()
+        .map_or(1.0, |sphere| sphere.radius + Vector3::from(sphere.center).magnitude());
+    let camera_distance = radius / (FIELD_OF_VIEW.to_radians() * 0.5).sin();
+    // Cada pulsación mueve la cámara en proporción al tamaño del modelo
+    let step = radius * 0.25;
+
+    // Posiciones, normales y UVs (y tangentes y colores si los hay) intercalados
+    let layout = mesh.layout();
+    let vertex_data = mesh.interleaved();
+    // Para elegir triángulos con el ratón
+    let bvh = mesh.bvh();
 
     let mut vbo = 0;
+    let mut ebo = 0;
     let mut vao = 0;
 
     unsafe {
         gl::GenVertexArrays(1, &mut vao);
         gl::GenBuffers(1, &mut vbo);
+        gl::GenBuffers(1, &mut ebo);
 
         gl::BindVertexArray(vao);
 
         gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
         gl::BufferData(
             gl::ARRAY_BUFFER,
-            (vertices.len() * std::mem::size_of::<f32>()) as gl::types::GLsizeiptr,
-            vertices.as_ptr() as *const _,
+            (vertex_data.len() * std::mem::size_of::<f32>()) as gl::types::GLsizeiptr,
+            vertex_data.as_ptr() as *const _,
+            gl::STATIC_DRAW,
+        );
+
+        gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
+        gl::BufferData(
+            gl::ELEMENT_ARRAY_BUFFER,
+            (mesh.indices.len() * std::mem::size_of::<u32>()) as gl::types::GLsizeiptr,
+            mesh.indices.as_ptr() as *const _,
             gl::STATIC_DRAW,
         );
 
-        gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 8 * std::mem::size_of::<f32>() as i32, std::ptr::null());
-        gl::EnableVertexAttribArray(0);
+        layout.configure();
 
         gl::Enable(gl::CULL_FACE); // Habilita el culling
         gl::CullFace(gl::BACK);    // Descarta las caras traseras
@@ -68,11 +105,15 @@ fn main()
         
 
     let shader_program = create_shader_program(vertex_shader_src.as_str(), fragment_shader_src.as_str());
+    if let Err(error) = layout.validate_program(shader_program) {
+        panic!("Vertex layout does not match the shader: {}", error);
+    }
     let start = Instant::now();
 
     let mut fps_counter = FpsCounter::new(60);
 
-    let (mut pos_x, mut pos_y, mut pos_z): (f32, f32, f32) = (0.0, 0.0, -5.0);
+    let (mut pos_x, mut pos_y, mut pos_z): (f32, f32, f32) = (0.0, 0.0, -camera_distance);
+    let mut pick: Option<(i32, i32)> = None;
 
     // Bucle principal
     let mut event_pump = sdl_context.event_pump().unwrap();
@@ -81,17 +122,26 @@ fn main()
         for event in event_pump.poll_iter() {
             match event {
                 Event::Quit { .. } => break 'running,
+                // El ratón da coordenadas de ventana, que es como las espera el rayo; GL dibuja en
+                // píxeles, que con HiDPI son más
+                Event::MouseButtonDown { x, y, .. } => pick = Some((x, y)),
+                Event::Window { win_event: WindowEvent::SizeChanged(..), .. } => {
+                    let (width, height) = window.drawable_size();
+                    unsafe {
+                        gl::Viewport(0, 0, width as i32, height as i32);
+                    }
+                },
                 Event::KeyDown {keycode, .. } => {
                     match keycode {
                         Some(keycode) => {
                             match keycode {
                                 Keycode::Escape => break 'running,
-                                Keycode::W => pos_z += 1.0,
-                                Keycode::S => pos_z -= 1.0,
-                                Keycode::A => pos_x += 1.0,
-                                Keycode::D => pos_x -= 1.0,
-                                Keycode::U => pos_y += 1.0,
-                                Keycode::J => pos_y -= 1.0,
+                                Keycode::W => pos_z += step,
+                                Keycode::S => pos_z -= step,
+                                Keycode::A => pos_x += step,
+                                Keycode::D => pos_x -= step,
+                                Keycode::U => pos_y += step,
+                                Keycode::J => pos_y -= step,
                                 _ => ()
                             }
                         },
@@ -107,7 +157,18 @@ fn main()
         let scale = create_scale_matrix(1.0);
         let model = scale * position * rotation;
         let view = create_view_matrix(Point3::new(pos_x, pos_y, pos_z), Vector3::new(0.0, 1.0, 0.0));
-        let projection = create_projection_matrix(800.0/600.0);
+        let (width, height) = window.size();
+        let projection = create_projection_matrix(width as f32 / height.max(1) as f32);
+
+        // El triángulo elegido se muestra en el título de la ventana
+        if let Some((x, y)) = pick.take() {
+            let ray = create_picking_ray(x, y, (width, height), &(projection * view * model));
+            let title = match bvh.intersect(&ray, f32::INFINITY) {
+                Some(hit) => format!("Ventana OpenGL - triángulo {} a {:.3}", hit.triangle, hit.distance),
+                None => "Ventana OpenGL".to_string(),
+            };
+            window.set_title(&title).unwrap();
+        }
 
         unsafe {
             gl::ClearColor(0.1, 0.2, 0.3, 1.0);
@@ -120,7 +181,7 @@ fn main()
             set_uniform_vec(shader_program, "cameraPos", &Vector3::new(pos_x, pos_y, pos_z));
 
             gl::BindVertexArray(vao);
-            gl::DrawArrays(gl::TRIANGLES, 0, (vertices.len()/3) as i32);
+            gl::DrawElements(gl::TRIANGLES, mesh.indices.len() as i32, gl::UNSIGNED_INT, std::ptr::null());
         }
 
         window.gl_swap_window();
@@ -240,7 +301,20 @@ fn create_view_matrix(position: Point3<f32>, rotation: Vector3<f32>) -> Matrix4<
 }
 
 fn create_projection_matrix(aspect_ratio: f32) -> Matrix4<f32> {
-    perspective(Deg(45.0), aspect_ratio, 0.01, 10000.0)
+    perspective(Deg(FIELD_OF_VIEW), aspect_ratio, 0.01, 10000.0)
+}
+
+// Rayo en el espacio del modelo que pasa por el píxel, desde el plano cercano hasta el lejano
+fn create_picking_ray(x: i32, y: i32, (width, height): (u32, u32), model_view_projection: &Matrix4<f32>) -> Ray {
+    let inverse = model_view_projection.invert().unwrap();
+    let ndc_x = 2.0 * x as f32 / width as f32 - 1.0;
+    let ndc_y = 1.0 - 2.0 * y as f32 / height as f32;
+    let unproject = |depth: f32| {
+        let point = inverse * Vector4::new(ndc_x, ndc_y, depth, 1.0);
+        point.truncate() / point.w
+    };
+    let (near, far) = (unproject(-1.0), unproject(1.0));
+    Ray::new(near.into(), (far - near).into())
 }
 
 fn read_file_to_string(path: &str) -> Result<String, io::Error> {
@@ -0,0 +1,96 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct Material
+{
+    pub name: String,
+
+    // Ka, Kd, Ks
+    pub ambient: [f32; 3],
+    pub diffuse: [f32; 3],
+    pub specular: [f32; 3],
+    // Ns
+    pub shininess: f32,
+    // d (o 1 - Tr)
+    pub dissolve: f32,
+    pub illumination: u32,
+
+    // Rutas de texturas (map_Ka, map_Kd, map_Ks, map_Ns, map_d, map_Bump)
+    pub ambient_map: Option<String>,
+    pub diffuse_map: Option<String>,
+    pub specular_map: Option<String>,
+    pub shininess_map: Option<String>,
+    pub dissolve_map: Option<String>,
+    pub bump_map: Option<String>,
+
+    // Parámetros metallic-roughness de los modelos glTF; None en los que vienen de un .mtl
+    pub pbr: Option<PbrMaterial>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlphaMode
+{
+    Opaque,
+    // Los fragmentos con alfa menor que el corte se descartan
+    Mask(f32),
+    Blend,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PbrMaterial
+{
+    // RGBA lineal
+    pub base_color: [f32; 4],
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emissive: [f32; 3],
+    pub alpha_mode: AlphaMode,
+    pub double_sided: bool,
+
+    pub base_color_map: Option<String>,
+    // Azul = metallic, verde = roughness
+    pub metallic_roughness_map: Option<String>,
+    pub normal_map: Option<String>,
+    pub occlusion_map: Option<String>,
+    pub emissive_map: Option<String>,
+}
+
+impl Default for PbrMaterial {
+    // Valores por defecto de la especificación glTF 2.0
+    fn default() -> PbrMaterial
+    {
+        PbrMaterial {
+            base_color: [1.0; 4],
+            metallic: 1.0,
+            roughness: 1.0,
+            emissive: [0.0; 3],
+            alpha_mode: AlphaMode::Opaque,
+            double_sided: false,
+            base_color_map: None,
+            metallic_roughness_map: None,
+            normal_map: None,
+            occlusion_map: None,
+            emissive_map: None,
+        }
+    }
+}
+
+impl Material {
+    pub fn new(name: &str) -> Material
+    {
+        Material {
+            name: name.to_string(),
+            ambient: [0.0; 3],
+            diffuse: [0.8; 3],
+            specular: [0.0; 3],
+            shininess: 0.0,
+            dissolve: 1.0,
+            illumination: 2,
+            ambient_map: None,
+            diffuse_map: None,
+            specular_map: None,
+            shininess_map: None,
+            dissolve_map: None,
+            bump_map: None,
+            pbr: None,
+        }
+    }
+}
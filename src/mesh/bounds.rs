@@ -0,0 +1,177 @@
+use cgmath::{InnerSpace, Matrix4, Vector3};
+
+use super::Mesh;
+
+// Caja alineada con los ejes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb
+{
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+impl Aabb {
+    // None si no hay ningún punto
+    pub fn from_points<I: IntoIterator<Item = [f32; 3]>>(points: I) -> Option<Aabb>
+    {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut aabb = Aabb { min: first, max: first };
+        for point in points {
+            aabb.extend(point);
+        }
+        Some(aabb)
+    }
+
+    pub fn extend(&mut self, point: [f32; 3])
+    {
+        self.min = [0, 1, 2].map(|i| self.min[i].min(point[i]));
+        self.max = [0, 1, 2].map(|i| self.max[i].max(point[i]));
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb
+    {
+        let mut aabb = *self;
+        aabb.extend(other.min);
+        aabb.extend(other.max);
+        aabb
+    }
+
+    pub fn center(&self) -> [f32; 3]
+    {
+        [0, 1, 2].map(|i| (self.min[i] + self.max[i]) * 0.5)
+    }
+
+    pub fn size(&self) -> [f32; 3]
+    {
+        [0, 1, 2].map(|i| self.max[i] - self.min[i])
+    }
+
+    // Lado más largo de la caja
+    pub fn max_extent(&self) -> f32
+    {
+        let [x, y, z] = self.size();
+        x.max(y).max(z)
+    }
+
+    pub fn diagonal(&self) -> f32
+    {
+        Vector3::from(self.size()).magnitude()
+    }
+
+    pub fn surface_area(&self) -> f32
+    {
+        let [x, y, z] = self.size();
+        2.0 * (x * y + y * z + z * x)
+    }
+
+    pub fn contains(&self, point: [f32; 3]) -> bool
+    {
+        (0..3).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere
+{
+    pub center: [f32; 3],
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    // Algoritmo de Ritter: una esfera inicial a partir de dos puntos alejados que se amplía
+    // con los que quedan fuera. No es la mínima, pero suele estar a menos de un 10%; si la
+    // esfera que envuelve la caja es menor, se usa esa.
+    pub fn from_points(points: &[[f32; 3]]) -> Option<BoundingSphere>
+    {
+        let aabb = Aabb::from_points(points.iter().copied())?;
+        let farthest_from = |from: Vector3<f32>| {
+            points
+                .iter()
+                .map(|&point| Vector3::from(point))
+                .max_by(|a, b| (a - from).magnitude2().total_cmp(&(b - from).magnitude2()))
+                .unwrap()
+        };
+
+        let x = Vector3::from(points[0]);
+        let y = farthest_from(x);
+        let z = farthest_from(y);
+        let mut center = (y + z) * 0.5;
+        let mut radius = (z - y).magnitude() * 0.5;
+
+        for &point in points {
+            let point = Vector3::from(point);
+            let distance = (point - center).magnitude();
+            if distance > radius {
+                // La nueva esfera toca el punto y el lado opuesto de la anterior
+                let new_radius = (radius + distance) * 0.5;
+                center += (point - center) * ((new_radius - radius) / distance);
+                radius = new_radius;
+            }
+        }
+
+        let box_sphere = BoundingSphere {
+            center: aabb.center(),
+            radius: aabb.diagonal() * 0.5,
+        };
+        let ritter = BoundingSphere {
+            center: center.into(),
+            // Margen para que los errores de redondeo no dejen puntos fuera
+            radius: radius * (1.0 + f32::EPSILON * 4.0),
+        };
+        Some(if box_sphere.radius < ritter.radius { box_sphere } else { ritter })
+    }
+
+    pub fn contains(&self, point: [f32; 3]) -> bool
+    {
+        (Vector3::from(point) - Vector3::from(self.center)).magnitude() <= self.radius
+    }
+}
+
+impl Mesh {
+    // None si la malla no tiene vértices
+    pub fn bounds(&self) -> Option<Aabb>
+    {
+        Aabb::from_points(self.vertices.iter().map(|vertex| vertex.position))
+    }
+
+    pub fn bounding_sphere(&self) -> Option<BoundingSphere>
+    {
+        let points: Vec<[f32; 3]> = self.vertices.iter().map(|vertex| vertex.position).collect();
+        BoundingSphere::from_points(&points)
+    }
+
+    // Desplaza la malla para que el centro de su caja quede en el origen. Devuelve la
+    // traslación aplicada (cero si la malla está vacía).
+    pub fn recenter(&mut self) -> [f32; 3]
+    {
+        let center = match self.bounds() {
+            Some(bounds) => bounds.center(),
+            None => return [0.0; 3],
+        };
+
+        let offset = center.map(|value| -value);
+        for vertex in self.vertices.iter_mut() {
+            vertex.position = [0, 1, 2].map(|i| vertex.position[i] + offset[i]);
+        }
+        offset
+    }
+
+    // Centra la malla en el origen y la escala (igual en los tres ejes) para que su lado más
+    // largo mida 1. Devuelve la transformación aplicada, para poder llevar otros datos al
+    // mismo espacio o deshacerla con su inversa.
+    pub fn normalize_to_unit_box(&mut self) -> Matrix4<f32>
+    {
+        let offset = self.recenter();
+        let extent = self.bounds().map_or(0.0, |bounds| bounds.max_extent());
+        // Una malla plana en todos los ejes (un punto) solo se centra
+        let scale = if extent > 0.0 { 1.0 / extent } else { 1.0 };
+
+        // La escala es uniforme, así que normales y tangentes no cambian
+        for vertex in self.vertices.iter_mut() {
+            vertex.position = vertex.position.map(|value| value * scale);
+        }
+
+        Matrix4::from_scale(scale) * Matrix4::from_translation(Vector3::from(offset))
+    }
+}
@@ -0,0 +1,349 @@
+use cgmath::{InnerSpace, Vector3};
+
+use super::{bounds::Aabb, Mesh};
+
+// Cajas en las que se reparten los centros de los triángulos al buscar el mejor corte
+const BIN_COUNT: usize = 16;
+// Coste de visitar un nodo relativo al de probar un triángulo
+const TRAVERSAL_COST: f32 = 1.0;
+// Las hojas con más triángulos se parten aunque la SAH diga que no compensa
+const MAX_LEAF_SIZE: usize = 8;
+// Cota del error relativo de tres operaciones en f32
+const GAMMA_3: f32 = 3.0 * f32::EPSILON / (1.0 - 3.0 * f32::EPSILON);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray
+{
+    pub origin: [f32; 3],
+    // Unitaria si se crea con Ray::new; las distancias se miden en longitudes de `direction`
+    pub direction: [f32; 3],
+}
+
+impl Ray {
+    pub fn new(origin: [f32; 3], direction: [f32; 3]) -> Ray
+    {
+        Ray {
+            origin,
+            direction: Vector3::from(direction).normalize().into(),
+        }
+    }
+
+    pub fn at(&self, distance: f32) -> [f32; 3]
+    {
+        [0, 1, 2].map(|i| self.origin[i] + self.direction[i] * distance)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit
+{
+    // Posición del triángulo en la malla (índice / 3)
+    pub triangle: usize,
+    pub distance: f32,
+    // Pesos de las tres esquinas del triángulo en el punto de corte; suman 1
+    pub barycentrics: [f32; 3],
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node
+{
+    bounds: Aabb,
+    // En las hojas, los triángulos first..first + count de Bvh::triangles. En los nodos
+    // interiores count es 0, el hijo izquierdo es el nodo siguiente y el derecho es first.
+    first: u32,
+    count: u32,
+}
+
+// Jerarquía de cajas sobre los triángulos de una malla, construida con la heurística de área
+// (SAH). Guarda una copia de las esquinas, así que no depende de la malla después de crearla.
+#[derive(Debug, Clone)]
+pub struct Bvh
+{
+    nodes: Vec<Node>,
+    // Triángulos de la malla en el orden de las hojas, con sus esquinas
+    triangles: Vec<u32>,
+    corners: Vec<[[f32; 3]; 3]>,
+}
+
+// Corte de Möller-Trumbore por las dos caras del triángulo: distancia y pesos de las esquinas
+// 1 y 2. Sirve también para comprobar la jerarquía contra la fuerza bruta.
+pub fn intersect_triangle(ray: &Ray, corners: &[[f32; 3]; 3], max_distance: f32) -> Option<(f32, f32, f32)> {
+    let [a, b, c] = corners.map(Vector3::from);
+    let direction = Vector3::from(ray.direction);
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = direction.cross(edge2);
+    let determinant = edge1.dot(p);
+    // Rayo paralelo al plano del triángulo (o triángulo sin área)
+    if determinant == 0.0 || !determinant.is_finite() {
+        return None;
+    }
+
+    let inverse = 1.0 / determinant;
+    let offset = Vector3::from(ray.origin) - a;
+    let u = offset.dot(p) * inverse;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = offset.cross(edge1);
+    let v = direction.dot(q) * inverse;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let distance = edge2.dot(q) * inverse;
+    if !(0.0..=max_distance).contains(&distance) {
+        return None;
+    }
+    Some((distance, u, v))
+}
+
+impl Bvh {
+    pub fn new(mesh: &Mesh) -> Bvh
+    {
+        // Los triángulos con alguna coordenada no finita no se pueden cortar y estropearían
+        // las cajas
+        let mut triangles: Vec<u32> = Vec::with_capacity(mesh.triangle_count());
+        let mut all_corners: Vec<[[f32; 3]; 3]> = Vec::with_capacity(mesh.triangle_count());
+        for (t, triangle) in mesh.triangles().enumerate() {
+            let corners = triangle.map(|index| mesh.vertices[index as usize].position);
+            if corners.iter().flatten().all(|value| value.is_finite()) {
+                triangles.push(t as u32);
+                all_corners.push(corners);
+            }
+        }
+
+        let bounds: Vec<Aabb> = all_corners.iter().map(|corners| Aabb::from_points(*corners).unwrap()).collect();
+        let centers: Vec<[f32; 3]> = bounds.iter().map(Aabb::center).collect();
+        let mut builder = Builder {
+            bounds: &bounds,
+            centers: &centers,
+            order: (0..triangles.len() as u32).collect(),
+            nodes: Vec::new(),
+        };
+        if !triangles.is_empty() {
+            builder.build(0, triangles.len());
+        }
+
+        let order = builder.order;
+        Bvh {
+            nodes: builder.nodes,
+            triangles: order.iter().map(|&i| triangles[i as usize]).collect(),
+            corners: order.iter().map(|&i| all_corners[i as usize]).collect(),
+        }
+    }
+
+    // None si la malla no tiene triángulos
+    pub fn bounds(&self) -> Option<Aabb>
+    {
+        self.nodes.first().map(|node| node.bounds)
+    }
+
+    pub fn node_count(&self) -> usize
+    {
+        self.nodes.len()
+    }
+
+    // El corte más cercano al origen del rayo, por cualquiera de las dos caras, a una
+    // distancia entre 0 y max_distance (f32::INFINITY para no limitarla)
+    pub fn intersect(&self, ray: &Ray, max_distance: f32) -> Option<RayHit>
+    {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        let inverse_direction = ray.direction.map(|value| 1.0 / value);
+        let mut closest = max_distance;
+        let mut hit: Option<(usize, f32, f32)> = None;
+
+        let mut stack: Vec<(usize, f32)> = Vec::with_capacity(64);
+        if let Some(entry) = intersect_box(ray, &inverse_direction, &self.nodes[0].bounds, closest) {
+            stack.push((0, entry));
+        }
+        while let Some((node_index, entry)) = stack.pop() {
+            // La caja se cortó antes de encontrar un triángulo más cercano
+            if entry > closest {
+                continue;
+            }
+
+            let node = &self.nodes[node_index];
+            if node.count > 0 {
+                let range = node.first as usize..(node.first + node.count) as usize;
+                for i in range {
+                    if let Some((distance, u, v)) = intersect_triangle(ray, &self.corners[i], closest) {
+                        // Con empates se queda el primero que se encuentra
+                        if hit.is_none() || distance < closest {
+                            closest = distance;
+                            hit = Some((i, u, v));
+                        }
+                    }
+                }
+                continue;
+            }
+
+            // Primero el hijo más cercano: se apila el último
+            let children = [node_index + 1, node.first as usize];
+            let entries = children.map(|child| intersect_box(ray, &inverse_direction, &self.nodes[child].bounds, closest));
+            let (near, far) = if entries[1].unwrap_or(f32::INFINITY) < entries[0].unwrap_or(f32::INFINITY) { (1, 0) } else { (0, 1) };
+            for k in [far, near] {
+                if let Some(entry) = entries[k] {
+                    stack.push((children[k], entry));
+                }
+            }
+        }
+
+        hit.map(|(i, u, v)| RayHit {
+            triangle: self.triangles[i] as usize,
+            distance: closest,
+            barycentrics: [1.0 - u - v, u, v],
+        })
+    }
+}
+
+impl Mesh {
+    pub fn bvh(&self) -> Bvh
+    {
+        Bvh::new(self)
+    }
+}
+
+struct Builder<'a>
+{
+    bounds: &'a [Aabb],
+    centers: &'a [[f32; 3]],
+    // Índices en bounds y centers, reordenados para que cada hoja tenga un tramo seguido
+    order: Vec<u32>,
+    nodes: Vec<Node>,
+}
+
+impl Builder<'_> {
+    fn build(&mut self, start: usize, end: usize)
+    {
+        let slice = &self.order[start..end];
+        let mut bounds = self.bounds[slice[0] as usize];
+        let mut center_bounds = Aabb { min: self.centers[slice[0] as usize], max: self.centers[slice[0] as usize] };
+        for &i in slice.iter() {
+            bounds = bounds.union(&self.bounds[i as usize]);
+            center_bounds.extend(self.centers[i as usize]);
+        }
+
+        let node_index = self.nodes.len();
+        self.nodes.push(Node { bounds, first: start as u32, count: (end - start) as u32 });
+
+        let Some((axis, split)) = self.best_split(start, end, &bounds, &center_bounds) else {
+            return;
+        };
+
+        // Se separan los triángulos cuyo centro cae en las cajas anteriores al corte
+        let bin = |center: [f32; 3]| bin_index(center[axis], center_bounds.min[axis], center_bounds.size()[axis]);
+        let mut middle = start;
+        for i in start..end {
+            if bin(self.centers[self.order[i] as usize]) < split {
+                self.order.swap(i, middle);
+                middle += 1;
+            }
+        }
+
+        self.build(start, middle);
+        let right = self.nodes.len();
+        self.build(middle, end);
+        self.nodes[node_index].first = right as u32;
+        self.nodes[node_index].count = 0;
+    }
+
+    // Eje y primera caja del lado derecho del corte más barato, o None si sale más barato
+    // dejar una hoja (o no se puede partir: todos los centros en el mismo punto)
+    fn best_split(&self, start: usize, end: usize, bounds: &Aabb, center_bounds: &Aabb) -> Option<(usize, usize)>
+    {
+        let count = end - start;
+        if count == 1 {
+            return None;
+        }
+
+        // Costes sin dividir por el área del padre, que puede ser cero
+        let leaf_cost = count as f32 * bounds.surface_area();
+        let mut best: Option<(f32, usize, usize)> = None;
+        for axis in 0..3 {
+            let extent = center_bounds.size()[axis];
+            if extent <= 0.0 {
+                continue;
+            }
+
+            let mut bin_counts = [0usize; BIN_COUNT];
+            let mut bin_bounds: [Option<Aabb>; BIN_COUNT] = [None; BIN_COUNT];
+            for &i in self.order[start..end].iter() {
+                let bin = bin_index(self.centers[i as usize][axis], center_bounds.min[axis], extent);
+                bin_counts[bin] += 1;
+                let triangle_bounds = self.bounds[i as usize];
+                bin_bounds[bin] = Some(bin_bounds[bin].map_or(triangle_bounds, |bounds| bounds.union(&triangle_bounds)));
+            }
+
+            // Área y número de triángulos a la derecha de cada corte, de derecha a izquierda
+            let mut right_costs = [0.0; BIN_COUNT];
+            let mut right_counts = [0; BIN_COUNT];
+            let mut accumulated: Option<Aabb> = None;
+            let mut right_count = 0;
+            for bin in (1..BIN_COUNT).rev() {
+                accumulated = union(accumulated, bin_bounds[bin]);
+                right_count += bin_counts[bin];
+                right_counts[bin] = right_count;
+                right_costs[bin] = accumulated.map_or(0.0, |bounds| bounds.surface_area()) * right_count as f32;
+            }
+
+            let mut accumulated: Option<Aabb> = None;
+            let mut left_count = 0;
+            for split in 1..BIN_COUNT {
+                accumulated = union(accumulated, bin_bounds[split - 1]);
+                left_count += bin_counts[split - 1];
+                if left_count == 0 || right_counts[split] == 0 {
+                    continue;
+                }
+                let left_cost = accumulated.map_or(0.0, |bounds| bounds.surface_area()) * left_count as f32;
+                let cost = TRAVERSAL_COST * bounds.surface_area() + left_cost + right_costs[split];
+                if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                    best = Some((cost, axis, split));
+                }
+            }
+        }
+
+        let (cost, axis, split) = best?;
+        if cost >= leaf_cost && count <= MAX_LEAF_SIZE {
+            return None;
+        }
+        Some((axis, split))
+    }
+}
+
+fn bin_index(value: f32, min: f32, extent: f32) -> usize {
+    (((value - min) / extent * BIN_COUNT as f32) as usize).min(BIN_COUNT - 1)
+}
+
+fn union(a: Option<Aabb>, b: Option<Aabb>) -> Option<Aabb> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.union(&b)),
+        (a, b) => a.or(b),
+    }
+}
+
+// Distancia a la que el rayo entra en la caja (0 si empieza dentro), si la corta antes de
+// max_distance
+fn intersect_box(ray: &Ray, inverse_direction: &[f32; 3], bounds: &Aabb, max_distance: f32) -> Option<f32> {
+    let mut near = 0.0f32;
+    let mut far = max_distance;
+    for (axis, &inverse) in inverse_direction.iter().enumerate() {
+        // Paralelo a las caras del eje: está entre ellas o no corta nunca (y 0 * infinito
+        // daría NaN si el origen está justo en una cara)
+        if ray.direction[axis] == 0.0 {
+            if ray.origin[axis] < bounds.min[axis] || ray.origin[axis] > bounds.max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let t1 = (bounds.min[axis] - ray.origin[axis]) * inverse;
+        let t2 = (bounds.max[axis] - ray.origin[axis]) * inverse;
+        // La salida se alarga un poco para cubrir el redondeo, como en PBRT: si no, un rayo
+        // que roza el borde de la caja puede perder un triángulo que sí corta.
+        near = near.max(t1.min(t2));
+        far = far.min(t1.max(t2) * (1.0 + 2.0 * GAMMA_3));
+    }
+    (near <= far).then_some(near)
+}
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use cgmath::{InnerSpace, Vector3};
+
+use super::{normals::NormalOptions, Mesh, Vertex};
+
+struct Face
+{
+    vertices: [usize; 3],
+    normal: Vector3<f64>,
+    offset: f64,
+    // Puntos por fuera de esta cara que todavía no están en la envolvente
+    outside: Vec<usize>,
+    alive: bool,
+}
+
+impl Face {
+    fn new(points: &[Vector3<f64>], vertices: [usize; 3]) -> Face
+    {
+        let [a, b, c] = vertices.map(|vertex| points[vertex]);
+        let cross = (b - a).cross(c - a);
+        let normal = if cross.magnitude2() > 0.0 { cross.normalize() } else { cross };
+        Face {
+            vertices,
+            normal,
+            offset: normal.dot(a),
+            outside: Vec::new(),
+            alive: true,
+        }
+    }
+
+    fn distance(&self, point: Vector3<f64>) -> f64
+    {
+        self.normal.dot(point) - self.offset
+    }
+}
+
+// Envolvente convexa (quickhull) de un conjunto de puntos: una malla cerrada, con un vértice
+// por esquina de la envolvente y los triángulos hacia fuera. Los puntos no finitos se ignoran.
+// Devuelve None si los puntos no encierran volumen (menos de cuatro, alineados o coplanarios).
+pub fn convex_hull(points: &[[f32; 3]]) -> Option<Mesh> {
+    let points: Vec<Vector3<f64>> = points
+        .iter()
+        .filter(|point| point.iter().all(|value| value.is_finite()))
+        .map(|point| Vector3::new(point[0] as f64, point[1] as f64, point[2] as f64))
+        .collect();
+    if points.len() < 4 {
+        return None;
+    }
+
+    // Tolerancia proporcional a la magnitud de las coordenadas: los puntos llegan en f32 y
+    // los que están a menos de eso de una cara se consideran sobre ella
+    let mut scale = Vector3::new(0.0, 0.0, 0.0);
+    for point in points.iter() {
+        for axis in 0..3 {
+            scale[axis] = f64::max(scale[axis], point[axis].abs());
+        }
+    }
+    let epsilon = 3.0 * f32::EPSILON as f64 * (scale.x + scale.y + scale.z);
+
+    // Con empates en la distancia quickhull puede tomar como esquina un punto que queda en
+    // medio de una arista o una cara. Se quitan y se repite con las esquinas que quedan, que
+    // son muchos menos puntos.
+    let mut points = points;
+    let mut faces = quickhull(&points, epsilon)?;
+    loop {
+        let redundant = redundant_vertices(&faces, &points, epsilon);
+        if redundant.is_empty() {
+            break;
+        }
+        let mut keep = vec![false; points.len()];
+        for face in faces.iter() {
+            for &vertex in face.vertices.iter() {
+                keep[vertex] = true;
+            }
+        }
+        for vertex in redundant {
+            keep[vertex] = false;
+        }
+        points = points.iter().zip(keep).filter(|(_, keep)| *keep).map(|(&point, _)| point).collect();
+        faces = quickhull(&points, epsilon)?;
+    }
+
+    // Solo las esquinas de la envolvente pasan a la malla
+    let mut remap: Vec<Option<u32>> = vec![None; points.len()];
+    let mut vertices: Vec<Vertex> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+    for face in faces.iter() {
+        for &point in face.vertices.iter() {
+            let index = *remap[point].get_or_insert_with(|| {
+                let position = points[point];
+                vertices.push(Vertex::new([position.x as f32, position.y as f32, position.z as f32], [0.0; 3], [0.0; 2]));
+                (vertices.len() - 1) as u32
+            });
+            indices.push(index);
+        }
+    }
+
+    let mut mesh = Mesh::new(vertices, indices);
+    mesh.compute_smooth_normals(&NormalOptions::default());
+    Some(mesh)
+}
+
+// Caras de la envolvente, hacia fuera
+fn quickhull(points: &[Vector3<f64>], epsilon: f64) -> Option<Vec<Face>> {
+    let simplex = initial_simplex(points, epsilon)?;
+    let mut faces: Vec<Face> = Vec::new();
+    // Cara de cada arista orientada; la cara vecina por la arista (a, b) es la de (b, a)
+    let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+    let inside = simplex.iter().fold(Vector3::new(0.0, 0.0, 0.0), |sum, &vertex| sum + points[vertex]) / 4.0;
+    for [a, b, c] in [[0, 1, 2], [0, 3, 1], [1, 3, 2], [2, 3, 0]] {
+        let mut face = Face::new(points, [simplex[a], simplex[b], simplex[c]]);
+        if face.distance(inside) > 0.0 {
+            face = Face::new(points, [simplex[a], simplex[c], simplex[b]]);
+        }
+        add_face(&mut faces, &mut edges, face);
+    }
+
+    let all: Vec<usize> = (0..points.len()).filter(|point| !simplex.contains(point)).collect();
+    assign_points(points, &mut faces, &[0, 1, 2, 3], &all, epsilon);
+
+    // Caras que pueden tener puntos por fuera; las que mueren se saltan al sacarlas
+    let mut pending: Vec<usize> = (0..4).collect();
+    let mut visible = vec![false; faces.len()];
+    while let Some(current) = pending.pop() {
+        if !faces[current].alive || faces[current].outside.is_empty() {
+            continue;
+        }
+        // El punto más alejado de la cara es seguro que es una esquina de la envolvente
+        let face = &faces[current];
+        let center = face.vertices.iter().fold(Vector3::new(0.0, 0.0, 0.0), |sum, &vertex| sum + points[vertex]) / 3.0;
+        let eye = farthest(points, face.outside.iter().copied(), |point| face.distance(points[point]), center, epsilon).unwrap();
+        let eye_point = points[eye];
+
+        // Caras que ve el punto, recorridas por vecindad desde la actual, y el horizonte: las
+        // aristas entre una cara visible y una que no lo es
+        visible.resize(faces.len(), false);
+        let mut visible_faces = vec![current];
+        let mut horizon: Vec<(usize, usize)> = Vec::new();
+        visible[current] = true;
+        let mut next = 0;
+        while next < visible_faces.len() {
+            let face = visible_faces[next];
+            next += 1;
+            let [a, b, c] = faces[face].vertices;
+            for (from, to) in [(a, b), (b, c), (c, a)] {
+                // Con errores de redondeo la malla puede dejar de ser cerrada; mejor no dar
+                // envolvente que entrar en pánico
+                let neighbour = *edges.get(&(to, from))?;
+                if visible[neighbour] {
+                    continue;
+                }
+                if faces[neighbour].distance(eye_point) > epsilon {
+                    visible[neighbour] = true;
+                    visible_faces.push(neighbour);
+                } else {
+                    horizon.push((from, to));
+                }
+            }
+        }
+
+        let mut orphans: Vec<usize> = Vec::new();
+        for &face in visible_faces.iter() {
+            visible[face] = false;
+            faces[face].alive = false;
+            orphans.append(&mut faces[face].outside);
+            let [a, b, c] = faces[face].vertices;
+            for edge in [(a, b), (b, c), (c, a)] {
+                edges.remove(&edge);
+            }
+        }
+        orphans.retain(|&point| point != eye);
+
+        // Las caras nuevas unen el punto con el horizonte, en el sentido de las visibles
+        let first_new = faces.len();
+        for (from, to) in horizon {
+            add_face(&mut faces, &mut edges, Face::new(points, [from, to, eye]));
+        }
+        let new_faces: Vec<usize> = (first_new..faces.len()).collect();
+        assign_points(points, &mut faces, &new_faces, &orphans, epsilon);
+        pending.extend(new_faces);
+    }
+
+    faces.retain(|face| face.alive);
+    Some(faces)
+}
+
+// Una esquina de verdad toca al menos tres planos distintos; las que solo tocan uno (están en
+// medio de una cara) o dos (en medio de una arista) sobran
+fn redundant_vertices(faces: &[Face], points: &[Vector3<f64>], epsilon: f64) -> Vec<usize> {
+    let mut planes: HashMap<usize, Vec<&Face>> = HashMap::new();
+    for face in faces.iter() {
+        for &vertex in face.vertices.iter() {
+            let distinct = planes.entry(vertex).or_default();
+            let coplanar = |other: &&Face| {
+                other.normal.dot(face.normal) > 0.0
+                    && face.vertices.iter().all(|&point| other.distance(points[point]).abs() <= epsilon)
+            };
+            if !distinct.iter().any(coplanar) {
+                distinct.push(face);
+            }
+        }
+    }
+    let mut redundant: Vec<usize> = planes.into_iter().filter(|(_, distinct)| distinct.len() < 3).map(|(vertex, _)| vertex).collect();
+    redundant.sort_unstable();
+    redundant
+}
+
+impl Mesh {
+    // Envolvente convexa de todos los vértices de la malla, tengan o no triángulos (nubes de
+    // puntos de PLY, por ejemplo)
+    pub fn convex_hull(&self) -> Option<Mesh>
+    {
+        let positions: Vec<[f32; 3]> = self.vertices.iter().map(|vertex| vertex.position).collect();
+        convex_hull(&positions)
+    }
+}
+
+// Cuatro puntos que forman un tetraedro con volumen, lo más separados posible
+fn initial_simplex(points: &[Vector3<f64>], epsilon: f64) -> Option<[usize; 4]> {
+    let all = || 0..points.len();
+    let centroid = points.iter().fold(Vector3::new(0.0, 0.0, 0.0), |sum, &point| sum + point) / points.len() as f64;
+
+    // El punto más lejano del centro y el más lejano de ese son esquinas de la envolvente
+    let a = farthest(points, all(), |point| (points[point] - centroid).magnitude(), centroid, epsilon)?;
+    let b = farthest(points, all(), |point| (points[point] - points[a]).magnitude(), points[a], epsilon)?;
+    if (points[a] - points[b]).magnitude() <= epsilon {
+        return None;
+    }
+
+    let direction = (points[b] - points[a]).normalize();
+    let line_distance = |point: usize| (points[point] - points[a]).cross(direction).magnitude();
+    let c = farthest(points, all(), line_distance, points[a], epsilon)?;
+    if line_distance(c) <= epsilon {
+        return None;
+    }
+
+    let plane = Face::new(points, [a, b, c]);
+    let plane_distance = |point: usize| plane.distance(points[point]).abs();
+    let d = farthest(points, all(), plane_distance, (points[a] + points[b] + points[c]) / 3.0, epsilon)?;
+    if plane_distance(d) <= epsilon {
+        return None;
+    }
+
+    Some([a, b, c, d])
+}
+
+// El candidato con mayor `distance`. Entre los empatados (a menos de epsilon) se queda con el
+// más alejado de `anchor`: los empatados están en una cara o arista de la envolvente y así se
+// elige una de sus esquinas, no un punto intermedio que dejaría vértices de más.
+fn farthest<D: Fn(usize) -> f64>(
+    points: &[Vector3<f64>],
+    candidates: impl Iterator<Item = usize> + Clone,
+    distance: D,
+    anchor: Vector3<f64>,
+    epsilon: f64,
+) -> Option<usize> {
+    let max = candidates.clone().map(&distance).max_by(f64::total_cmp)?;
+    candidates
+        .filter(|&point| distance(point) >= max - epsilon)
+        .max_by(|&p, &q| (points[p] - anchor).magnitude2().total_cmp(&(points[q] - anchor).magnitude2()))
+}
+
+fn add_face(faces: &mut Vec<Face>, edges: &mut HashMap<(usize, usize), usize>, face: Face) {
+    let [a, b, c] = face.vertices;
+    for edge in [(a, b), (b, c), (c, a)] {
+        edges.insert(edge, faces.len());
+    }
+    faces.push(face);
+}
+
+// Cada punto se apunta a la cara de la que más sobresale; los que no sobresalen de ninguna
+// quedan dentro de la envolvente y se descartan
+fn assign_points(points: &[Vector3<f64>], faces: &mut [Face], candidates: &[usize], assigned: &[usize], epsilon: f64) {
+    for &point in assigned {
+        let mut best: Option<(usize, f64)> = None;
+        for &face in candidates {
+            let distance = faces[face].distance(points[point]);
+            if distance > epsilon && best.is_none_or(|(_, best_distance)| distance > best_distance) {
+                best = Some((face, distance));
+            }
+        }
+        if let Some((face, _)) = best {
+            faces[face].outside.push(point);
+        }
+    }
+}
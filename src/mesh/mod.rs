@@ -0,0 +1,90 @@
+pub mod bounds;
+pub mod bvh;
+pub mod hull;
+pub mod normals;
+pub mod optimize;
+pub mod primitives;
+pub mod simplify;
+pub mod subdivide;
+pub mod tangents;
+pub mod transform;
+pub mod triangulate;
+pub mod validate;
+
+// Mismo orden que espera vertex_shader.glsl: posición, normal, coordenadas de textura
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vertex
+{
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub uv: [f32; 2],
+}
+
+impl Vertex {
+    pub fn new(position: [f32; 3], normal: [f32; 3], uv: [f32; 2]) -> Vertex
+    {
+        Vertex {
+            position,
+            normal,
+            uv,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Mesh
+{
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    // Atributo opcional: tangente por vértice con el signo del bitangente en w
+    pub tangents: Option<Vec<[f32; 4]>>,
+    // Atributo opcional: color RGBA por vértice (PLY, glTF COLOR_0), con valores entre 0 y 1
+    pub colors: Option<Vec<[f32; 4]>>,
+}
+
+impl Mesh {
+    pub fn new(vertices: Vec<Vertex>, indices: Vec<u32>) -> Mesh
+    {
+        Mesh {
+            vertices,
+            indices,
+            tangents: None,
+            colors: None,
+        }
+    }
+
+    pub fn triangle_count(&self) -> usize
+    {
+        self.indices.len() / 3
+    }
+
+    pub fn triangle(&self, index: usize) -> [u32; 3]
+    {
+        let i = index * 3;
+        [self.indices[i], self.indices[i + 1], self.indices[i + 2]]
+    }
+
+    pub fn triangles(&self) -> impl Iterator<Item = [u32; 3]> + '_
+    {
+        self.indices.chunks_exact(3).map(|t| [t[0], t[1], t[2]])
+    }
+
+    // Expande el índice a la lista plana de floats (8 por vértice) que usaba parse_obj
+    pub fn to_vertex_soup(&self) -> Vec<f32>
+    {
+        let mut res: Vec<f32> = Vec::with_capacity(self.indices.len() * 8);
+        for &index in self.indices.iter() {
+            let vertex = &self.vertices[index as usize];
+            res.extend_from_slice(&vertex.position);
+            res.extend_from_slice(&vertex.normal);
+            res.extend_from_slice(&vertex.uv);
+        }
+        res
+    }
+}
+
+// Clave exacta para usar vectores en un HashMap; -0.0 y 0.0 se consideran iguales
+pub(crate) fn float_key<const N: usize>(v: [f32; N]) -> [u32; N] {
+    v.map(|x| (x + 0.0).to_bits())
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use cgmath::{InnerSpace, Vector3};
+
+use super::{float_key, Mesh, Vertex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalWeighting
+{
+    // Cada cara pesa según su área
+    Area,
+    // Cada cara pesa según el ángulo que forma en el vértice
+    Angle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NormalOptions
+{
+    pub weighting: NormalWeighting,
+    // Ángulo máximo (en grados) entre caras para suavizar entre ellas
+    pub crease_angle: Option<f32>,
+}
+
+impl Default for NormalOptions {
+    fn default() -> NormalOptions
+    {
+        NormalOptions {
+            weighting: NormalWeighting::Angle,
+            crease_angle: None,
+        }
+    }
+}
+
+// Calcula una normal por esquina de triángulo. `corners` contiene las posiciones de los
+// triángulos de tres en tres y `smoothing_groups` un grupo por triángulo; el grupo 0 es plano.
+// Las esquinas con la misma posición y grupo se suavizan entre sí.
+pub fn generate_corner_normals(corners: &[[f32; 3]], smoothing_groups: &[u32], options: &NormalOptions) -> Vec<[f32; 3]> {
+    let triangle_count = corners.len() / 3;
+
+    let mut face_normals: Vec<Vector3<f32>> = Vec::with_capacity(triangle_count);
+    let mut weights: Vec<[f32; 3]> = Vec::with_capacity(triangle_count);
+    for t in 0..triangle_count {
+        let [a, b, c] = [0, 1, 2].map(|k| Vector3::from(corners[t * 3 + k]));
+        let cross = (b - a).cross(c - a);
+        let area = cross.magnitude();
+        face_normals.push(if area > 0.0 { cross / area } else { Vector3::new(0.0, 0.0, 0.0) });
+
+        weights.push(match options.weighting {
+            NormalWeighting::Area => [area; 3],
+            NormalWeighting::Angle => [angle(b - a, c - a), angle(c - b, a - b), angle(a - c, b - c)],
+        });
+    }
+
+    // Esquinas que comparten posición (comparada bit a bit) y grupo de suavizado
+    let mut shared: HashMap<([u32; 3], u32), Vec<usize>> = HashMap::new();
+    for (corner, position) in corners.iter().enumerate() {
+        let group = smoothing_groups[corner / 3];
+        if group != 0 {
+            shared.entry((float_key(*position), group)).or_default().push(corner);
+        }
+    }
+
+    let crease_cos = options.crease_angle.map(|angle| angle.to_radians().cos());
+    let mut normals: Vec<[f32; 3]> = vec![[0.0; 3]; corners.len()];
+
+    for neighbours in shared.values() {
+        match crease_cos {
+            None => {
+                let sum = neighbours
+                    .iter()
+                    .fold(Vector3::new(0.0, 0.0, 0.0), |acc, &c| acc + face_normals[c / 3] * weights[c / 3][c % 3]);
+                for &corner in neighbours.iter() {
+                    normals[corner] = finish(sum, face_normals[corner / 3]);
+                }
+            }
+            Some(crease_cos) => {
+                for &corner in neighbours.iter() {
+                    let own = face_normals[corner / 3];
+                    let sum = neighbours
+                        .iter()
+                        .filter(|&&c| c / 3 == corner / 3 || own.dot(face_normals[c / 3]) >= crease_cos)
+                        .fold(Vector3::new(0.0, 0.0, 0.0), |acc, &c| acc + face_normals[c / 3] * weights[c / 3][c % 3]);
+                    normals[corner] = finish(sum, own);
+                }
+            }
+        }
+    }
+
+    // Triángulos sin suavizado: normal de la cara
+    for (corner, normal) in normals.iter_mut().enumerate() {
+        if smoothing_groups[corner / 3] == 0 {
+            *normal = face_normals[corner / 3].into();
+        }
+    }
+
+    normals
+}
+
+fn angle(a: Vector3<f32>, b: Vector3<f32>) -> f32 {
+    let lengths = a.magnitude() * b.magnitude();
+    if lengths <= 0.0 {
+        return 0.0;
+    }
+    (a.dot(b) / lengths).clamp(-1.0, 1.0).acos()
+}
+
+fn finish(sum: Vector3<f32>, fallback: Vector3<f32>) -> [f32; 3] {
+    if sum.magnitude2() > 0.0 {
+        sum.normalize().into()
+    } else {
+        fallback.into()
+    }
+}
+
+impl Mesh {
+    // Sustituye las normales por las de cada cara; los vértices dejan de compartirse entre triángulos
+    pub fn compute_flat_normals(&mut self)
+    {
+        let groups = vec![0; self.triangle_count()];
+        self.apply_corner_normals(&groups, &NormalOptions::default());
+    }
+
+    // Recalcula normales suavizadas entre todos los triángulos que comparten posición
+    pub fn compute_smooth_normals(&mut self, options: &NormalOptions)
+    {
+        let groups = vec![1; self.triangle_count()];
+        self.apply_corner_normals(&groups, options);
+    }
+
+    fn apply_corner_normals(&mut self, smoothing_groups: &[u32], options: &NormalOptions)
+    {
+        let corners: Vec<[f32; 3]> = self.indices.iter().map(|&i| self.vertices[i as usize].position).collect();
+        let normals = generate_corner_normals(&corners, smoothing_groups, options);
+
+        // Un vértice solo se reutiliza si conserva la misma normal en todas sus esquinas
+        let mut welded: HashMap<(u32, [u32; 3]), u32> = HashMap::new();
+        let mut vertices: Vec<Vertex> = Vec::with_capacity(self.vertices.len());
+        let mut colors: Option<Vec<[f32; 4]>> = self.colors.as_ref().map(|_| Vec::with_capacity(self.vertices.len()));
+        for (index, normal) in self.indices.iter_mut().zip(normals) {
+            let old = *index;
+            *index = *welded.entry((old, float_key(normal))).or_insert_with(|| {
+                vertices.push(Vertex { normal, ..self.vertices[old as usize] });
+                if let (Some(colors), Some(old_colors)) = (colors.as_mut(), self.colors.as_ref()) {
+                    colors.push(old_colors[old as usize]);
+                }
+                (vertices.len() - 1) as u32
+            });
+        }
+        self.vertices = vertices;
+        self.colors = colors;
+        // Los tangentes dependen de las normales y dejan de ser válidos
+        self.tangents = None;
+    }
+}
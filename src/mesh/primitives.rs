@@ -0,0 +1,344 @@
+use std::{
+    collections::HashMap,
+    f32::consts::{FRAC_PI_2, PI, TAU},
+};
+
+use cgmath::{InnerSpace, Vector3};
+
+use super::{float_key, Mesh, Vertex};
+
+// Generadores de formas básicas centradas en el origen, con Y hacia arriba, caras frontales en
+// sentido antihorario vistas desde fuera y la misma disposición de vértices que parse_obj
+// (v = 0 abajo). Las costuras de UV usan vértices duplicados.
+impl Mesh {
+    // Cubo de lado `size` con normales planas y la textura completa en cada cara
+    pub fn cube(size: f32) -> Mesh
+    {
+        let h = size * 0.5;
+        let mut mesh = Mesh::default();
+        // Normal de la cara y los ejes hacia los que crecen u y v vistos desde fuera
+        let faces: [([f32; 3], [f32; 3], [f32; 3]); 6] = [
+            ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]),
+            ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+            ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+            ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+            ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+            ([0.0, 0.0, -1.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+        ];
+
+        for (normal, u_axis, v_axis) in faces {
+            let [n, u, v] = [normal, u_axis, v_axis].map(Vector3::from);
+            let first = mesh.vertices.len() as u32;
+            for [s, t] in [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]] {
+                let position = (n + u * (s * 2.0 - 1.0) + v * (t * 2.0 - 1.0)) * h;
+                mesh.vertices.push(Vertex::new(position.into(), normal, [s, t]));
+            }
+            mesh.indices.extend_from_slice(&[first, first + 1, first + 2, first, first + 2, first + 3]);
+        }
+
+        mesh
+    }
+
+    // Rejilla en el plano XZ mirando hacia +Y, con u a lo largo de X y v a lo largo de -Z
+    pub fn plane(width: f32, depth: f32, x_segments: usize, z_segments: usize) -> Mesh
+    {
+        let (columns, rows) = (x_segments.max(1), z_segments.max(1));
+        let mut mesh = Mesh::default();
+        for row in 0..=rows {
+            let v = row as f32 / rows as f32;
+            for column in 0..=columns {
+                let u = column as f32 / columns as f32;
+                let position = [(u - 0.5) * width, 0.0, (0.5 - v) * depth];
+                mesh.vertices.push(Vertex::new(position, [0.0, 1.0, 0.0], [u, v]));
+            }
+        }
+        push_grid(&mut mesh, 0, rows, columns);
+        mesh
+    }
+
+    // Esfera por meridianos y paralelos; `rings` es el número de franjas entre los polos
+    pub fn uv_sphere(radius: f32, segments: usize, rings: usize) -> Mesh
+    {
+        let rings = rings.max(2);
+        let profile: Vec<ProfilePoint> = (0..=rings)
+            .map(|ring| {
+                let v = 1.0 - ring as f32 / rings as f32;
+                let angle = FRAC_PI_2 - PI * ring as f32 / rings as f32;
+                ProfilePoint::on_circle(radius, 0.0, angle, v)
+            })
+            .collect();
+        lathe(&profile, segments)
+    }
+
+    // Esfera a partir de un icosaedro cuyos triángulos se dividen en cuatro `subdivisions`
+    // veces; los triángulos son casi iguales en toda la superficie, al contrario que en
+    // uv_sphere
+    pub fn icosphere(radius: f32, subdivisions: usize) -> Mesh
+    {
+        let t = (1.0 + 5.0f32.sqrt()) * 0.5;
+        let mut points: Vec<Vector3<f32>> = [
+            [-1.0, t, 0.0],
+            [1.0, t, 0.0],
+            [-1.0, -t, 0.0],
+            [1.0, -t, 0.0],
+            [0.0, -1.0, t],
+            [0.0, 1.0, t],
+            [0.0, -1.0, -t],
+            [0.0, 1.0, -t],
+            [t, 0.0, -1.0],
+            [t, 0.0, 1.0],
+            [-t, 0.0, -1.0],
+            [-t, 0.0, 1.0],
+        ]
+        .iter()
+        .map(|&point| Vector3::from(point).normalize())
+        .collect();
+        let mut triangles: Vec<[u32; 3]> = vec![
+            [0, 11, 5],
+            [0, 5, 1],
+            [0, 1, 7],
+            [0, 7, 10],
+            [0, 10, 11],
+            [1, 5, 9],
+            [5, 11, 4],
+            [11, 10, 2],
+            [10, 7, 6],
+            [7, 1, 8],
+            [3, 9, 4],
+            [3, 4, 2],
+            [3, 2, 6],
+            [3, 6, 8],
+            [3, 8, 9],
+            [4, 9, 5],
+            [2, 4, 11],
+            [6, 2, 10],
+            [8, 6, 7],
+            [9, 8, 1],
+        ];
+
+        for _ in 0..subdivisions {
+            let mut middles: HashMap<(u32, u32), u32> = HashMap::new();
+            let mut middle = |a: u32, b: u32| {
+                *middles.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                    points.push((points[a as usize] + points[b as usize]).normalize());
+                    (points.len() - 1) as u32
+                })
+            };
+            triangles = triangles
+                .iter()
+                .flat_map(|&[a, b, c]| {
+                    let (ab, bc, ca) = (middle(a, b), middle(b, c), middle(c, a));
+                    [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+                })
+                .collect();
+        }
+
+        // Coordenadas esféricas por esquina: los triángulos que cruzan la costura usan copias
+        // con u + 1 y los polos toman la u media de las otras dos esquinas
+        let spherical = |point: Vector3<f32>| [0.5 + point.x.atan2(point.z) / TAU, 0.5 + point.y.clamp(-1.0, 1.0).asin() / PI];
+        let mut mesh = Mesh::default();
+        let mut welded: HashMap<(u32, [u32; 2]), u32> = HashMap::new();
+        for triangle in triangles {
+            let mut uvs = triangle.map(|point| spherical(points[point as usize]));
+            let is_pole = |point: u32| points[point as usize].y.abs() > 1.0 - 1e-6;
+            let regular: Vec<usize> = (0..3).filter(|&k| !is_pole(triangle[k])).collect();
+            let (min, max) = regular.iter().fold((f32::MAX, f32::MIN), |(min, max), &k| (min.min(uvs[k][0]), max.max(uvs[k][0])));
+            if max - min > 0.5 {
+                for &k in regular.iter() {
+                    if uvs[k][0] < 0.5 {
+                        uvs[k][0] += 1.0;
+                    }
+                }
+            }
+            let average = regular.iter().map(|&k| uvs[k][0]).sum::<f32>() / regular.len() as f32;
+            for (k, uv) in uvs.iter_mut().enumerate() {
+                if is_pole(triangle[k]) {
+                    uv[0] = average;
+                }
+            }
+
+            for (k, &point) in triangle.iter().enumerate() {
+                let index = *welded.entry((point, float_key(uvs[k]))).or_insert_with(|| {
+                    let normal = points[point as usize];
+                    mesh.vertices.push(Vertex::new((normal * radius).into(), normal.into(), uvs[k]));
+                    (mesh.vertices.len() - 1) as u32
+                });
+                mesh.indices.push(index);
+            }
+        }
+
+        mesh
+    }
+
+    // Cilindro de eje Y con tapas planas
+    pub fn cylinder(radius: f32, height: f32, segments: usize) -> Mesh
+    {
+        let h = height * 0.5;
+        let side = [
+            ProfilePoint { radius, y: h, normal: [1.0, 0.0], v: 1.0 },
+            ProfilePoint { radius, y: -h, normal: [1.0, 0.0], v: 0.0 },
+        ];
+        let mut mesh = lathe(&side, segments);
+        push_disc(&mut mesh, radius, h, segments, true);
+        push_disc(&mut mesh, radius, -h, segments, false);
+        mesh
+    }
+
+    // Cono de eje Y con la base abajo; el vértice superior se repite en cada meridiano para
+    // que cada uno tenga la normal de su lado
+    pub fn cone(radius: f32, height: f32, segments: usize) -> Mesh
+    {
+        let h = height * 0.5;
+        let slope = Vector3::new(height, radius, 0.0).normalize();
+        let side = [
+            ProfilePoint { radius: 0.0, y: h, normal: [slope.x, slope.y], v: 1.0 },
+            ProfilePoint { radius, y: -h, normal: [slope.x, slope.y], v: 0.0 },
+        ];
+        let mut mesh = lathe(&side, segments);
+        push_disc(&mut mesh, radius, -h, segments, false);
+        mesh
+    }
+
+    // Toro alrededor del eje Y: `major_radius` hasta el centro del tubo y `minor_radius` del tubo
+    pub fn torus(major_radius: f32, minor_radius: f32, major_segments: usize, minor_segments: usize) -> Mesh
+    {
+        let minor_segments = minor_segments.max(3);
+        // La sección se recorre desde el interior del anillo por arriba hacia el exterior, igual
+        // que el perfil de la esfera va de arriba abajo por fuera
+        let profile: Vec<ProfilePoint> = (0..=minor_segments)
+            .map(|j| {
+                let v = 1.0 - j as f32 / minor_segments as f32;
+                let angle = PI - TAU * (j % minor_segments) as f32 / minor_segments as f32;
+                ProfilePoint::on_circle(minor_radius, major_radius, angle, v)
+            })
+            .collect();
+        lathe(&profile, major_segments)
+    }
+
+    // Cilindro de altura `height` con dos semiesferas de radio `radius` en los extremos; la
+    // altura total es height + 2 * radius. `rings` es el número de franjas de cada semiesfera.
+    pub fn capsule(radius: f32, height: f32, segments: usize, rings: usize) -> Mesh
+    {
+        let rings = rings.max(1);
+        let h = height * 0.5;
+        let total = height + 2.0 * radius;
+        let mut profile: Vec<ProfilePoint> = Vec::with_capacity(rings * 2 + 2);
+        for (center, start) in [(h, FRAC_PI_2), (-h, 0.0)] {
+            for ring in 0..=rings {
+                let angle = start - FRAC_PI_2 * ring as f32 / rings as f32;
+                let mut point = ProfilePoint::on_circle(radius, 0.0, angle, 0.0);
+                point.y += center;
+                point.v = (point.y + total * 0.5) / total;
+                profile.push(point);
+            }
+        }
+        lathe(&profile, segments)
+    }
+}
+
+// Punto del perfil que se hace girar alrededor del eje Y: distancia al eje, altura, normal en
+// el plano del perfil (componente radial y vertical) y coordenada v
+#[derive(Debug, Clone, Copy)]
+struct ProfilePoint
+{
+    radius: f32,
+    y: f32,
+    normal: [f32; 2],
+    v: f32,
+}
+
+impl ProfilePoint {
+    // Punto de una circunferencia de radio `radius` con centro a `offset` del eje
+    fn on_circle(radius: f32, offset: f32, angle: f32, v: f32) -> ProfilePoint
+    {
+        let (sin, cos) = angle.sin_cos();
+        ProfilePoint {
+            // Los polos quedan exactamente sobre el eje
+            radius: if offset == 0.0 && cos.abs() < 1e-6 { 0.0 } else { offset + radius * cos },
+            y: radius * sin,
+            normal: [cos, sin],
+            v,
+        }
+    }
+}
+
+// Superficie de revolución. El perfil va de arriba abajo por el lado exterior; la columna de
+// u = 1 repite la posición de la de u = 0. Donde el perfil toca el eje hay un vértice por
+// segmento, con la u del centro del segmento y un único triángulo.
+fn lathe(profile: &[ProfilePoint], segments: usize) -> Mesh {
+    let segments = segments.max(3);
+    let direction = |u: f32| {
+        let (sin, cos) = (u * TAU).sin_cos();
+        [sin, cos]
+    };
+
+    let mut mesh = Mesh::default();
+    let mut row_starts: Vec<u32> = Vec::with_capacity(profile.len());
+    for point in profile {
+        row_starts.push(mesh.vertices.len() as u32);
+        let columns: Vec<(f32, [f32; 2])> = if point.radius == 0.0 {
+            (0..segments).map(|column| (column as f32 + 0.5) / segments as f32).map(|u| (u, direction(u))).collect()
+        } else {
+            (0..=segments)
+                .map(|column| (column as f32 / segments as f32, direction((column % segments) as f32 / segments as f32)))
+                .collect()
+        };
+        for (u, direction) in columns {
+            let position = [point.radius * direction[0], point.y, point.radius * direction[1]];
+            let normal = [point.normal[0] * direction[0], point.normal[1], point.normal[0] * direction[1]];
+            mesh.vertices.push(Vertex::new(position, normal, [u, point.v]));
+        }
+    }
+
+    for (row, pair) in profile.windows(2).enumerate() {
+        let (top, bottom) = (row_starts[row], row_starts[row + 1]);
+        for column in 0..segments as u32 {
+            let (a, b, c, d) = (bottom + column, bottom + column + 1, top + column + 1, top + column);
+            match (pair[0].radius > 0.0, pair[1].radius > 0.0) {
+                (true, true) => mesh.indices.extend_from_slice(&[a, b, c, a, c, d]),
+                (false, true) => mesh.indices.extend_from_slice(&[a, b, d]),
+                (true, false) => mesh.indices.extend_from_slice(&[a, c, d]),
+                (false, false) => (),
+            }
+        }
+    }
+
+    mesh
+}
+
+// Triángulos de una rejilla de (rows + 1) x (columns + 1) vértices que empieza en `first`, con
+// las filas creciendo hacia v y las columnas hacia u
+fn push_grid(mesh: &mut Mesh, first: u32, rows: usize, columns: usize) {
+    let stride = columns as u32 + 1;
+    for row in 0..rows as u32 {
+        for column in 0..columns as u32 {
+            let a = first + row * stride + column;
+            let (b, c, d) = (a + 1, a + stride + 1, a + stride);
+            mesh.indices.extend_from_slice(&[a, b, c, a, c, d]);
+        }
+    }
+}
+
+// Tapa circular a la altura `y`, mirando hacia arriba o hacia abajo
+fn push_disc(mesh: &mut Mesh, radius: f32, y: f32, segments: usize, up: bool) {
+    let segments = segments.max(3);
+    let normal = [0.0, if up { 1.0 } else { -1.0 }, 0.0];
+    let center = mesh.vertices.len() as u32;
+    mesh.vertices.push(Vertex::new([0.0, y, 0.0], normal, [0.5, 0.5]));
+    for column in 0..segments {
+        let (sin, cos) = (column as f32 / segments as f32 * TAU).sin_cos();
+        // Vista desde fuera, la textura no sale reflejada en ninguna de las dos tapas
+        let uv = [0.5 + sin * 0.5, 0.5 + if up { -cos } else { cos } * 0.5];
+        mesh.vertices.push(Vertex::new([radius * sin, y, radius * cos], normal, uv));
+    }
+
+    let segments = segments as u32;
+    for column in 0..segments {
+        let (a, b) = (center + 1 + column, center + 1 + (column + 1) % segments);
+        if up {
+            mesh.indices.extend_from_slice(&[center, a, b]);
+        } else {
+            mesh.indices.extend_from_slice(&[center, b, a]);
+        }
+    }
+}
@@ -0,0 +1,553 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap},
+};
+
+use cgmath::{InnerSpace, Vector3};
+
+use super::{float_key, Mesh};
+
+// Peso de los planos que sujetan bordes y costuras frente a los de las caras
+const BOUNDARY_WEIGHT: f64 = 10.0;
+// Coseno mínimo entre la normal de un triángulo antes y después de un colapso; por debajo el
+// triángulo se doblaría sobre sus vecinos
+const MIN_NORMAL_DOT: f64 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimplifyOptions
+{
+    // Se deja de colapsar al llegar a este número de triángulos...
+    pub target_triangle_count: usize,
+    // ...o cuando el siguiente colapso movería la superficie más que esta distancia, relativa al
+    // lado más largo de la caja de la malla
+    pub max_error: f32,
+}
+
+impl Default for SimplifyOptions {
+    fn default() -> SimplifyOptions
+    {
+        SimplifyOptions {
+            target_triangle_count: 0,
+            max_error: 0.01,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LodOptions
+{
+    // Niveles además del original
+    pub levels: usize,
+    // Fracción de los triángulos del nivel anterior que se intenta conservar en cada nivel
+    pub reduction: f32,
+    // Error máximo (relativo, como en SimplifyOptions) del último nivel
+    pub max_error: f32,
+}
+
+impl Default for LodOptions {
+    fn default() -> LodOptions
+    {
+        LodOptions {
+            levels: 4,
+            reduction: 0.5,
+            max_error: 0.05,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lod
+{
+    pub mesh: Mesh,
+    // Error estimado respecto a la malla original, relativo a su tamaño
+    pub error: f32,
+}
+
+impl Mesh {
+    // Reduce la malla colapsando aristas según el error cuadrático (Garland-Heckbert). Los
+    // vértices conservan sus atributos: cada colapso lleva un vértice sobre uno de sus vecinos,
+    // así que las costuras de UV y normales y los bordes abiertos solo se recorren a lo largo
+    // de sí mismos y sus esquinas no se mueven.
+    pub fn simplify(&self, options: &SimplifyOptions) -> Lod
+    {
+        let mut simplifier = Simplifier::new(self);
+        simplifier.collapse_until(options.target_triangle_count, options.max_error as f64);
+        simplifier.lod()
+    }
+
+    // Cadena de niveles de detalle: el primero es la malla original y cada uno tiene como
+    // mucho `reduction` veces los triángulos del anterior. Termina antes si ya no se puede
+    // simplificar sin superar el error máximo.
+    pub fn lod_chain(&self, options: &LodOptions) -> Vec<Lod>
+    {
+        let mut lods = vec![Lod {
+            mesh: self.clone(),
+            error: 0.0,
+        }];
+
+        // Todos los niveles salen del mismo proceso de colapsos, así que cada uno es una
+        // simplificación del anterior y el error crece de forma monótona
+        let mut simplifier = Simplifier::new(self);
+        let mut target = self.triangle_count();
+        for _ in 0..options.levels {
+            target = (target as f32 * options.reduction) as usize;
+            let before = simplifier.triangle_count;
+            simplifier.collapse_until(target, options.max_error as f64);
+            if simplifier.triangle_count == before {
+                break;
+            }
+            lods.push(simplifier.lod());
+        }
+
+        lods
+    }
+}
+
+// Forma cuadrática simétrica 4x4 (solo la mitad superior) y el área de las caras que la forman
+#[derive(Debug, Clone, Copy, Default)]
+struct Quadric
+{
+    a: [f64; 10],
+    area: f64,
+}
+
+impl Quadric {
+    // Distancia al cuadrado al plano que pasa por `point` con normal unitaria `normal`
+    fn plane(normal: Vector3<f64>, point: Vector3<f64>, weight: f64) -> Quadric
+    {
+        let d = -normal.dot(point);
+        let [x, y, z] = [normal.x, normal.y, normal.z];
+        Quadric {
+            a: [x * x, x * y, x * z, x * d, y * y, y * z, y * d, z * z, z * d, d * d].map(|value| value * weight),
+            area: weight,
+        }
+    }
+
+    fn add(&mut self, other: &Quadric)
+    {
+        for (a, b) in self.a.iter_mut().zip(other.a) {
+            *a += b;
+        }
+        self.area += other.area;
+    }
+
+    fn error(&self, p: Vector3<f64>) -> f64
+    {
+        let [xx, xy, xz, xd, yy, yz, yd, zz, zd, dd] = self.a;
+        let (x, y, z) = (p.x, p.y, p.z);
+        x * x * xx + y * y * yy + z * z * zz + 2.0 * (x * y * xy + x * z * xz + y * z * yz + x * xd + y * yd + z * zd) + dd
+    }
+}
+
+// Triángulo que usa una arista y sus dos vértices, en el orden de las posiciones de la clave
+type EdgeUse = (usize, [u32; 2]);
+
+#[derive(Debug, Clone, Copy)]
+struct Candidate
+{
+    cost: f64,
+    from: u32,
+    to: u32,
+    version: u32,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Candidate) -> bool
+    {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Candidate) -> Option<Ordering>
+    {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Candidate) -> Ordering
+    {
+        self.cost.total_cmp(&other.cost).then(self.from.cmp(&other.from)).then(self.to.cmp(&other.to))
+    }
+}
+
+// Estado de la simplificación. Los colapsos se deciden por posición (los vértices que solo
+// se diferencian en normal o UV son copias de la misma posición) y se aplican a las copias.
+struct Simplifier<'a>
+{
+    source: &'a Mesh,
+    // Posición a la que pertenece cada vértice
+    vertex_position: Vec<u32>,
+    positions: Vec<Vector3<f64>>,
+    quadrics: Vec<Quadric>,
+    border: Vec<bool>,
+    locked: Vec<bool>,
+    collapsed: Vec<bool>,
+    // Triángulos que usan cada posición; puede contener triángulos ya eliminados
+    position_triangles: Vec<Vec<u32>>,
+    triangles: Vec<[u32; 3]>,
+    alive: Vec<bool>,
+    triangle_count: usize,
+    version: Vec<u32>,
+    heap: BinaryHeap<Reverse<Candidate>>,
+    // Lado más largo de la caja, para que el error sea relativo
+    scale: f64,
+    error: f64,
+}
+
+impl<'a> Simplifier<'a> {
+    fn new(source: &'a Mesh) -> Simplifier<'a>
+    {
+        let mut ids: HashMap<[u32; 3], u32> = HashMap::new();
+        let mut positions: Vec<Vector3<f64>> = Vec::new();
+        let vertex_position: Vec<u32> = source
+            .vertices
+            .iter()
+            .map(|vertex| {
+                *ids.entry(float_key(vertex.position)).or_insert_with(|| {
+                    positions.push(Vector3::from(vertex.position.map(|value| value as f64)));
+                    (positions.len() - 1) as u32
+                })
+            })
+            .collect();
+
+        let triangles: Vec<[u32; 3]> = source.triangles().collect();
+        // Los triángulos con dos esquinas en la misma posición se descartan desde el principio
+        let alive: Vec<bool> = triangles
+            .iter()
+            .map(|triangle| {
+                let [a, b, c] = triangle.map(|vertex| vertex_position[vertex as usize]);
+                a != b && b != c && a != c
+            })
+            .collect();
+
+        let scale = source.bounds().map_or(0.0, |bounds| bounds.max_extent()) as f64;
+        let mut simplifier = Simplifier {
+            source,
+            vertex_position,
+            quadrics: vec![Quadric::default(); positions.len()],
+            border: vec![false; positions.len()],
+            locked: vec![false; positions.len()],
+            collapsed: vec![false; positions.len()],
+            position_triangles: vec![Vec::new(); positions.len()],
+            version: vec![0; positions.len()],
+            positions,
+            triangle_count: alive.iter().filter(|&&alive| alive).count(),
+            triangles,
+            alive,
+            heap: BinaryHeap::new(),
+            scale: if scale > 0.0 { scale } else { 1.0 },
+            error: 0.0,
+        };
+        simplifier.classify();
+
+        for position in 0..simplifier.positions.len() as u32 {
+            simplifier.push_candidate(position);
+        }
+        simplifier
+    }
+
+    // Cuádricas de las caras, bordes y costuras, y qué posiciones no se pueden mover
+    fn classify(&mut self)
+    {
+        // Aristas entre posiciones, con el triángulo y los vértices que las forman
+        let mut edges: HashMap<(u32, u32), Vec<EdgeUse>> = HashMap::new();
+        for (t, triangle) in self.triangles.iter().enumerate() {
+            if !self.alive[t] {
+                continue;
+            }
+
+            let [a, b, c] = triangle.map(|vertex| self.positions[self.vertex_position[vertex as usize] as usize]);
+            let cross = (b - a).cross(c - a);
+            let area = cross.magnitude() * 0.5;
+            if area > 0.0 {
+                let face = Quadric::plane(cross.normalize(), a, area);
+                for vertex in triangle {
+                    self.quadrics[self.vertex_position[*vertex as usize] as usize].add(&face);
+                }
+            }
+
+            for k in 0..3 {
+                let (va, vb) = (triangle[k], triangle[(k + 1) % 3]);
+                let (pa, pb) = (self.vertex_position[va as usize], self.vertex_position[vb as usize]);
+                self.position_triangles[pa as usize].push(t as u32);
+                let (key, pair) = if pa < pb { ((pa, pb), [va, vb]) } else { ((pb, pa), [vb, va]) };
+                edges.entry(key).or_default().push((t, pair));
+            }
+        }
+
+        let mut border_edges: Vec<u32> = vec![0; self.positions.len()];
+        for (&(pa, pb), uses) in edges.iter() {
+            let boundary = match uses.len() {
+                1 => {
+                    for p in [pa, pb] {
+                        self.border[p as usize] = true;
+                        border_edges[p as usize] += 1;
+                    }
+                    true
+                }
+                // Costura: la misma arista con vértices distintos a cada lado
+                2 => uses[0].1 != uses[1].1,
+                // Aristas no variedad: sus extremos se quedan donde están
+                _ => {
+                    self.locked[pa as usize] = true;
+                    self.locked[pb as usize] = true;
+                    false
+                }
+            };
+
+            if boundary {
+                // Plano perpendicular a cada cara que contiene la arista, para que el borde o
+                // la costura no se desplace hacia los lados
+                for &(t, _) in uses.iter() {
+                    let [a, b, c] = self.triangles[t].map(|vertex| self.positions[self.vertex_position[vertex as usize] as usize]);
+                    let start = self.positions[pa as usize];
+                    let edge = self.positions[pb as usize] - start;
+                    let normal = edge.cross((b - a).cross(c - a));
+                    if normal.magnitude2() > 0.0 {
+                        let constraint = Quadric {
+                            area: 0.0,
+                            ..Quadric::plane(normal.normalize(), start, edge.magnitude2() * BOUNDARY_WEIGHT)
+                        };
+                        self.quadrics[pa as usize].add(&constraint);
+                        self.quadrics[pb as usize].add(&constraint);
+                    }
+                }
+            }
+        }
+
+        // Un vértice con más de dos aristas de borde une varias partes de la malla por un punto
+        for (p, &count) in border_edges.iter().enumerate() {
+            if count > 2 {
+                self.locked[p] = true;
+            }
+        }
+    }
+
+    fn triangle_positions(&self, t: u32) -> [u32; 3]
+    {
+        self.triangles[t as usize].map(|vertex| self.vertex_position[vertex as usize])
+    }
+
+    fn live_triangles(&self, position: u32) -> impl Iterator<Item = u32> + '_
+    {
+        self.position_triangles[position as usize].iter().copied().filter(|&t| self.alive[t as usize])
+    }
+
+    // Posiciones unidas a `position` por una arista
+    fn neighbours(&self, position: u32) -> Vec<u32>
+    {
+        let mut neighbours: Vec<u32> = self
+            .live_triangles(position)
+            .flat_map(|t| self.triangle_positions(t))
+            .filter(|&p| p != position)
+            .collect();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        neighbours
+    }
+
+    // Comprueba si `from` puede colapsar sobre `to` y devuelve a qué copia de `to` pasa cada
+    // copia de `from`
+    fn collapse_plan(&self, from: u32, to: u32) -> Option<Vec<(u32, u32)>>
+    {
+        if self.locked[from as usize] {
+            return None;
+        }
+
+        let mut shared = 0;
+        let mut plan: Vec<(u32, u32)> = Vec::new();
+        for t in self.live_triangles(from) {
+            let positions = self.triangle_positions(t);
+            let Some(k_to) = positions.iter().position(|&p| p == to) else { continue };
+            shared += 1;
+            let k_from = positions.iter().position(|&p| p == from).unwrap();
+            let (copy, target) = (self.triangles[t as usize][k_from], self.triangles[t as usize][k_to]);
+            match plan.iter().find(|&&(c, _)| c == copy) {
+                Some(&(_, existing)) if existing != target => return None,
+                Some(_) => (),
+                None => plan.push((copy, target)),
+            }
+        }
+
+        // Un vértice de borde solo avanza por el borde y uno interior necesita los dos
+        // triángulos de la arista
+        if shared != if self.border[from as usize] { 1 } else { 2 } {
+            return None;
+        }
+
+        // Cada copia de `from` tiene que llegar a `to` por su lado de la costura; si no, el
+        // colapso rompería la costura
+        for t in self.live_triangles(from) {
+            for (k, p) in self.triangle_positions(t).into_iter().enumerate() {
+                let copy = self.triangles[t as usize][k];
+                if p == from && !plan.iter().any(|&(c, _)| c == copy) {
+                    return None;
+                }
+            }
+        }
+
+        // Condición de enlace: los únicos vecinos comunes son los vértices opuestos a la
+        // arista; si hay más, el colapso crearía aristas no variedad
+        let theirs = self.neighbours(to);
+        let common = self.neighbours(from).iter().filter(|p| theirs.binary_search(p).is_ok()).count();
+        if common != shared {
+            return None;
+        }
+
+        // Ningún triángulo que queda puede darse la vuelta
+        let target = self.positions[to as usize];
+        for t in self.live_triangles(from) {
+            let positions = self.triangle_positions(t);
+            if positions.contains(&to) {
+                continue;
+            }
+            let before = positions.map(|p| self.positions[p as usize]);
+            let after = positions.map(|p| if p == from { target } else { self.positions[p as usize] });
+            let old_normal = (before[1] - before[0]).cross(before[2] - before[0]);
+            let new_normal = (after[1] - after[0]).cross(after[2] - after[0]);
+            if new_normal.magnitude2() <= 0.0 {
+                return None;
+            }
+            if old_normal.magnitude2() > 0.0 && old_normal.normalize().dot(new_normal.normalize()) < MIN_NORMAL_DOT {
+                return None;
+            }
+        }
+
+        Some(plan)
+    }
+
+    // Distancia media estimada entre la superficie original y la que queda tras el colapso
+    fn cost(&self, from: u32, to: u32) -> f64
+    {
+        let mut quadric = self.quadrics[from as usize];
+        quadric.add(&self.quadrics[to as usize]);
+        let area = if quadric.area > 0.0 { quadric.area } else { 1.0 };
+        (quadric.error(self.positions[to as usize]).max(0.0) / area).sqrt() / self.scale
+    }
+
+    // Mejor colapso de una posición hacia alguno de sus vecinos
+    fn push_candidate(&mut self, from: u32)
+    {
+        if self.collapsed[from as usize] || self.locked[from as usize] {
+            return;
+        }
+
+        // Comprobar si un colapso es válido cuesta más que calcular su error, así que se
+        // comprueban de menor a mayor error hasta encontrar uno
+        let mut options: Vec<(f64, u32)> = self.neighbours(from).into_iter().map(|to| (self.cost(from, to), to)).collect();
+        options.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let best = options.into_iter().find(|&(_, to)| self.collapse_plan(from, to).is_some());
+
+        if let Some((cost, to)) = best {
+            self.heap.push(Reverse(Candidate {
+                cost,
+                from,
+                to,
+                version: self.version[from as usize],
+            }));
+        }
+    }
+
+    fn collapse_until(&mut self, target_triangle_count: usize, max_error: f64)
+    {
+        while self.triangle_count > target_triangle_count {
+            let Some(Reverse(candidate)) = self.heap.pop() else { break };
+            let Candidate { cost, from, to, version } = candidate;
+            if self.collapsed[from as usize] || self.collapsed[to as usize] || version != self.version[from as usize] {
+                continue;
+            }
+            // Los candidatos salen de menor a mayor coste, así que ninguno de los que quedan cabe
+            if cost > max_error {
+                self.heap.push(Reverse(candidate));
+                break;
+            }
+            let Some(plan) = self.collapse_plan(from, to) else {
+                self.version[from as usize] += 1;
+                self.push_candidate(from);
+                continue;
+            };
+
+            self.collapse(from, to, &plan);
+            self.error = self.error.max(cost);
+
+            // Los vecinos de `to` han cambiado de entorno: sus candidatos se recalculan
+            let mut affected = self.neighbours(to);
+            affected.push(to);
+            for &position in affected.iter() {
+                self.version[position as usize] += 1;
+            }
+            for position in affected {
+                self.push_candidate(position);
+            }
+        }
+    }
+
+    fn collapse(&mut self, from: u32, to: u32, plan: &[(u32, u32)])
+    {
+        let triangles = std::mem::take(&mut self.position_triangles[from as usize]);
+        for &t in triangles.iter() {
+            if !self.alive[t as usize] {
+                continue;
+            }
+            if self.triangle_positions(t).contains(&to) {
+                self.alive[t as usize] = false;
+                self.triangle_count -= 1;
+                continue;
+            }
+            for corner in self.triangles[t as usize].iter_mut() {
+                if let Some(&(_, target)) = plan.iter().find(|&&(copy, _)| copy == *corner) {
+                    *corner = target;
+                }
+            }
+            self.position_triangles[to as usize].push(t);
+        }
+        let alive = &self.alive;
+        self.position_triangles[to as usize].retain(|&t| alive[t as usize]);
+
+        let quadric = self.quadrics[from as usize];
+        self.quadrics[to as usize].add(&quadric);
+        self.collapsed[from as usize] = true;
+    }
+
+    // Malla con los triángulos que quedan, en su orden original, y solo los vértices que usan
+    fn lod(&self) -> Lod
+    {
+        let source = self.source;
+        let mut remap: Vec<Option<u32>> = vec![None; source.vertices.len()];
+        let mut used: Vec<bool> = vec![false; source.vertices.len()];
+        for (t, triangle) in self.triangles.iter().enumerate() {
+            if self.alive[t] {
+                for &vertex in triangle {
+                    used[vertex as usize] = true;
+                }
+            }
+        }
+
+        let mut mesh = Mesh::default();
+        for (vertex, _) in used.iter().enumerate().filter(|(_, &used)| used) {
+            remap[vertex] = Some(mesh.vertices.len() as u32);
+            mesh.vertices.push(source.vertices[vertex]);
+            if let Some(tangents) = &source.tangents {
+                mesh.tangents.get_or_insert_with(Vec::new).push(tangents[vertex]);
+            }
+            if let Some(colors) = &source.colors {
+                mesh.colors.get_or_insert_with(Vec::new).push(colors[vertex]);
+            }
+        }
+
+        for (t, triangle) in self.triangles.iter().enumerate() {
+            if self.alive[t] {
+                mesh.indices.extend(triangle.iter().map(|&vertex| remap[vertex as usize].unwrap()));
+            }
+        }
+
+        Lod {
+            mesh,
+            error: self.error as f32,
+        }
+    }
+}
@@ -0,0 +1,558 @@
+use std::collections::HashMap;
+
+use cgmath::{InnerSpace, Vector3};
+
+use super::{float_key, normals::NormalOptions, Mesh, Vertex};
+
+// Arista marcada como pliegue. Con sharpness >= 1 se mantiene viva durante ese número de
+// niveles (f32::INFINITY para siempre); los valores intermedios dan pliegues semiafilados.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Crease
+{
+    // Índices de vértice de la malla de partida
+    pub edge: [u32; 2],
+    pub sharpness: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SubdivisionOptions
+{
+    pub levels: usize,
+    // Las aristas entre caras que forman más de este ángulo (en grados) se tratan como
+    // pliegues afilados; también se usa al recalcular las normales
+    pub crease_angle: Option<f32>,
+    pub creases: Vec<Crease>,
+    // Solo para Catmull-Clark: número de esquinas de cada cara, en el orden de los índices,
+    // cuando se conoce (cada cara de n esquinas ocupa n - 2 triángulos consecutivos). Con None
+    // se emparejan los triángulos consecutivos.
+    pub polygons: Option<Vec<usize>>,
+}
+
+impl Mesh {
+    // Subdivisión de Loop: cada triángulo se divide en cuatro en cada nivel. Los bordes abiertos
+    // y los pliegues siguen la regla de curva B-spline, así que no encogen.
+    pub fn subdivide_loop(&self, options: &SubdivisionOptions) -> Mesh
+    {
+        let mut control = ControlMesh::from_triangles(self, options);
+        for _ in 0..options.levels {
+            control = control.loop_step();
+        }
+        control.into_mesh(self, options)
+    }
+
+    // Subdivisión de Catmull-Clark. La malla indexada solo guarda triángulos, así que las caras
+    // originales salen de `options.polygons` o, si no se indican, de cada par de triángulos
+    // consecutivos que comparten una diagonal, que es como quedan tras triangular las caras de
+    // 4 lados de un OBJ. Ese emparejamiento solo es fiable con mallas recién leídas: después
+    // de optimize() los triángulos cambian de orden, y en una malla de triángulos se unirían
+    // pares que no eran cuadriláteros. El resultado son cuadriláteros guardados como pares de
+    // triángulos, así que se puede volver a subdividir.
+    pub fn subdivide_catmull_clark(&self, options: &SubdivisionOptions) -> Mesh
+    {
+        let mut control = ControlMesh::from_polygons(self, options);
+        for _ in 0..options.levels {
+            control = control.catmull_clark_step();
+        }
+        control.into_mesh(self, options)
+    }
+}
+
+// Esquina de una cara: el punto (posición compartida entre caras) y los atributos que son
+// propios de la cara, que se interpolan linealmente dentro de ella para no mezclar los dos
+// lados de una costura
+#[derive(Debug, Clone, Copy)]
+struct Corner
+{
+    point: u32,
+    uv: [f32; 2],
+    color: [f32; 4],
+}
+
+impl Corner {
+    fn mix(corners: &[Corner], point: u32) -> Corner
+    {
+        let weight = 1.0 / corners.len() as f32;
+        let mut uv = [0.0; 2];
+        let mut color = [0.0; 4];
+        for corner in corners {
+            for (sum, value) in uv.iter_mut().zip(corner.uv) {
+                *sum += value * weight;
+            }
+            for (sum, value) in color.iter_mut().zip(corner.color) {
+                *sum += value * weight;
+            }
+        }
+        Corner { point, uv, color }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Edge
+{
+    points: [u32; 2],
+    faces: Vec<usize>,
+    sharpness: f32,
+}
+
+impl Edge {
+    // Los bordes abiertos y las aristas no variedad se comportan como pliegues permanentes.
+    // Una cara degenerada con dos esquinas en el mismo punto pasa dos veces por la misma
+    // arista, que sigue siendo un borde.
+    fn is_interior(&self) -> bool
+    {
+        matches!(self.faces[..], [f, g] if f != g)
+    }
+
+    fn sharpness(&self) -> f32
+    {
+        if self.is_interior() {
+            self.sharpness
+        } else {
+            f32::INFINITY
+        }
+    }
+}
+
+struct ControlMesh
+{
+    points: Vec<Vector3<f64>>,
+    faces: Vec<Vec<Corner>>,
+    // Afilado de las aristas marcadas, por sus puntos ordenados
+    sharpness: HashMap<(u32, u32), f32>,
+}
+
+fn edge_key(a: u32, b: u32) -> (u32, u32) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+impl ControlMesh {
+    fn from_triangles(mesh: &Mesh, options: &SubdivisionOptions) -> ControlMesh
+    {
+        let faces: Vec<Vec<u32>> = mesh.triangles().map(|triangle| triangle.to_vec()).collect();
+        ControlMesh::new(mesh, faces, options)
+    }
+
+    fn from_polygons(mesh: &Mesh, options: &SubdivisionOptions) -> ControlMesh
+    {
+        let triangles: Vec<[u32; 3]> = mesh.triangles().collect();
+        let mut faces: Vec<Vec<u32>> = Vec::with_capacity(triangles.len());
+        let mut t = 0;
+
+        // Las caras que no se pueden reconstruir (o los triángulos que no cubre la lista) se
+        // subdividen como triángulos sueltos
+        for &corners in options.polygons.iter().flatten() {
+            let count = corners.saturating_sub(2).max(1);
+            let Some(group) = triangles.get(t..t + count) else {
+                break;
+            };
+            match polygon(group) {
+                Some(face) => faces.push(face),
+                None => faces.extend(group.iter().map(|triangle| triangle.to_vec())),
+            }
+            t += count;
+        }
+        if options.polygons.is_some() {
+            faces.extend(triangles[t..].iter().map(|triangle| triangle.to_vec()));
+            return ControlMesh::new(mesh, faces, options);
+        }
+
+        while t < triangles.len() {
+            match triangles.get(t + 1).and_then(|&next| quad(triangles[t], next)) {
+                Some(quad) => {
+                    faces.push(quad.to_vec());
+                    t += 2;
+                }
+                None => {
+                    faces.push(triangles[t].to_vec());
+                    t += 1;
+                }
+            }
+        }
+        ControlMesh::new(mesh, faces, options)
+    }
+
+    fn new(mesh: &Mesh, faces: Vec<Vec<u32>>, options: &SubdivisionOptions) -> ControlMesh
+    {
+        // Los vértices que solo se diferencian en normal, UV o color comparten punto
+        let mut ids: HashMap<[u32; 3], u32> = HashMap::new();
+        let mut points: Vec<Vector3<f64>> = Vec::new();
+        let vertex_point: Vec<u32> = mesh
+            .vertices
+            .iter()
+            .map(|vertex| {
+                *ids.entry(float_key(vertex.position)).or_insert_with(|| {
+                    points.push(Vector3::from(vertex.position.map(|value| value as f64)));
+                    (points.len() - 1) as u32
+                })
+            })
+            .collect();
+
+        let faces: Vec<Vec<Corner>> = faces
+            .iter()
+            .map(|face| {
+                face.iter()
+                    .map(|&vertex| Corner {
+                        point: vertex_point[vertex as usize],
+                        uv: mesh.vertices[vertex as usize].uv,
+                        color: mesh.colors.as_ref().map_or([1.0; 4], |colors| colors[vertex as usize]),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut control = ControlMesh {
+            points,
+            faces,
+            sharpness: HashMap::new(),
+        };
+
+        for crease in options.creases.iter() {
+            let [a, b] = crease.edge.map(|vertex| vertex_point[vertex as usize]);
+            if a != b && crease.sharpness > 0.0 {
+                control.sharpness.insert(edge_key(a, b), crease.sharpness);
+            }
+        }
+
+        if let Some(angle) = options.crease_angle {
+            let crease_cos = (angle as f64).to_radians().cos();
+            let normals: Vec<Vector3<f64>> = (0..control.faces.len()).map(|face| control.face_normal(face)).collect();
+            for edge in control.edges() {
+                if let [a, b] = edge.faces[..] {
+                    if normals[a].dot(normals[b]) < crease_cos {
+                        control.sharpness.insert(edge_key(edge.points[0], edge.points[1]), f32::INFINITY);
+                    }
+                }
+            }
+        }
+
+        control
+    }
+
+    // Normal de Newell, válida también para caras de más de tres lados no planas
+    fn face_normal(&self, face: usize) -> Vector3<f64>
+    {
+        let corners = &self.faces[face];
+        let mut normal = Vector3::new(0.0, 0.0, 0.0);
+        for (k, corner) in corners.iter().enumerate() {
+            let a = self.points[corner.point as usize];
+            let b = self.points[corners[(k + 1) % corners.len()].point as usize];
+            normal += a.cross(b);
+        }
+        if normal.magnitude2() > 0.0 {
+            normal.normalize()
+        } else {
+            normal
+        }
+    }
+
+    fn edges(&self) -> Vec<Edge>
+    {
+        let mut index: HashMap<(u32, u32), usize> = HashMap::new();
+        let mut edges: Vec<Edge> = Vec::new();
+        for (f, face) in self.faces.iter().enumerate() {
+            for (k, corner) in face.iter().enumerate() {
+                let key = edge_key(corner.point, face[(k + 1) % face.len()].point);
+                let e = *index.entry(key).or_insert_with(|| {
+                    edges.push(Edge {
+                        points: [key.0, key.1],
+                        faces: Vec::new(),
+                        sharpness: self.sharpness.get(&key).copied().unwrap_or(0.0),
+                    });
+                    edges.len() - 1
+                });
+                edges[e].faces.push(f);
+            }
+        }
+        edges
+    }
+
+    // Aristas que llegan a cada punto
+    fn point_edges(&self, edges: &[Edge]) -> Vec<Vec<usize>>
+    {
+        let mut point_edges: Vec<Vec<usize>> = vec![Vec::new(); self.points.len()];
+        for (e, edge) in edges.iter().enumerate() {
+            for point in edge.points {
+                point_edges[point as usize].push(e);
+            }
+        }
+        point_edges
+    }
+
+    // Nueva posición de un punto a partir de la que daría la regla suave. Con dos aristas
+    // afiladas se sigue la curva del pliegue, con más el punto es una esquina y no se mueve, y
+    // con un afilado medio menor que 1 se mezcla con la posición suave.
+    fn vertex_rule(&self, point: u32, smooth: Vector3<f64>, edges: &[Edge], incident: &[usize]) -> Vector3<f64>
+    {
+        let sharp: Vec<&Edge> = incident.iter().map(|&e| &edges[e]).filter(|edge| edge.sharpness() > 0.0).collect();
+        let position = self.points[point as usize];
+        let sharp_position = match sharp.len() {
+            0 | 1 => return smooth,
+            // Un punto con solo dos aristas y las dos afiladas (la esquina de una malla
+            // abierta) también es una esquina
+            2 if incident.len() == 2 => position,
+            2 => {
+                let other = |edge: &Edge| self.points[edge.points[(edge.points[0] == point) as usize] as usize];
+                position * 0.75 + (other(sharp[0]) + other(sharp[1])) * 0.125
+            }
+            _ => position,
+        };
+
+        let sharpness = sharp.iter().map(|edge| edge.sharpness() as f64).sum::<f64>() / sharp.len() as f64;
+        if sharpness >= 1.0 {
+            sharp_position
+        } else {
+            smooth + (sharp_position - smooth) * sharpness
+        }
+    }
+
+    fn edge_rule(&self, edge: &Edge, smooth: Vector3<f64>) -> Vector3<f64>
+    {
+        let sharpness = edge.sharpness() as f64;
+        if sharpness <= 0.0 {
+            return smooth;
+        }
+        let middle = (self.points[edge.points[0] as usize] + self.points[edge.points[1] as usize]) * 0.5;
+        if sharpness >= 1.0 {
+            middle
+        } else {
+            smooth + (middle - smooth) * sharpness
+        }
+    }
+
+    // Cada mitad de una arista afilada hereda su afilado menos uno
+    fn child_sharpness(edges: &[Edge], first_edge_point: u32) -> HashMap<(u32, u32), f32>
+    {
+        let mut sharpness = HashMap::new();
+        for (e, edge) in edges.iter().enumerate() {
+            let child = edge.sharpness - 1.0;
+            if edge.sharpness > 0.0 && child > 0.0 {
+                let middle = first_edge_point + e as u32;
+                for point in edge.points {
+                    sharpness.insert(edge_key(point, middle), child);
+                }
+            }
+        }
+        sharpness
+    }
+
+    fn loop_step(&self) -> ControlMesh
+    {
+        let edges = self.edges();
+        let point_edges = self.point_edges(&edges);
+        let edge_index: HashMap<(u32, u32), usize> =
+            edges.iter().enumerate().map(|(e, edge)| ((edge.points[0], edge.points[1]), e)).collect();
+
+        // Puntos de vértice, seguidos de un punto por arista
+        let mut points: Vec<Vector3<f64>> = Vec::with_capacity(self.points.len() + edges.len());
+        for (p, incident) in point_edges.iter().enumerate() {
+            let position = self.points[p];
+            let n = incident.len();
+            let smooth = if n == 0 {
+                position
+            } else {
+                let sum = incident.iter().fold(Vector3::new(0.0, 0.0, 0.0), |sum, &e| {
+                    let edge = &edges[e];
+                    sum + self.points[edge.points[(edge.points[0] == p as u32) as usize] as usize]
+                });
+                // Pesos de Warren
+                let beta = if n == 3 { 3.0 / 16.0 } else { 3.0 / (8.0 * n as f64) };
+                position * (1.0 - n as f64 * beta) + sum * beta
+            };
+            points.push(self.vertex_rule(p as u32, smooth, &edges, incident));
+        }
+
+        for edge in edges.iter() {
+            let [a, b] = edge.points.map(|point| self.points[point as usize]);
+            let smooth = match edge.faces[..] {
+                [f, g] if edge.is_interior() => {
+                    let opposite = |face: usize| {
+                        let corner = self.faces[face].iter().find(|corner| !edge.points.contains(&corner.point)).unwrap();
+                        self.points[corner.point as usize]
+                    };
+                    (a + b) * 0.375 + (opposite(f) + opposite(g)) * 0.125
+                }
+                _ => (a + b) * 0.5,
+            };
+            points.push(self.edge_rule(edge, smooth));
+        }
+
+        let first_edge_point = self.points.len() as u32;
+        let mut faces: Vec<Vec<Corner>> = Vec::with_capacity(self.faces.len() * 4);
+        for face in self.faces.iter() {
+            // Esquina en el punto medio de cada lado, con los atributos de la cara
+            let middle: Vec<Corner> = (0..3)
+                .map(|k| {
+                    let (a, b) = (face[k], face[(k + 1) % 3]);
+                    let e = edge_index[&edge_key(a.point, b.point)];
+                    Corner::mix(&[a, b], first_edge_point + e as u32)
+                })
+                .collect();
+            for k in 0..3 {
+                faces.push(vec![face[k], middle[k], middle[(k + 2) % 3]]);
+            }
+            faces.push(middle);
+        }
+
+        ControlMesh {
+            points,
+            faces,
+            sharpness: ControlMesh::child_sharpness(&edges, first_edge_point),
+        }
+    }
+
+    fn catmull_clark_step(&self) -> ControlMesh
+    {
+        let edges = self.edges();
+        let point_edges = self.point_edges(&edges);
+        let edge_index: HashMap<(u32, u32), usize> =
+            edges.iter().enumerate().map(|(e, edge)| ((edge.points[0], edge.points[1]), e)).collect();
+
+        let face_points: Vec<Vector3<f64>> = self
+            .faces
+            .iter()
+            .map(|face| face.iter().fold(Vector3::new(0.0, 0.0, 0.0), |sum, corner| sum + self.points[corner.point as usize]) / face.len() as f64)
+            .collect();
+
+        // Puntos de vértice, luego uno por arista y por último uno por cara
+        let mut points: Vec<Vector3<f64>> = Vec::with_capacity(self.points.len() + edges.len() + self.faces.len());
+        for (p, incident) in point_edges.iter().enumerate() {
+            let position = self.points[p];
+            let n = incident.len() as f64;
+            let mut faces: Vec<usize> = incident.iter().flat_map(|&e| edges[e].faces.iter().copied()).collect();
+            faces.sort_unstable();
+            faces.dedup();
+            let smooth = if incident.is_empty() || faces.is_empty() {
+                position
+            } else {
+                let q = faces.iter().fold(Vector3::new(0.0, 0.0, 0.0), |sum, &f| sum + face_points[f]) / faces.len() as f64;
+                let r = incident.iter().fold(Vector3::new(0.0, 0.0, 0.0), |sum, &e| {
+                    let edge = &edges[e];
+                    sum + (self.points[edge.points[0] as usize] + self.points[edge.points[1] as usize]) * 0.5
+                }) / n;
+                (q + r * 2.0 + position * (n - 3.0)) / n
+            };
+            points.push(self.vertex_rule(p as u32, smooth, &edges, incident));
+        }
+
+        for edge in edges.iter() {
+            let [a, b] = edge.points.map(|point| self.points[point as usize]);
+            let smooth = match edge.faces[..] {
+                [f, g] if edge.is_interior() => (a + b + face_points[f] + face_points[g]) * 0.25,
+                _ => (a + b) * 0.5,
+            };
+            points.push(self.edge_rule(edge, smooth));
+        }
+
+        let first_edge_point = self.points.len() as u32;
+        let first_face_point = first_edge_point + edges.len() as u32;
+        points.extend_from_slice(&face_points);
+
+        // Un cuadrilátero por esquina: esquina, mitad del lado siguiente, centro, mitad del anterior
+        let mut faces: Vec<Vec<Corner>> = Vec::with_capacity(self.faces.iter().map(|face| face.len()).sum());
+        for (f, face) in self.faces.iter().enumerate() {
+            let n = face.len();
+            let center = Corner::mix(face, first_face_point + f as u32);
+            let middle: Vec<Corner> = (0..n)
+                .map(|k| {
+                    let (a, b) = (face[k], face[(k + 1) % n]);
+                    let e = edge_index[&edge_key(a.point, b.point)];
+                    Corner::mix(&[a, b], first_edge_point + e as u32)
+                })
+                .collect();
+            for k in 0..n {
+                faces.push(vec![face[k], middle[k], center, middle[(k + n - 1) % n]]);
+            }
+        }
+
+        ControlMesh {
+            points,
+            faces,
+            sharpness: ControlMesh::child_sharpness(&edges, first_edge_point),
+        }
+    }
+
+    // Malla indexada con normales nuevas; las caras de más de tres lados se guardan como
+    // triángulos consecutivos que comparten la diagonal desde la primera esquina
+    fn into_mesh(self, source: &Mesh, options: &SubdivisionOptions) -> Mesh
+    {
+        let mut mesh = Mesh::default();
+        let mut colors: Vec<[f32; 4]> = Vec::new();
+        let mut welded: HashMap<(u32, [u32; 2], [u32; 4]), u32> = HashMap::new();
+
+        for face in self.faces.iter() {
+            let indices: Vec<u32> = face
+                .iter()
+                .map(|corner| {
+                    *welded.entry((corner.point, float_key(corner.uv), float_key(corner.color))).or_insert_with(|| {
+                        let position = self.points[corner.point as usize];
+                        let position = [position.x as f32, position.y as f32, position.z as f32];
+                        mesh.vertices.push(Vertex::new(position, [0.0; 3], corner.uv));
+                        colors.push(corner.color);
+                        (mesh.vertices.len() - 1) as u32
+                    })
+                })
+                .collect();
+            for k in 1..indices.len() - 1 {
+                mesh.indices.extend_from_slice(&[indices[0], indices[k], indices[k + 1]]);
+            }
+        }
+
+        if source.colors.is_some() {
+            mesh.colors = Some(colors);
+        }
+        mesh.compute_smooth_normals(&NormalOptions {
+            crease_angle: options.crease_angle,
+            ..NormalOptions::default()
+        });
+        if source.tangents.is_some() {
+            mesh.compute_tangents();
+        }
+        mesh
+    }
+}
+
+// Cuadrilátero formado por dos triángulos que comparten una arista recorrida en sentidos
+// opuestos, en el sentido de giro de ambos
+fn quad(first: [u32; 3], second: [u32; 3]) -> Option<[u32; 4]> {
+    for k in 0..3 {
+        let (x, y) = (first[k], first[(k + 1) % 3]);
+        let z = first[(k + 2) % 3];
+        for j in 0..3 {
+            if second[j] == y && second[(j + 1) % 3] == x {
+                let d = second[(j + 2) % 3];
+                if d == z || first.contains(&d) {
+                    return None;
+                }
+                return Some([y, z, x, d]);
+            }
+        }
+    }
+    None
+}
+
+// Contorno de un polígono a partir de sus triángulos: las aristas que no comparten con otro
+// triángulo del grupo, encadenadas en el sentido de giro. None si no forman un solo contorno
+// con una esquina más por triángulo.
+fn polygon(triangles: &[[u32; 3]]) -> Option<Vec<u32>> {
+    let edges: Vec<(u32, u32)> = triangles.iter().flat_map(|t| [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])]).collect();
+    let mut next: HashMap<u32, u32> = HashMap::new();
+    for &(a, b) in edges.iter() {
+        if !edges.contains(&(b, a)) && next.insert(a, b).is_some() {
+            return None;
+        }
+    }
+
+    let sides = triangles.len() + 2;
+    let start = *next.keys().min()?;
+    let mut face = vec![start];
+    let mut corner = next[&start];
+    while corner != start && face.len() <= sides {
+        face.push(corner);
+        corner = *next.get(&corner)?;
+    }
+    (face.len() == sides && next.len() == sides).then_some(face)
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use cgmath::{InnerSpace, Vector3};
+
+use super::Mesh;
+
+impl Mesh {
+    // Calcula un tangente por vértice (xyz + signo del bitangente en w) a partir de las UVs,
+    // siguiendo las reglas de MikkTSpace: tangentes proyectados sobre el plano de la normal,
+    // ponderados por ángulo y sin mezclar esquinas con distinta orientación de UVs ni abanicos
+    // que no están unidos por una arista. Los vértices con más de un grupo se duplican.
+    pub fn compute_tangents(&mut self)
+    {
+        let corner_count = self.indices.len();
+        let mut corner_tangents: Vec<Vector3<f32>> = vec![Vector3::new(0.0, 0.0, 0.0); corner_count];
+        let mut corner_weights: Vec<f32> = vec![0.0; corner_count];
+        let mut corner_orientation: Vec<bool> = vec![true; corner_count];
+        let mut degenerate: Vec<bool> = vec![false; self.triangle_count()];
+
+        for t in 0..self.triangle_count() {
+            let vertices = self.triangle(t).map(|i| self.vertices[i as usize]);
+            let p = vertices.map(|v| Vector3::from(v.position));
+            let uv = vertices.map(|v| v.uv);
+
+            let (s1, t1) = (uv[1][0] - uv[0][0], uv[1][1] - uv[0][1]);
+            let (s2, t2) = (uv[2][0] - uv[0][0], uv[2][1] - uv[0][1]);
+            let d1 = p[1] - p[0];
+            let d2 = p[2] - p[0];
+
+            let signed_area = s1 * t2 - s2 * t1;
+            let orientation = signed_area > 0.0;
+
+            // Dirección de la u creciente sobre el triángulo (sin dividir por el área, como MikkTSpace)
+            let mut face_tangent = d1 * t2 - d2 * t1;
+            if signed_area < 0.0 {
+                face_tangent = -face_tangent;
+            }
+
+            for k in 0..3 {
+                let corner = t * 3 + k;
+                corner_orientation[corner] = orientation;
+                degenerate[corner / 3] = signed_area == 0.0;
+
+                let normal = Vector3::from(vertices[k].normal);
+                let projected = face_tangent - normal * normal.dot(face_tangent);
+                if projected.magnitude2() <= 0.0 || signed_area == 0.0 {
+                    continue;
+                }
+
+                // Ángulo de la esquina medido sobre el plano tangente
+                let e1 = p[(k + 1) % 3] - p[k];
+                let e2 = p[(k + 2) % 3] - p[k];
+                let e1 = e1 - normal * normal.dot(e1);
+                let e2 = e2 - normal * normal.dot(e2);
+                let lengths = e1.magnitude() * e2.magnitude();
+                let angle = if lengths > 0.0 { (e1.dot(e2) / lengths).clamp(-1.0, 1.0).acos() } else { 0.0 };
+
+                corner_tangents[corner] = projected.normalize();
+                corner_weights[corner] = angle;
+            }
+        }
+
+        // Como en MikkTSpace, dos esquinas del mismo vértice solo se suavizan entre sí si sus
+        // triángulos tienen la misma orientación y están unidos por aristas que pasan por el
+        // vértice: dos abanicos que solo se tocan en el vértice quedan separados
+        let mut groups: Vec<usize> = (0..corner_count).collect();
+        fn find(groups: &mut [usize], mut corner: usize) -> usize {
+            while groups[corner] != corner {
+                groups[corner] = groups[groups[corner]];
+                corner = groups[corner];
+            }
+            corner
+        }
+        let mut edges: HashMap<(u32, u32, bool), Vec<usize>> = HashMap::new();
+        for t in 0..self.triangle_count() {
+            if degenerate[t] {
+                continue;
+            }
+            for k in 0..3 {
+                let (a, b) = (self.indices[t * 3 + k], self.indices[t * 3 + (k + 1) % 3]);
+                edges.entry((a.min(b), a.max(b), corner_orientation[t * 3])).or_default().push(t);
+            }
+        }
+        let corner_of = |indices: &[u32], t: usize, vertex: u32| (t * 3..t * 3 + 3).find(|&corner| indices[corner] == vertex);
+        for ((a, b, _), triangles) in edges.iter() {
+            for pair in triangles.windows(2) {
+                for vertex in [*a, *b] {
+                    if let (Some(first), Some(second)) = (corner_of(&self.indices, pair[0], vertex), corner_of(&self.indices, pair[1], vertex)) {
+                        let (first, second) = (find(&mut groups, first), find(&mut groups, second));
+                        groups[second] = first;
+                    }
+                }
+            }
+        }
+
+        // Las esquinas de triángulos con UVs degeneradas no forman grupo: toman el del primer
+        // triángulo válido que comparte el vértice (o, si no hay ninguno, todas el de la primera)
+        let mut first_valid: HashMap<u32, usize> = HashMap::new();
+        for corner in 0..corner_count {
+            let valid = !degenerate[corner / 3];
+            let entry = first_valid.entry(self.indices[corner]).or_insert(corner);
+            if valid && degenerate[*entry / 3] {
+                *entry = corner;
+            }
+        }
+        let group_of: Vec<usize> = (0..corner_count)
+            .map(|corner| match first_valid.get(&self.indices[corner]) {
+                Some(&valid) if degenerate[corner / 3] => find(&mut groups, valid),
+                _ => find(&mut groups, corner),
+            })
+            .collect();
+
+        // Cada grupo distinto del primero de un vértice crea un vértice nuevo
+        let mut sums: HashMap<usize, (Vector3<f32>, u32, bool)> = HashMap::new();
+        let mut used: Vec<bool> = vec![false; self.vertices.len()];
+        for corner in 0..corner_count {
+            let vertex = self.indices[corner];
+            let group = group_of[corner];
+            let orientation = corner_orientation[group] || degenerate[group / 3];
+            let entry = sums.entry(group).or_insert_with(|| {
+                let target = if used[vertex as usize] {
+                    self.vertices.push(self.vertices[vertex as usize]);
+                    if let Some(colors) = self.colors.as_mut() {
+                        colors.push(colors[vertex as usize]);
+                    }
+                    (self.vertices.len() - 1) as u32
+                } else {
+                    used[vertex as usize] = true;
+                    vertex
+                };
+                (Vector3::new(0.0, 0.0, 0.0), target, orientation)
+            });
+            entry.0 += corner_tangents[corner] * corner_weights[corner];
+            self.indices[corner] = entry.1;
+        }
+
+        let mut tangents: Vec<[f32; 4]> = vec![[1.0, 0.0, 0.0, 1.0]; self.vertices.len()];
+        for (sum, vertex, orientation) in sums.into_values() {
+            let normal = Vector3::from(self.vertices[vertex as usize].normal);
+            let sign = if orientation { 1.0 } else { -1.0 };
+
+            let tangent = if sum.magnitude2() > 0.0 {
+                sum.normalize()
+            } else {
+                // Vértice sin ningún triángulo con UVs válidas: cualquier dirección perpendicular
+                // a la normal
+                let helper = if normal.x.abs() < 0.9 { Vector3::unit_x() } else { Vector3::unit_y() };
+                let tangent = helper - normal * normal.dot(helper);
+                if tangent.magnitude2() > 0.0 { tangent.normalize() } else { Vector3::unit_x() }
+            };
+
+            tangents[vertex as usize] = [tangent.x, tangent.y, tangent.z, sign];
+        }
+
+        self.tangents = Some(tangents);
+    }
+
+    // Bitangente reconstruido como en el shader: signo * (normal x tangente)
+    pub fn bitangent(&self, vertex: usize) -> Option<[f32; 3]>
+    {
+        let tangent = self.tangents.as_ref()?[vertex];
+        let normal = Vector3::from(self.vertices[vertex].normal);
+        let bitangent = normal.cross(Vector3::new(tangent[0], tangent[1], tangent[2])) * tangent[3];
+        Some(bitangent.into())
+    }
+}
@@ -0,0 +1,200 @@
+use std::{collections::HashMap, ops::Range};
+
+use cgmath::{InnerSpace, Matrix4, Vector3, Vector4};
+
+use super::{float_key, Mesh};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis
+{
+    X,
+    Y,
+    Z,
+}
+
+impl Mesh {
+    // Aplica una transformación afín a posiciones, normales y tangentes. Las normales se
+    // transforman con la inversa traspuesta para que sigan siendo perpendiculares a la
+    // superficie con escalas no uniformes. Si la transformación refleja la malla (determinante
+    // negativo) se invierte el orden de los triángulos para que las caras sigan hacia fuera.
+    pub fn transform(&mut self, matrix: &Matrix4<f32>)
+    {
+        let [x, y, z] = [matrix.x.truncate(), matrix.y.truncate(), matrix.z.truncate()];
+        let determinant = x.dot(y.cross(z));
+        // Matriz de cofactores: la inversa traspuesta multiplicada por el determinante, que
+        // también existe cuando la matriz no es invertible
+        let cofactors = [y.cross(z), z.cross(x), x.cross(y)];
+        let sign = if determinant < 0.0 { -1.0 } else { 1.0 };
+        let normal_matrix = cofactors.map(|column| column * sign);
+
+        let linear = |vector: [f32; 3]| x * vector[0] + y * vector[1] + z * vector[2];
+        let normalized = |vector: Vector3<f32>| if vector.magnitude2() > 0.0 { vector.normalize() } else { vector };
+        for vertex in self.vertices.iter_mut() {
+            let position = matrix * Vector4::new(vertex.position[0], vertex.position[1], vertex.position[2], 1.0);
+            vertex.position = position.truncate().into();
+            let normal = normal_matrix[0] * vertex.normal[0] + normal_matrix[1] * vertex.normal[1] + normal_matrix[2] * vertex.normal[2];
+            vertex.normal = normalized(normal).into();
+        }
+        // Los tangentes siguen a la superficie como las posiciones; al reflejar, el bitangente
+        // cambia de lado respecto a normal y tangente
+        if let Some(tangents) = self.tangents.as_mut() {
+            for tangent in tangents.iter_mut() {
+                let direction = normalized(linear([tangent[0], tangent[1], tangent[2]]));
+                *tangent = [direction.x, direction.y, direction.z, tangent[3] * sign];
+            }
+        }
+
+        if determinant < 0.0 {
+            self.reverse_triangles();
+        }
+    }
+
+    // Da la vuelta a la malla: invierte el orden de los triángulos y las normales, de modo que
+    // se ve la cara que antes quedaba detrás
+    pub fn flip_winding(&mut self)
+    {
+        self.reverse_triangles();
+        for vertex in self.vertices.iter_mut() {
+            vertex.normal = vertex.normal.map(|value| -value);
+        }
+        // Con la normal invertida, el bitangente conserva su dirección cambiando de signo
+        if let Some(tangents) = self.tangents.as_mut() {
+            for tangent in tangents.iter_mut() {
+                tangent[3] = -tangent[3];
+            }
+        }
+    }
+
+    // Refleja la malla respecto al plano perpendicular al eje que pasa por el origen
+    pub fn mirror(&mut self, axis: Axis)
+    {
+        let scale = match axis {
+            Axis::X => Vector3::new(-1.0, 1.0, 1.0),
+            Axis::Y => Vector3::new(1.0, -1.0, 1.0),
+            Axis::Z => Vector3::new(1.0, 1.0, -1.0),
+        };
+        self.transform(&Matrix4::from_nonuniform_scale(scale.x, scale.y, scale.z));
+    }
+
+    // Añade los vértices y triángulos de otra malla. Los colores que falten se completan con
+    // blanco; los tangentes solo se conservan si las dos mallas los tienen.
+    pub fn append(&mut self, other: &Mesh)
+    {
+        let offset = self.vertices.len() as u32;
+        let had_vertices = !self.vertices.is_empty();
+
+        if self.colors.is_some() || other.colors.is_some() {
+            let colors = self.colors.get_or_insert_with(|| vec![[1.0; 4]; offset as usize]);
+            match &other.colors {
+                Some(other_colors) => colors.extend_from_slice(other_colors),
+                None => colors.extend(std::iter::repeat_n([1.0; 4], other.vertices.len())),
+            }
+        }
+        self.tangents = match (self.tangents.take(), &other.tangents) {
+            (Some(mut tangents), Some(other_tangents)) => {
+                tangents.extend_from_slice(other_tangents);
+                Some(tangents)
+            }
+            // Una malla vacía no tiene nada que perder
+            (None, Some(other_tangents)) if !had_vertices => Some(other_tangents.clone()),
+            _ => None,
+        };
+
+        self.vertices.extend_from_slice(&other.vertices);
+        self.indices.extend(other.indices.iter().map(|&index| index + offset));
+    }
+
+    // Una sola malla con todas las de la lista, en orden
+    pub fn merge(meshes: &[Mesh]) -> Mesh
+    {
+        let mut merged = Mesh::default();
+        for mesh in meshes {
+            merged.append(mesh);
+        }
+        merged
+    }
+
+    // Separa las partes que no comparten ninguna posición. Se usan posiciones y no índices
+    // para que las costuras de UVs o normales (vértices repetidos) no partan una pieza.
+    pub fn split_components(&self) -> Vec<Mesh>
+    {
+        let mut position_ids: HashMap<[u32; 3], usize> = HashMap::new();
+        let vertex_position: Vec<usize> = self
+            .vertices
+            .iter()
+            .map(|vertex| {
+                let next = position_ids.len();
+                *position_ids.entry(float_key(vertex.position)).or_insert(next)
+            })
+            .collect();
+
+        let mut parents: Vec<usize> = (0..position_ids.len()).collect();
+        fn find(parents: &mut [usize], mut node: usize) -> usize {
+            while parents[node] != node {
+                parents[node] = parents[parents[node]];
+                node = parents[node];
+            }
+            node
+        }
+        for [a, b, c] in self.triangles() {
+            let root = find(&mut parents, vertex_position[a as usize]);
+            for vertex in [b, c] {
+                let other = find(&mut parents, vertex_position[vertex as usize]);
+                parents[other] = root;
+            }
+        }
+
+        // Las piezas salen en el orden de su primer triángulo
+        let mut component_of_root: HashMap<usize, usize> = HashMap::new();
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        for (t, [a, _, _]) in self.triangles().enumerate() {
+            let root = find(&mut parents, vertex_position[a as usize]);
+            let component = *component_of_root.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[component].push(t);
+        }
+
+        components.into_iter().map(|triangles| self.extract_triangles(triangles)).collect()
+    }
+
+    // Malla independiente con los triángulos del rango y solo los vértices que usan
+    pub fn extract_range(&self, triangles: Range<usize>) -> Mesh
+    {
+        self.extract_triangles(triangles)
+    }
+
+    fn extract_triangles(&self, triangles: impl IntoIterator<Item = usize>) -> Mesh
+    {
+        let mut remap: Vec<Option<u32>> = vec![None; self.vertices.len()];
+        let mut mesh = Mesh::new(Vec::new(), Vec::new());
+        mesh.tangents = self.tangents.as_ref().map(|_| Vec::new());
+        mesh.colors = self.colors.as_ref().map(|_| Vec::new());
+
+        for t in triangles {
+            for index in self.triangle(t) {
+                let new_index = *remap[index as usize].get_or_insert_with(|| {
+                    mesh.vertices.push(self.vertices[index as usize]);
+                    if let (Some(tangents), Some(new_tangents)) = (&self.tangents, mesh.tangents.as_mut()) {
+                        new_tangents.push(tangents[index as usize]);
+                    }
+                    if let (Some(colors), Some(new_colors)) = (&self.colors, mesh.colors.as_mut()) {
+                        new_colors.push(colors[index as usize]);
+                    }
+                    (mesh.vertices.len() - 1) as u32
+                });
+                mesh.indices.push(new_index);
+            }
+        }
+
+        mesh
+    }
+
+    fn reverse_triangles(&mut self)
+    {
+        for triangle in self.indices.chunks_exact_mut(3) {
+            triangle.swap(1, 2);
+        }
+    }
+}
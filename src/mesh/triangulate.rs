@@ -0,0 +1,90 @@
+use cgmath::{InnerSpace, Vector3};
+
+// Divide un polígono (cóncavo o no plano) en n - 2 triángulos mediante ear clipping.
+// Devuelve índices dentro de `polygon` conservando el sentido de giro original.
+pub fn triangulate_polygon(polygon: &[[f32; 3]]) -> Vec<[usize; 3]> {
+    let n = polygon.len();
+    if n < 3 {
+        return Vec::new();
+    }
+    if n == 3 {
+        return vec![[0, 1, 2]];
+    }
+
+    // La normal de Newell es robusta para polígonos cóncavos y ligeramente no planos
+    let mut normal = Vector3::new(0.0f32, 0.0, 0.0);
+    for i in 0..n {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % n];
+        normal.x += (a[1] - b[1]) * (a[2] + b[2]);
+        normal.y += (a[2] - b[2]) * (a[0] + b[0]);
+        normal.z += (a[0] - b[0]) * (a[1] + b[1]);
+    }
+
+    if normal.magnitude2() <= f32::EPSILON * f32::EPSILON {
+        // Polígono degenerado: no hay plano sobre el que proyectar
+        return (1..n - 1).map(|i| [0, i, i + 1]).collect();
+    }
+    let normal = normal.normalize();
+
+    // Base del plano tal que el polígono queda en sentido antihorario
+    let helper = if normal.x.abs() < 0.9 { Vector3::unit_x() } else { Vector3::unit_y() };
+    let u = normal.cross(helper).normalize();
+    let v = normal.cross(u);
+    let points: Vec<[f32; 2]> = polygon
+        .iter()
+        .map(|p| {
+            let p = Vector3::from(*p);
+            [p.dot(u), p.dot(v)]
+        })
+        .collect();
+
+    let mut remaining: Vec<usize> = (0..n).collect();
+    let mut triangles: Vec<[usize; 3]> = Vec::with_capacity(n - 2);
+
+    while remaining.len() > 3 {
+        let m = remaining.len();
+        let corner = |k: usize| (remaining[(k + m - 1) % m], remaining[k], remaining[(k + 1) % m]);
+
+        let ear = (0..m).find(|&k| {
+            let (prev, current, next) = corner(k);
+            cross(points[prev], points[current], points[next]) > 0.0
+                && !remaining.iter().any(|&other| {
+                    other != prev
+                        && other != current
+                        && other != next
+                        && points[other] != points[prev]
+                        && points[other] != points[current]
+                        && points[other] != points[next]
+                        && point_in_triangle(points[other], points[prev], points[current], points[next])
+                })
+        });
+
+        // Si no hay ninguna oreja (polígono autointersectado o errores numéricos)
+        // se recorta la esquina más convexa para poder continuar
+        let k = ear.unwrap_or_else(|| {
+            (0..m)
+                .max_by(|&a, &b| {
+                    let (pa, ca, na) = corner(a);
+                    let (pb, cb, nb) = corner(b);
+                    cross(points[pa], points[ca], points[na]).total_cmp(&cross(points[pb], points[cb], points[nb]))
+                })
+                .unwrap()
+        });
+
+        let (prev, current, next) = corner(k);
+        triangles.push([prev, current, next]);
+        remaining.remove(k);
+    }
+
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+    triangles
+}
+
+fn cross(a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+fn point_in_triangle(p: [f32; 2], a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
@@ -0,0 +1,358 @@
+use std::{collections::HashMap, fmt};
+
+use cgmath::{InnerSpace, Vector3};
+
+use super::{float_key, Mesh};
+
+// Resultado de Mesh::validate. Los triángulos se identifican por su posición en la lista de
+// triángulos (índice / 3) y las aristas por los vértices del primer triángulo que las usa.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ValidationReport
+{
+    // Posiciones dentro de Mesh::indices que apuntan fuera de Mesh::vertices
+    pub out_of_range_indices: Vec<usize>,
+    // Índices sobrantes al final que no llegan a formar un triángulo
+    pub trailing_indices: usize,
+    // Vértices con alguna coordenada, normal o UV NaN o infinita
+    pub non_finite_vertices: Vec<usize>,
+    pub unreferenced_vertices: Vec<usize>,
+    // Triángulos sin área: dos esquinas en la misma posición, esquinas alineadas, o alguna
+    // esquina inválida (fuera de rango o no finita)
+    pub degenerate_triangles: Vec<usize>,
+    // Triángulos con las mismas tres posiciones que otro anterior, en cualquier orden
+    pub duplicate_triangles: Vec<usize>,
+    // Aristas compartidas por más de dos triángulos
+    pub non_manifold_edges: Vec<[u32; 2]>,
+    // Aristas entre dos triángulos que la recorren en el mismo sentido: uno de los dos está
+    // del revés
+    pub inconsistent_edges: Vec<[u32; 2]>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool
+    {
+        *self == ValidationReport::default()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        if self.is_valid() {
+            return write!(f, "mesh is valid");
+        }
+
+        let problems = [
+            (self.out_of_range_indices.len(), "out of range indices"),
+            (self.trailing_indices, "trailing indices"),
+            (self.non_finite_vertices.len(), "non-finite vertices"),
+            (self.unreferenced_vertices.len(), "unreferenced vertices"),
+            (self.degenerate_triangles.len(), "degenerate triangles"),
+            (self.duplicate_triangles.len(), "duplicate triangles"),
+            (self.non_manifold_edges.len(), "non-manifold edges"),
+            (self.inconsistent_edges.len(), "edges with inconsistent winding"),
+        ];
+        let problems: Vec<String> = problems
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, name)| format!("{} {}", count, name))
+            .collect();
+        write!(f, "mesh has {}", problems.join(", "))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepairOptions
+{
+    // Quita los triángulos degenerados, incluidos los que usan índices fuera de rango
+    pub remove_degenerate: bool,
+    pub remove_duplicates: bool,
+    // Gira los triángulos necesarios para que cada parte conexa tenga un único sentido; en
+    // cada parte se conserva el sentido de la mayoría
+    pub unify_winding: bool,
+    pub remove_unreferenced_vertices: bool,
+}
+
+impl Default for RepairOptions {
+    fn default() -> RepairOptions
+    {
+        RepairOptions {
+            remove_degenerate: true,
+            remove_duplicates: true,
+            unify_winding: true,
+            remove_unreferenced_vertices: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RepairSummary
+{
+    pub removed_triangles: usize,
+    pub flipped_triangles: usize,
+    pub removed_vertices: usize,
+}
+
+// Cómo recorre un triángulo una arista entre posiciones: el triángulo, los vértices en el
+// orden de la clave y si la recorre en ese mismo orden
+type EdgeUse = (usize, [u32; 2], bool);
+
+// Datos compartidos por la validación y la reparación
+struct Analysis
+{
+    triangles: Vec<[u32; 3]>,
+    degenerate: Vec<bool>,
+    duplicate: Vec<bool>,
+    edges: HashMap<(u32, u32), Vec<EdgeUse>>,
+}
+
+impl Mesh {
+    pub fn validate(&self) -> ValidationReport
+    {
+        let mut report = ValidationReport {
+            trailing_indices: self.indices.len() % 3,
+            ..ValidationReport::default()
+        };
+
+        report.out_of_range_indices = self
+            .indices
+            .iter()
+            .enumerate()
+            .filter(|(_, &index)| index as usize >= self.vertices.len())
+            .map(|(position, _)| position)
+            .collect();
+
+        report.non_finite_vertices = (0..self.vertices.len()).filter(|&vertex| !self.is_finite(vertex)).collect();
+
+        let mut referenced = vec![false; self.vertices.len()];
+        for &index in self.indices.iter() {
+            if let Some(referenced) = referenced.get_mut(index as usize) {
+                *referenced = true;
+            }
+        }
+        report.unreferenced_vertices = (0..self.vertices.len()).filter(|&vertex| !referenced[vertex]).collect();
+
+        let analysis = self.analyse(&vec![true; self.triangle_count()]);
+        report.degenerate_triangles = (0..analysis.triangles.len()).filter(|&t| analysis.degenerate[t]).collect();
+        report.duplicate_triangles = (0..analysis.triangles.len()).filter(|&t| analysis.duplicate[t]).collect();
+
+        let mut edges: Vec<(&(u32, u32), &Vec<EdgeUse>)> = analysis.edges.iter().collect();
+        edges.sort_unstable_by_key(|(key, _)| **key);
+        for (_, uses) in edges {
+            match uses.len() {
+                1 => (),
+                2 if uses[0].2 == uses[1].2 => report.inconsistent_edges.push(uses[0].1),
+                2 => (),
+                _ => report.non_manifold_edges.push(uses[0].1),
+            }
+        }
+
+        report
+    }
+
+    // Arregla lo que se puede arreglar sin inventar datos. Las normales de los triángulos
+    // girados no se tocan; si venían calculadas a partir del sentido erróneo conviene
+    // recalcularlas después.
+    pub fn repair(&mut self, options: &RepairOptions) -> RepairSummary
+    {
+        self.repair_triangles(options).0
+    }
+
+    // Como repair, y además indica qué triángulos originales se conservan (en el mismo orden)
+    pub(crate) fn repair_triangles(&mut self, options: &RepairOptions) -> (RepairSummary, Vec<bool>)
+    {
+        let mut summary = RepairSummary::default();
+        let complete = self.indices.len() - self.indices.len() % 3;
+        self.indices.truncate(complete);
+
+        let mut kept = vec![true; self.triangle_count()];
+        if options.remove_degenerate || options.remove_duplicates {
+            let analysis = self.analyse(&kept);
+            for (t, kept) in kept.iter_mut().enumerate() {
+                *kept = !(options.remove_degenerate && analysis.degenerate[t] || options.remove_duplicates && analysis.duplicate[t]);
+            }
+        }
+
+        if options.unify_winding {
+            let flipped = self.winding_flips(&kept);
+            for (t, &flip) in flipped.iter().enumerate() {
+                if flip {
+                    self.indices.swap(t * 3 + 1, t * 3 + 2);
+                    summary.flipped_triangles += 1;
+                }
+            }
+        }
+
+        let mut t = 0;
+        self.indices.retain(|_| {
+            let keep = kept[t / 3];
+            t += 1;
+            keep
+        });
+        summary.removed_triangles = kept.iter().filter(|&&kept| !kept).count();
+
+        if options.remove_unreferenced_vertices {
+            summary.removed_vertices = self.remove_unreferenced_vertices();
+        }
+
+        (summary, kept)
+    }
+
+    fn is_finite(&self, vertex: usize) -> bool
+    {
+        let vertex = &self.vertices[vertex];
+        vertex.position.iter().chain(vertex.normal.iter()).chain(vertex.uv.iter()).all(|value| value.is_finite())
+    }
+
+    // Clasifica los triángulos marcados en `active` y reúne sus aristas entre posiciones.
+    // Los degenerados y los duplicados no cuentan para las aristas.
+    fn analyse(&self, active: &[bool]) -> Analysis
+    {
+        let triangles: Vec<[u32; 3]> = self.triangles().collect();
+        let mut degenerate = vec![false; triangles.len()];
+        let mut duplicate = vec![false; triangles.len()];
+        let mut edges: HashMap<(u32, u32), Vec<EdgeUse>> = HashMap::new();
+
+        let mut ids: HashMap<[u32; 3], u32> = HashMap::new();
+        let mut seen: HashMap<[u32; 3], usize> = HashMap::new();
+
+        for (t, triangle) in triangles.iter().enumerate() {
+            if !active[t] {
+                continue;
+            }
+
+            let valid = triangle
+                .iter()
+                .all(|&vertex| (vertex as usize) < self.vertices.len() && self.is_finite(vertex as usize));
+            if !valid {
+                degenerate[t] = true;
+                continue;
+            }
+
+            let corners = triangle.map(|vertex| self.vertices[vertex as usize].position);
+            let positions = corners.map(|corner| {
+                let next = ids.len() as u32;
+                *ids.entry(float_key(corner)).or_insert(next)
+            });
+            let [a, b, c] = corners.map(Vector3::from);
+            let [pa, pb, pc] = positions;
+            if pa == pb || pb == pc || pa == pc || (b - a).cross(c - a).magnitude2() == 0.0 {
+                degenerate[t] = true;
+                continue;
+            }
+
+            let mut sorted = positions;
+            sorted.sort_unstable();
+            if seen.insert(sorted, t).is_some() {
+                duplicate[t] = true;
+                continue;
+            }
+
+            for k in 0..3 {
+                let (va, vb) = (triangle[k], triangle[(k + 1) % 3]);
+                let (pa, pb) = (positions[k], positions[(k + 1) % 3]);
+                let edge = if pa < pb { ((pa, pb), (t, [va, vb], true)) } else { ((pb, pa), (t, [vb, va], false)) };
+                edges.entry(edge.0).or_default().push(edge.1);
+            }
+        }
+
+        Analysis {
+            triangles,
+            degenerate,
+            duplicate,
+            edges,
+        }
+    }
+
+    // Triángulos que hay que girar para que las aristas compartidas por dos triángulos se
+    // recorran en sentidos opuestos. Las partes no orientables (una cinta de Möbius) se dejan
+    // con el primer sentido que se encuentra.
+    fn winding_flips(&self, active: &[bool]) -> Vec<bool>
+    {
+        let analysis = self.analyse(active);
+        let triangle_count = analysis.triangles.len();
+
+        // Vecinos de cada triángulo a través de aristas variedad y si comparten sentido
+        let mut neighbours: Vec<Vec<(usize, bool)>> = vec![Vec::new(); triangle_count];
+        for uses in analysis.edges.values() {
+            if let [(a, _, forward_a), (b, _, forward_b)] = uses[..] {
+                neighbours[a].push((b, forward_a == forward_b));
+                neighbours[b].push((a, forward_a == forward_b));
+            }
+        }
+
+        let mut flipped = vec![false; triangle_count];
+        let mut visited = vec![false; triangle_count];
+        for seed in 0..triangle_count {
+            if visited[seed] || !active[seed] {
+                continue;
+            }
+
+            visited[seed] = true;
+            let mut component = vec![seed];
+            let mut next = 0;
+            while next < component.len() {
+                let t = component[next];
+                next += 1;
+                for &(neighbour, same_direction) in neighbours[t].iter() {
+                    if !visited[neighbour] {
+                        visited[neighbour] = true;
+                        flipped[neighbour] = flipped[t] ^ same_direction;
+                        component.push(neighbour);
+                    }
+                }
+            }
+
+            // Se gira la minoría
+            let flips = component.iter().filter(|&&t| flipped[t]).count();
+            if flips * 2 > component.len() {
+                for &t in component.iter() {
+                    flipped[t] = !flipped[t];
+                }
+            }
+        }
+
+        flipped
+    }
+
+    // Devuelve cuántos vértices se han quitado
+    fn remove_unreferenced_vertices(&mut self) -> usize
+    {
+        let mut remap: Vec<Option<u32>> = vec![None; self.vertices.len()];
+        for &index in self.indices.iter() {
+            if let Some(slot) = remap.get_mut(index as usize) {
+                *slot = Some(0);
+            }
+        }
+
+        let mut count = 0;
+        for new_index in remap.iter_mut().flatten() {
+            *new_index = count;
+            count += 1;
+        }
+        let removed = self.vertices.len() - count as usize;
+
+        let keep = |vertex: usize| remap[vertex].is_some();
+        let mut vertex = 0;
+        self.vertices.retain(|_| {
+            vertex += 1;
+            keep(vertex - 1)
+        });
+        for attribute in [&mut self.tangents, &mut self.colors].into_iter().flatten() {
+            let mut vertex = 0;
+            attribute.retain(|_| {
+                vertex += 1;
+                keep(vertex - 1)
+            });
+        }
+        // Los índices fuera de rango (si no se han quitado sus triángulos) siguen fuera de rango
+        for index in self.indices.iter_mut() {
+            *index = match remap.get(*index as usize) {
+                Some(Some(new_index)) => *new_index,
+                _ => count,
+            };
+        }
+
+        removed
+    }
+}
@@ -0,0 +1,574 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    error::Error,
+    fmt,
+    fs::{self, File},
+    hash::{Hash, Hasher},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use crate::{
+    material::{AlphaMode, Material, PbrMaterial},
+    mesh::{normals::NormalWeighting, Mesh, Vertex},
+    model::{Model, SubMesh},
+    obj_parser::{parse_obj_parallel, ObjError, ObjOptions},
+};
+
+const MAGIC: &[u8; 4] = b"GPMC";
+const VERSION: u32 = 4;
+
+#[derive(Debug)]
+pub enum CacheError
+{
+    Io(io::Error),
+    BadMagic,
+    UnsupportedVersion(u32),
+    Truncated,
+    InvalidData(&'static str),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            CacheError::Io(e) => write!(f, "I/O error: {}", e),
+            CacheError::BadMagic => write!(f, "not a mesh cache file"),
+            CacheError::UnsupportedVersion(version) => write!(f, "unsupported mesh cache version {}", version),
+            CacheError::Truncated => write!(f, "mesh cache file is truncated"),
+            CacheError::InvalidData(what) => write!(f, "invalid mesh cache data: {}", what),
+        }
+    }
+}
+
+impl Error for CacheError {
+    fn source(&self) -> Option<&(dyn Error + 'static)>
+    {
+        match self {
+            CacheError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CacheError {
+    fn from(e: io::Error) -> CacheError
+    {
+        CacheError::Io(e)
+    }
+}
+
+// Tamaño y fecha de modificación de un archivo, para saber si ha cambiado
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp
+{
+    pub size: u64,
+    pub modified_secs: u64,
+    pub modified_nanos: u32,
+}
+
+impl FileStamp {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<FileStamp>
+    {
+        let metadata = fs::metadata(path)?;
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        Ok(FileStamp {
+            size: metadata.len(),
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+        })
+    }
+}
+
+// Identifica el archivo de origen, sus librerías de materiales y las opciones con las que se
+// generó la entrada de la caché
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceKey
+{
+    pub path: String,
+    pub stamp: FileStamp,
+    // Cada mtllib con el nombre que aparece en el .obj; None si no existía al leerlo
+    pub libraries: Vec<(String, Option<FileStamp>)>,
+    pub options: ObjOptions,
+}
+
+impl SourceKey {
+    pub fn from_file(file_path: &str, options: &ObjOptions) -> io::Result<SourceKey>
+    {
+        let stamp = FileStamp::from_file(file_path)?;
+        let path = fs::canonicalize(file_path)?;
+
+        // Las librerías se buscan junto al .obj, igual que al parsearlo con FileResolver
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        let mut libraries = Vec::new();
+        for line in BufReader::new(File::open(&path)?).lines() {
+            let line = line?;
+            let mut tokens = line.split_whitespace();
+            if tokens.next() == Some("mtllib") {
+                for name in tokens {
+                    libraries.push((name.to_string(), FileStamp::from_file(base_dir.join(name)).ok()));
+                }
+            }
+        }
+
+        Ok(SourceKey {
+            path: path.to_string_lossy().to_string(),
+            stamp,
+            libraries,
+            options: *options,
+        })
+    }
+}
+
+// Caché en disco de modelos ya procesados: una entrada por archivo de origen
+pub struct MeshCache
+{
+    directory: PathBuf,
+}
+
+impl MeshCache {
+    pub fn new<P: AsRef<Path>>(directory: P) -> MeshCache
+    {
+        MeshCache {
+            directory: directory.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn entry_path(&self, key: &SourceKey) -> PathBuf
+    {
+        let mut hasher = DefaultHasher::new();
+        key.path.hash(&mut hasher);
+        self.directory.join(format!("{:016x}.gpmc", hasher.finish()))
+    }
+
+    // Devuelve el modelo de la caché si sigue siendo válido; si no, parsea el .obj y guarda el resultado
+    pub fn load_obj(&self, file_path: &str, options: &ObjOptions) -> Result<Model, ObjError>
+    {
+        let key = SourceKey::from_file(file_path, options)?;
+        let entry = self.entry_path(&key);
+
+        if let Ok(bytes) = fs::read(&entry) {
+            if let Ok((cached_key, model)) = read_model(&bytes) {
+                if cached_key.as_ref() == Some(&key) {
+                    return Ok(model);
+                }
+            }
+        }
+
+        let model = parse_obj_parallel(file_path, options)?;
+
+        // Un fallo al escribir la caché no impide usar el modelo recién leído
+        let _ = fs::create_dir_all(&self.directory).and_then(|_| {
+            let mut bytes: Vec<u8> = Vec::new();
+            write_model(&model, Some(&key), &mut bytes)?;
+            fs::write(&entry, bytes)
+        });
+
+        Ok(model)
+    }
+}
+
+pub fn write_model<W: Write>(model: &Model, key: Option<&SourceKey>, writer: &mut W) -> io::Result<()> {
+    let mut out = Encoder { bytes: Vec::new() };
+    out.bytes.extend_from_slice(MAGIC);
+    out.u32(VERSION);
+
+    match key {
+        Some(key) => {
+            out.u8(1);
+            out.string(&key.path);
+            out.stamp(&key.stamp);
+            out.u32(key.libraries.len() as u32);
+            for (name, stamp) in key.libraries.iter() {
+                out.string(name);
+                match stamp {
+                    Some(stamp) => {
+                        out.u8(1);
+                        out.stamp(stamp);
+                    }
+                    None => out.u8(0),
+                }
+            }
+            out.u8(match key.options.normals.weighting {
+                NormalWeighting::Area => 0,
+                NormalWeighting::Angle => 1,
+            });
+            out.f32(key.options.normals.crease_angle.unwrap_or(f32::NAN));
+            out.u8(key.options.tangents as u8);
+        }
+        None => out.u8(0),
+    }
+
+    let mesh = &model.mesh;
+    out.u32(mesh.vertices.len() as u32);
+    for vertex in mesh.vertices.iter() {
+        for value in vertex.position.iter().chain(vertex.normal.iter()).chain(vertex.uv.iter()) {
+            out.f32(*value);
+        }
+    }
+    out.u32(mesh.indices.len() as u32);
+    for &index in mesh.indices.iter() {
+        out.u32(index);
+    }
+    for attribute in [&mesh.tangents, &mesh.colors] {
+        match attribute {
+            Some(values) => {
+                out.u8(1);
+                for value in values.iter().flatten() {
+                    out.f32(*value);
+                }
+            }
+            None => out.u8(0),
+        }
+    }
+
+    out.u32(model.materials.len() as u32);
+    for material in model.materials.iter() {
+        out.string(&material.name);
+        for value in material.ambient.iter().chain(material.diffuse.iter()).chain(material.specular.iter()) {
+            out.f32(*value);
+        }
+        out.f32(material.shininess);
+        out.f32(material.dissolve);
+        out.u32(material.illumination);
+        for map in material_maps(material) {
+            out.optional_string(map.as_deref());
+        }
+
+        match &material.pbr {
+            Some(pbr) => {
+                out.u8(1);
+                for value in pbr.base_color.iter().chain([pbr.metallic, pbr.roughness].iter()).chain(pbr.emissive.iter()) {
+                    out.f32(*value);
+                }
+                match pbr.alpha_mode {
+                    AlphaMode::Opaque => out.u8(0),
+                    AlphaMode::Mask(cutoff) => {
+                        out.u8(1);
+                        out.f32(cutoff);
+                    }
+                    AlphaMode::Blend => out.u8(2),
+                }
+                out.u8(pbr.double_sided as u8);
+                for map in pbr_maps(pbr) {
+                    out.optional_string(map.as_deref());
+                }
+            }
+            None => out.u8(0),
+        }
+    }
+
+    out.u32(model.submeshes.len() as u32);
+    for submesh in model.submeshes.iter() {
+        out.string(&submesh.name);
+        out.optional_string(submesh.object.as_deref());
+        out.u32(submesh.material.map_or(u32::MAX, |m| m as u32));
+        out.u32(submesh.first_triangle as u32);
+        out.u32(submesh.triangle_count as u32);
+    }
+
+    writer.write_all(&out.bytes)
+}
+
+// Lee un modelo desde los bytes de un archivo de caché ya cargado en memoria
+pub fn read_model(bytes: &[u8]) -> Result<(Option<SourceKey>, Model), CacheError> {
+    let mut input = Decoder { bytes, position: 0 };
+
+    if input.take(4)? != MAGIC {
+        return Err(CacheError::BadMagic);
+    }
+    let version = input.u32()?;
+    if version != VERSION {
+        return Err(CacheError::UnsupportedVersion(version));
+    }
+
+    let key = match input.u8()? {
+        0 => None,
+        _ => {
+            let path = input.string()?;
+            let stamp = input.stamp()?;
+            let library_count = input.u32()? as usize;
+            let mut libraries = Vec::with_capacity(library_count.min(1024));
+            for _ in 0..library_count {
+                let name = input.string()?;
+                let stamp = match input.u8()? {
+                    0 => None,
+                    _ => Some(input.stamp()?),
+                };
+                libraries.push((name, stamp));
+            }
+            let mut options = ObjOptions::default();
+            options.normals.weighting = match input.u8()? {
+                0 => NormalWeighting::Area,
+                _ => NormalWeighting::Angle,
+            };
+            let crease = input.f32()?;
+            options.normals.crease_angle = if crease.is_nan() { None } else { Some(crease) };
+            options.tangents = input.u8()? != 0;
+
+            Some(SourceKey {
+                path,
+                stamp,
+                libraries,
+                options,
+            })
+        }
+    };
+
+    let vertex_count = input.u32()? as usize;
+    let vertex_bytes = input.take(vertex_count * 8 * 4)?;
+    let vertices: Vec<Vertex> = vertex_bytes
+        .chunks_exact(8 * 4)
+        .map(|chunk| {
+            let f = |i: usize| f32::from_le_bytes(chunk[i * 4..i * 4 + 4].try_into().unwrap());
+            Vertex::new([f(0), f(1), f(2)], [f(3), f(4), f(5)], [f(6), f(7)])
+        })
+        .collect();
+
+    let index_count = input.u32()? as usize;
+    let indices: Vec<u32> = input
+        .take(index_count * 4)?
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+        .collect();
+    if indices.iter().any(|&i| i as usize >= vertex_count) {
+        return Err(CacheError::InvalidData("index out of range"));
+    }
+
+    let tangents = input.optional_vec4s(vertex_count)?;
+    let colors = input.optional_vec4s(vertex_count)?;
+
+    let material_count = input.u32()? as usize;
+    let mut materials: Vec<Material> = Vec::with_capacity(material_count.min(1024));
+    for _ in 0..material_count {
+        let mut material = Material::new(&input.string()?);
+        material.ambient = [input.f32()?, input.f32()?, input.f32()?];
+        material.diffuse = [input.f32()?, input.f32()?, input.f32()?];
+        material.specular = [input.f32()?, input.f32()?, input.f32()?];
+        material.shininess = input.f32()?;
+        material.dissolve = input.f32()?;
+        material.illumination = input.u32()?;
+        material.ambient_map = input.optional_string()?;
+        material.diffuse_map = input.optional_string()?;
+        material.specular_map = input.optional_string()?;
+        material.shininess_map = input.optional_string()?;
+        material.dissolve_map = input.optional_string()?;
+        material.bump_map = input.optional_string()?;
+
+        if input.u8()? != 0 {
+            let mut pbr = PbrMaterial {
+                base_color: [input.f32()?, input.f32()?, input.f32()?, input.f32()?],
+                metallic: input.f32()?,
+                roughness: input.f32()?,
+                emissive: [input.f32()?, input.f32()?, input.f32()?],
+                alpha_mode: match input.u8()? {
+                    0 => AlphaMode::Opaque,
+                    1 => AlphaMode::Mask(input.f32()?),
+                    2 => AlphaMode::Blend,
+                    _ => return Err(CacheError::InvalidData("unknown alpha mode")),
+                },
+                double_sided: input.u8()? != 0,
+                ..PbrMaterial::default()
+            };
+            pbr.base_color_map = input.optional_string()?;
+            pbr.metallic_roughness_map = input.optional_string()?;
+            pbr.normal_map = input.optional_string()?;
+            pbr.occlusion_map = input.optional_string()?;
+            pbr.emissive_map = input.optional_string()?;
+            material.pbr = Some(pbr);
+        }
+        materials.push(material);
+    }
+
+    let submesh_count = input.u32()? as usize;
+    let mut submeshes: Vec<SubMesh> = Vec::with_capacity(submesh_count.min(1024));
+    for _ in 0..submesh_count {
+        let name = input.string()?;
+        let object = input.optional_string()?;
+        let material = match input.u32()? {
+            u32::MAX => None,
+            index if (index as usize) < materials.len() => Some(index as usize),
+            _ => return Err(CacheError::InvalidData("material index out of range")),
+        };
+        let first_triangle = input.u32()? as usize;
+        let triangle_count = input.u32()? as usize;
+        if (first_triangle + triangle_count) * 3 > indices.len() {
+            return Err(CacheError::InvalidData("submesh out of range"));
+        }
+
+        submeshes.push(SubMesh {
+            name,
+            object,
+            material,
+            first_triangle,
+            triangle_count,
+        });
+    }
+
+    let mut mesh = Mesh::new(vertices, indices);
+    mesh.tangents = tangents;
+    mesh.colors = colors;
+
+    Ok((
+        key,
+        Model {
+            mesh,
+            materials,
+            submeshes,
+        },
+    ))
+}
+
+fn material_maps(material: &Material) -> [&Option<String>; 6] {
+    [
+        &material.ambient_map,
+        &material.diffuse_map,
+        &material.specular_map,
+        &material.shininess_map,
+        &material.dissolve_map,
+        &material.bump_map,
+    ]
+}
+
+fn pbr_maps(pbr: &PbrMaterial) -> [&Option<String>; 5] {
+    [
+        &pbr.base_color_map,
+        &pbr.metallic_roughness_map,
+        &pbr.normal_map,
+        &pbr.occlusion_map,
+        &pbr.emissive_map,
+    ]
+}
+
+struct Encoder
+{
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    fn u8(&mut self, value: u8)
+    {
+        self.bytes.push(value);
+    }
+
+    fn u32(&mut self, value: u32)
+    {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64)
+    {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32)
+    {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn stamp(&mut self, stamp: &FileStamp)
+    {
+        self.u64(stamp.size);
+        self.u64(stamp.modified_secs);
+        self.u32(stamp.modified_nanos);
+    }
+
+    fn string(&mut self, value: &str)
+    {
+        self.u32(value.len() as u32);
+        self.bytes.extend_from_slice(value.as_bytes());
+    }
+
+    fn optional_string(&mut self, value: Option<&str>)
+    {
+        match value {
+            Some(value) => {
+                self.u8(1);
+                self.string(value);
+            }
+            None => self.u8(0),
+        }
+    }
+}
+
+struct Decoder<'a>
+{
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], CacheError>
+    {
+        let end = self.position.checked_add(count).ok_or(CacheError::Truncated)?;
+        let slice = self.bytes.get(self.position..end).ok_or(CacheError::Truncated)?;
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, CacheError>
+    {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, CacheError>
+    {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, CacheError>
+    {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Result<f32, CacheError>
+    {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn stamp(&mut self) -> Result<FileStamp, CacheError>
+    {
+        Ok(FileStamp {
+            size: self.u64()?,
+            modified_secs: self.u64()?,
+            modified_nanos: self.u32()?,
+        })
+    }
+
+    fn string(&mut self) -> Result<String, CacheError>
+    {
+        let length = self.u32()? as usize;
+        String::from_utf8(self.take(length)?.to_vec()).map_err(|_| CacheError::InvalidData("string is not UTF-8"))
+    }
+
+    fn optional_string(&mut self) -> Result<Option<String>, CacheError>
+    {
+        match self.u8()? {
+            0 => Ok(None),
+            _ => Ok(Some(self.string()?)),
+        }
+    }
+
+    // Atributo opcional con cuatro floats por vértice (tangentes, colores)
+    fn optional_vec4s(&mut self, count: usize) -> Result<Option<Vec<[f32; 4]>>, CacheError>
+    {
+        if self.u8()? == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            self.take(count * 4 * 4)?
+                .chunks_exact(4 * 4)
+                .map(|chunk| {
+                    let f = |i: usize| f32::from_le_bytes(chunk[i * 4..i * 4 + 4].try_into().unwrap());
+                    [f(0), f(1), f(2), f(3)]
+                })
+                .collect(),
+        ))
+    }
+}
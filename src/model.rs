@@ -0,0 +1,83 @@
+use std::ops::Range;
+
+use crate::{
+    material::Material,
+    mesh::{validate::{RepairOptions, RepairSummary}, Mesh},
+};
+
+// Parte de un modelo (objeto o grupo del archivo) que se dibuja con un único material
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubMesh
+{
+    pub name: String,
+    pub object: Option<String>,
+    pub material: Option<usize>,
+    pub first_triangle: usize,
+    pub triangle_count: usize,
+}
+
+impl SubMesh {
+    // Rango dentro de Mesh::indices, listo para DrawElements
+    pub fn index_range(&self) -> Range<usize>
+    {
+        self.first_triangle * 3..(self.first_triangle + self.triangle_count) * 3
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Model
+{
+    pub mesh: Mesh,
+    pub materials: Vec<Material>,
+    pub submeshes: Vec<SubMesh>,
+}
+
+impl Model {
+    // Modelo con una única submalla sin material que abarca toda la malla
+    pub fn from_mesh(mesh: Mesh) -> Model
+    {
+        let submeshes = vec![SubMesh {
+            name: String::new(),
+            object: None,
+            material: None,
+            first_triangle: 0,
+            triangle_count: mesh.triangle_count(),
+        }];
+
+        Model {
+            mesh,
+            materials: Vec::new(),
+            submeshes,
+        }
+    }
+
+    pub fn material_for(&self, submesh: &SubMesh) -> Option<&Material>
+    {
+        submesh.material.map(|index| &self.materials[index])
+    }
+
+    pub fn submesh(&self, name: &str) -> Option<&SubMesh>
+    {
+        self.submeshes.iter().find(|submesh| submesh.name == name)
+    }
+
+    // Malla independiente con solo los triángulos de la submalla
+    pub fn extract_submesh(&self, submesh: &SubMesh) -> Mesh
+    {
+        self.mesh.extract_range(submesh.first_triangle..submesh.first_triangle + submesh.triangle_count)
+    }
+
+    // Como Mesh::repair, ajustando los rangos de las submallas a los triángulos que quedan
+    pub fn repair(&mut self, options: &RepairOptions) -> RepairSummary
+    {
+        let (summary, kept) = self.mesh.repair_triangles(options);
+        let mut first_triangle = 0;
+        for submesh in self.submeshes.iter_mut() {
+            let range = submesh.first_triangle..submesh.first_triangle + submesh.triangle_count;
+            submesh.first_triangle = first_triangle;
+            submesh.triangle_count = kept[range].iter().filter(|&&kept| kept).count();
+            first_triangle += submesh.triangle_count;
+        }
+        summary
+    }
+}
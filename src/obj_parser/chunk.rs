@@ -0,0 +1,207 @@
+use std::ops::Range;
+
+use super::{parse_floats, rest_of_line, tokens, Location, ObjError, IGNORED_DIRECTIVES};
+
+// Esquina de cara tal como aparece en el archivo; los índices se resuelven al unir los trozos
+pub(super) struct RawCorner<'a>
+{
+    pub text: &'a str,
+    pub column: usize,
+    pub position: i64,
+    pub texture: Option<i64>,
+    pub normal: Option<i64>,
+}
+
+// Directivas que dependen del estado global del archivo y se aplican en orden al unir los trozos
+pub(super) enum Statement<'a>
+{
+    // `counts` son los v, vt y vn leídos dentro del trozo antes de la cara
+    Face { line: usize, corners: Range<usize>, counts: [usize; 3] },
+    MaterialLibrary(Vec<&'a str>),
+    UseMaterial(&'a str),
+    Smoothing(u32),
+    Object(&'a str),
+    Group(Option<String>),
+}
+
+pub(super) struct Chunk<'a>
+{
+    pub vertices: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub textures: Vec<[f32; 2]>,
+    pub corners: Vec<RawCorner<'a>>,
+    pub statements: Vec<Statement<'a>>,
+    pub line_count: usize,
+    // Primer error del trozo, con las líneas contadas desde su inicio
+    pub error: Option<ObjError>,
+}
+
+// Divide el texto en hasta `count` trozos de tamaño parecido, siempre en un salto de línea
+pub(super) fn split_chunks(source: &str, count: usize) -> Vec<&str> {
+    let count = count.max(1);
+    let target = source.len() / count + 1;
+
+    let mut chunks: Vec<&str> = Vec::with_capacity(count);
+    let mut start = 0;
+    while start < source.len() {
+        let end = if chunks.len() + 1 == count || start + target >= source.len() {
+            source.len()
+        } else {
+            match source[start + target..].find('\n') {
+                Some(newline) => start + target + newline + 1,
+                None => source.len(),
+            }
+        };
+        chunks.push(&source[start..end]);
+        start = end;
+    }
+
+    if chunks.is_empty() {
+        chunks.push(source);
+    }
+    chunks
+}
+
+pub(super) fn parse_chunk(text: &str) -> Chunk<'_> {
+    let mut chunk = Chunk {
+        vertices: Vec::new(),
+        normals: Vec::new(),
+        textures: Vec::new(),
+        corners: Vec::new(),
+        statements: Vec::new(),
+        line_count: text.bytes().filter(|&b| b == b'\n').count(),
+        error: None,
+    };
+
+    for (line_index, line) in text.lines().enumerate() {
+        if let Err(e) = parse_line(line, line_index + 1, &mut chunk) {
+            chunk.error = Some(e);
+            break;
+        }
+    }
+
+    chunk
+}
+
+fn parse_line<'a>(line: &'a str, line_number: usize, chunk: &mut Chunk<'a>) -> Result<(), ObjError> {
+    let mut tokens = tokens(line);
+
+    let (column, keyword) = match tokens.next() {
+        Some((_, keyword)) if keyword.starts_with('#') => return Ok(()),
+        Some(token) => token,
+        None => return Ok(()),
+    };
+    let location = Location {
+        line: line_number,
+        column,
+    };
+
+    match keyword {
+        "v" => chunk.vertices.push(parse_floats(&mut tokens, 3, location, keyword)?),
+        "vn" => chunk.normals.push(parse_floats(&mut tokens, 3, location, keyword)?),
+        // La coordenada v es opcional y la w no se usa
+        "vt" => chunk.textures.push(parse_floats(&mut tokens, 1, location, keyword)?),
+        "mtllib" => chunk.statements.push(Statement::MaterialLibrary(tokens.map(|(_, name)| name).collect())),
+        "usemtl" => chunk.statements.push(Statement::UseMaterial(rest_of_line(line, tokens, location, keyword)?)),
+        "s" => {
+            let (column, group) = tokens.next().ok_or_else(|| ObjError::MissingValue {
+                location,
+                directive: keyword.to_string(),
+            })?;
+            let group = match group {
+                "off" => 0,
+                _ => group.parse().map_err(|_| ObjError::ParseNumber {
+                    location: Location { line: line_number, column },
+                    text: group.to_string(),
+                })?,
+            };
+            chunk.statements.push(Statement::Smoothing(group));
+        }
+        "o" => chunk.statements.push(Statement::Object(rest_of_line(line, tokens, location, keyword).unwrap_or_default())),
+        "g" => {
+            let mut name: Option<String> = None;
+            for (_, part) in tokens {
+                match name.as_mut() {
+                    Some(name) => {
+                        name.push(' ');
+                        name.push_str(part);
+                    }
+                    None => name = Some(part.to_string()),
+                }
+            }
+            chunk.statements.push(Statement::Group(name));
+        }
+        "f" => {
+            let first = chunk.corners.len();
+            for (column, corner) in tokens {
+                let location = Location {
+                    line: line_number,
+                    column,
+                };
+                chunk.corners.push(parse_raw_corner(corner, location)?);
+            }
+
+            if chunk.corners.len() - first < 3 {
+                return Err(ObjError::BadFace {
+                    location,
+                    text: line.trim().to_string(),
+                });
+            }
+
+            chunk.statements.push(Statement::Face {
+                line: line_number,
+                corners: first..chunk.corners.len(),
+                counts: [chunk.vertices.len(), chunk.textures.len(), chunk.normals.len()],
+            });
+        }
+        _ if IGNORED_DIRECTIVES.contains(&keyword) => (),
+        _ => {
+            return Err(ObjError::UnsupportedDirective {
+                location,
+                directive: keyword.to_string(),
+            })
+        }
+    }
+
+    Ok(())
+}
+
+// v, v/vt, v//vn o v/vt/vn, sin comprobar todavía que los índices existan
+fn parse_raw_corner(corner: &str, location: Location) -> Result<RawCorner<'_>, ObjError> {
+    let bad_face = || ObjError::BadFace {
+        location,
+        text: corner.to_string(),
+    };
+    let parse = |index: &str| {
+        index.parse::<i64>().map_err(|_| ObjError::ParseNumber {
+            location,
+            text: corner.to_string(),
+        })
+    };
+    let mut parts = corner.split('/');
+
+    let position = match parts.next() {
+        Some(index) if !index.is_empty() => parse(index)?,
+        _ => return Err(bad_face()),
+    };
+    let texture = match parts.next() {
+        Some(index) if !index.is_empty() => Some(parse(index)?),
+        _ => None,
+    };
+    let normal = match parts.next() {
+        Some(index) if !index.is_empty() => Some(parse(index)?),
+        _ => None,
+    };
+
+    if parts.next().is_some() {
+        return Err(bad_face());
+    }
+
+    Ok(RawCorner {
+        text: corner,
+        column: location.column,
+        position,
+        texture,
+        normal,
+    })
+}
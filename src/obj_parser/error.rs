@@ -0,0 +1,98 @@
+use std::{error::Error, fmt, io};
+
+// Posición (base 1) dentro del archivo que se estaba leyendo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location
+{
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+#[derive(Debug)]
+pub enum ObjError
+{
+    Io(io::Error),
+    ParseNumber { location: Location, text: String },
+    MissingValue { location: Location, directive: String },
+    BadFace { location: Location, text: String },
+    IndexOutOfRange { location: Location, text: String, index: i64, count: usize },
+    UnsupportedDirective { location: Location, directive: String },
+    // Error dentro de una librería de materiales referenciada con mtllib
+    Material { path: String, source: Box<ObjError> },
+}
+
+impl ObjError {
+    pub fn location(&self) -> Option<Location>
+    {
+        match self {
+            ObjError::Io(_) | ObjError::Material { .. } => None,
+            ObjError::ParseNumber { location, .. }
+            | ObjError::MissingValue { location, .. }
+            | ObjError::BadFace { location, .. }
+            | ObjError::IndexOutOfRange { location, .. }
+            | ObjError::UnsupportedDirective { location, .. } => Some(*location),
+        }
+    }
+
+    // Error de un trozo del archivo leído por separado, que empieza tras `lines` líneas
+    pub(crate) fn offset_lines(mut self, lines: usize) -> ObjError
+    {
+        match &mut self {
+            ObjError::Io(_) | ObjError::Material { .. } => (),
+            ObjError::ParseNumber { location, .. }
+            | ObjError::MissingValue { location, .. }
+            | ObjError::BadFace { location, .. }
+            | ObjError::IndexOutOfRange { location, .. }
+            | ObjError::UnsupportedDirective { location, .. } => location.line += lines,
+        }
+        self
+    }
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            ObjError::Io(e) => write!(f, "I/O error: {}", e),
+            ObjError::ParseNumber { location, text } => write!(f, "{}: invalid number '{}'", location, text),
+            ObjError::MissingValue { location, directive } => {
+                write!(f, "{}: missing value for '{}'", location, directive)
+            }
+            ObjError::BadFace { location, text } => write!(f, "{}: malformed face element '{}'", location, text),
+            ObjError::IndexOutOfRange { location, text, index, count } => write!(
+                f,
+                "{}: index {} in '{}' is out of range ({} elements defined)",
+                location, index, text, count
+            ),
+            ObjError::UnsupportedDirective { location, directive } => {
+                write!(f, "{}: unsupported directive '{}'", location, directive)
+            }
+            ObjError::Material { path, source } => write!(f, "in material library '{}': {}", path, source),
+        }
+    }
+}
+
+impl Error for ObjError {
+    fn source(&self) -> Option<&(dyn Error + 'static)>
+    {
+        match self {
+            ObjError::Io(e) => Some(e),
+            ObjError::Material { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ObjError {
+    fn from(e: io::Error) -> ObjError
+    {
+        ObjError::Io(e)
+    }
+}
@@ -0,0 +1,354 @@
+use std::{collections::HashMap, fs::{self, File}, io::{self, BufRead}, thread};
+
+use crate::{material::Material, mesh::{normals::{generate_corner_normals, NormalOptions}, triangulate::triangulate_polygon, float_key, Mesh, Vertex}, model::{Model, SubMesh}};
+
+mod chunk;
+pub mod error;
+pub mod mtl;
+pub mod resolver;
+pub mod writer;
+
+pub use error::{Location, ObjError};
+pub use resolver::{FileResolver, NoResolver, ResourceResolver};
+
+use chunk::{Chunk, Statement};
+
+// Posición, normal y coordenadas de textura de un vértice comparables en un HashMap
+type VertexKey = ([u32; 3], [u32; 3], [u32; 2]);
+
+struct Face
+{
+    corners: Vec<FaceCorner>,
+    smoothing_group: u32,
+}
+
+// Índices (ya en base 0) de una esquina de cara: v, v/vt, v//vn o v/vt/vn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FaceCorner
+{
+    position: usize,
+    texture: Option<usize>,
+    normal: Option<usize>,
+}
+
+// Nombre con el que el escritor vuelve a caras sin material. Si ninguna librería lo define,
+// al leerlo se quita el material actual en vez de crear uno por defecto.
+pub const NO_MATERIAL: &str = "(null)";
+
+// Tamaño mínimo de cada trozo al leer en paralelo
+const MIN_CHUNK_SIZE: usize = 64 * 1024;
+
+// Directivas del formato que se aceptan aunque no afecten a la malla resultante
+const IGNORED_DIRECTIVES: &[&str] = &[
+    "vp", "l", "p", "mg", "cstype", "deg", "bmat", "step", "curv", "curv2", "surf", "parm",
+    "trim", "hole", "scrv", "sp", "end", "con", "bevel", "c_interp", "d_interp", "lod", "usemap", "maplib",
+    "shadow_obj", "trace_obj", "ctech", "stech", "call", "csh",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ObjOptions
+{
+    // Cómo se generan las normales de las caras que no las traen (sin líneas vn)
+    pub normals: NormalOptions,
+    // Genera también los tangentes para normal mapping (Mesh::tangents)
+    pub tangents: bool,
+}
+
+pub fn parse_obj(file_path: &str) -> Result<Model, ObjError> {
+    parse_obj_with_options(file_path, &ObjOptions::default())
+}
+
+pub fn parse_obj_with_options(file_path: &str, options: &ObjOptions) -> Result<Model, ObjError> {
+    let file = File::open(file_path)?;
+    let reader = io::BufReader::new(file);
+
+    // Las rutas de las librerías son relativas al archivo .obj
+    parse_obj_from_reader(reader, &FileResolver::for_file(file_path), options)
+}
+
+// Modelo escrito directamente en el código o en un test; mtllib se ignora
+pub fn parse_obj_from_str(source: &str, options: &ObjOptions) -> Result<Model, ObjError> {
+    parse_obj_from_reader(source.as_bytes(), &NoResolver, options)
+}
+
+pub fn parse_obj_from_reader<R: BufRead>(mut reader: R, resolver: &dyn ResourceResolver, options: &ObjOptions) -> Result<Model, ObjError> {
+    let mut source = String::new();
+    reader.read_to_string(&mut source)?;
+    parse_source(&source, resolver, options, 1)
+}
+
+// Igual que parse_obj_with_options, pero leyendo el archivo en paralelo con todos los núcleos
+// disponibles; el resultado es idéntico al de la lectura en serie
+pub fn parse_obj_parallel(file_path: &str, options: &ObjOptions) -> Result<Model, ObjError> {
+    let source = fs::read_to_string(file_path)?;
+    // Con trozos muy pequeños lanzar hilos cuesta más que leerlos
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let threads = threads.min(source.len() / MIN_CHUNK_SIZE).max(1);
+    parse_obj_from_str_parallel(&source, &FileResolver::for_file(file_path), options, threads)
+}
+
+// Lee el texto en exactamente `threads` trozos (o menos si no tiene tantas líneas)
+pub fn parse_obj_from_str_parallel(source: &str, resolver: &dyn ResourceResolver, options: &ObjOptions, threads: usize) -> Result<Model, ObjError> {
+    parse_source(source, resolver, options, threads)
+}
+
+// Lee el texto en `threads` trozos independientes (las líneas v/vt/vn y los índices de cada cara,
+// sin resolver) y después los une en orden, ajustando los índices y los números de línea
+fn parse_source(source: &str, resolver: &dyn ResourceResolver, options: &ObjOptions, threads: usize) -> Result<Model, ObjError> {
+    let pieces = chunk::split_chunks(source, threads);
+    let chunks: Vec<Chunk> = if pieces.len() == 1 {
+        vec![chunk::parse_chunk(pieces[0])]
+    } else {
+        thread::scope(|scope| {
+            let handles: Vec<_> = pieces.iter().map(|piece| scope.spawn(|| chunk::parse_chunk(piece))).collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        })
+    };
+
+    let mut vertices: Vec<[f32; 3]> = Vec::with_capacity(chunks.iter().map(|chunk| chunk.vertices.len()).sum());
+    let mut normals: Vec<[f32; 3]> = Vec::with_capacity(chunks.iter().map(|chunk| chunk.normals.len()).sum());
+    let mut textures: Vec<[f32; 2]> = Vec::with_capacity(chunks.iter().map(|chunk| chunk.textures.len()).sum());
+    let mut faces: Vec<Face> = Vec::new();
+
+    let mut materials: Vec<Material> = Vec::new();
+    let mut submeshes: Vec<SubMesh> = Vec::new();
+    let mut current_object: Option<String> = None;
+    let mut current_group: Option<String> = None;
+    let mut current_material: Option<usize> = None;
+    // Por defecto el suavizado está desactivado (s off)
+    let mut smoothing_group: u32 = 0;
+    let mut triangle_count: usize = 0;
+    let mut line_offset: usize = 0;
+
+    for chunk in chunks {
+        let offsets = [vertices.len(), textures.len(), normals.len()];
+        vertices.extend(chunk.vertices);
+        textures.extend(chunk.textures);
+        normals.extend(chunk.normals);
+
+        for statement in chunk.statements {
+            match statement {
+                Statement::MaterialLibrary(names) => {
+                    for name in names {
+                        let wrap = |e: ObjError| ObjError::Material {
+                            path: name.to_string(),
+                            source: Box::new(e),
+                        };
+                        let library = match resolver.open_material_library(name).map_err(|e| wrap(e.into()))? {
+                            Some(reader) => mtl::parse_mtl_from_reader(reader, resolver).map_err(wrap)?,
+                            None => continue,
+                        };
+                        for material in library {
+                            match materials.iter().position(|m| m.name == material.name) {
+                                Some(index) => materials[index] = material,
+                                None => materials.push(material),
+                            }
+                        }
+                    }
+                }
+                Statement::UseMaterial(name) => {
+                    current_material = match materials.iter().position(|m| m.name == name) {
+                        Some(index) => Some(index),
+                        None if name == NO_MATERIAL => None,
+                        None => {
+                            // Material sin definir en ninguna librería: se usan los valores por defecto
+                            materials.push(Material::new(name));
+                            Some(materials.len() - 1)
+                        }
+                    };
+                }
+                Statement::Smoothing(group) => smoothing_group = group,
+                Statement::Object(name) => {
+                    // Un objeto nuevo empieza sin grupo
+                    current_object = Some(name.to_string());
+                    current_group = None;
+                }
+                Statement::Group(name) => current_group = name,
+                Statement::Face { line, corners, counts } => {
+                    let line = line_offset + line;
+                    let mut face: Vec<FaceCorner> = Vec::with_capacity(corners.len());
+                    for corner in chunk.corners[corners].iter() {
+                        let location = Location {
+                            line,
+                            column: corner.column,
+                        };
+                        let resolve = |index: i64, kind: usize| resolve_index(index, offsets[kind] + counts[kind], corner.text, location);
+                        face.push(FaceCorner {
+                            position: resolve(corner.position, 0)?,
+                            texture: corner.texture.map(|index| resolve(index, 1)).transpose()?,
+                            normal: corner.normal.map(|index| resolve(index, 2)).transpose()?,
+                        });
+                    }
+
+                    // Un polígono de n lados siempre se divide en n - 2 triángulos
+                    let count = face.len() - 2;
+                    faces.push(Face {
+                        corners: face,
+                        smoothing_group,
+                    });
+                    let name = current_group.as_ref().or(current_object.as_ref()).cloned().unwrap_or_default();
+                    match submeshes.last_mut() {
+                        Some(submesh)
+                            if submesh.name == name
+                                && submesh.object == current_object
+                                && submesh.material == current_material =>
+                        {
+                            submesh.triangle_count += count
+                        }
+                        _ => submeshes.push(SubMesh {
+                            name,
+                            object: current_object.clone(),
+                            material: current_material,
+                            first_triangle: triangle_count,
+                            triangle_count: count,
+                        }),
+                    }
+                    triangle_count += count;
+                }
+            }
+        }
+
+        // Todo lo anterior a la línea con el error ya se ha aplicado, como al leer en serie
+        if let Some(error) = chunk.error {
+            return Err(error.offset_lines(line_offset));
+        }
+        line_offset += chunk.line_count;
+    }
+
+    let mut corrected_faces: Vec<FaceCorner> = Vec::new();
+    let mut triangle_groups: Vec<u32> = Vec::new();
+
+    for face in faces
+    {
+        if face.corners.len() == 3 {
+            corrected_faces.extend_from_slice(&face.corners);
+            triangle_groups.push(face.smoothing_group);
+            continue;
+        }
+
+        let polygon: Vec<[f32; 3]> = face.corners.iter().map(|corner| vertices[corner.position]).collect();
+        for triangle in triangulate_polygon(&polygon) {
+            for corner in triangle {
+                corrected_faces.push(face.corners[corner]);
+            }
+            triangle_groups.push(face.smoothing_group);
+        }
+    }
+
+    // Las esquinas sin vn reciben normales generadas, que se añaden a la lista de normales
+    if corrected_faces.iter().any(|corner| corner.normal.is_none()) {
+        let positions: Vec<[f32; 3]> = corrected_faces.iter().map(|corner| vertices[corner.position]).collect();
+        let generated = generate_corner_normals(&positions, &triangle_groups, &options.normals);
+
+        let mut generated_indices: HashMap<[u32; 3], usize> = HashMap::new();
+        for (corner, normal) in corrected_faces.iter_mut().zip(generated) {
+            if corner.normal.is_none() {
+                let index = *generated_indices.entry(float_key(normal)).or_insert_with(|| {
+                    normals.push(normal);
+                    normals.len() - 1
+                });
+                corner.normal = Some(index);
+            }
+        }
+    }
+
+    // Cada combinación distinta de valores v/vt/vn se convierte en un único vértice. Se compara
+    // por valor y no por índice para que los archivos que repiten vt/vn (uno por esquina) también
+    // compartan vértices, y para que leer lo que escribe writer dé exactamente la misma malla.
+    let mut mesh = Mesh::default();
+    let mut welded: HashMap<VertexKey, u32> = HashMap::new();
+    for corner in corrected_faces.iter()
+    {
+        let position = vertices[corner.position];
+        let normal = corner.normal.map_or([0.0; 3], |i| normals[i]);
+        let uv = corner.texture.map_or([0.0; 2], |i| textures[i]);
+
+        let index = *welded.entry((float_key(position), float_key(normal), float_key(uv))).or_insert_with(|| {
+            mesh.vertices.push(Vertex::new(position, normal, uv));
+            (mesh.vertices.len() - 1) as u32
+        });
+        mesh.indices.push(index);
+    }
+
+    if options.tangents {
+        mesh.compute_tangents();
+    }
+
+    Ok(Model {
+        mesh,
+        materials,
+        submeshes,
+    })
+}
+
+// Convierte un índice OBJ (base 1, o negativo relativo al último elemento leído) a base 0
+fn resolve_index(index: i64, count: usize, corner: &str, location: Location) -> Result<usize, ObjError>
+{
+    let resolved = if index > 0 { index - 1 } else { count as i64 + index };
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(ObjError::IndexOutOfRange {
+            location,
+            text: corner.to_string(),
+            index,
+            count,
+        });
+    }
+
+    Ok(resolved as usize)
+}
+
+// Palabras de una línea junto con su columna (base 1)
+fn tokens(line: &str) -> impl Iterator<Item = (usize, &str)>
+{
+    let start = line.as_ptr() as usize;
+    line.split_whitespace().map(move |token| (token.as_ptr() as usize - start + 1, token))
+}
+
+// Lee hasta N números; los que falten por encima de `required` quedan a 0 y los sobrantes se ignoran
+fn parse_floats<'a, const N: usize>(
+    tokens: &mut impl Iterator<Item = (usize, &'a str)>,
+    required: usize,
+    location: Location,
+    directive: &str,
+) -> Result<[f32; N], ObjError>
+{
+    let mut values = [0.0; N];
+    for (i, value) in values.iter_mut().enumerate() {
+        match tokens.next() {
+            Some((column, text)) => *value = parse_number(text, Location { line: location.line, column })?,
+            None if i < required => {
+                return Err(ObjError::MissingValue {
+                    location,
+                    directive: directive.to_string(),
+                })
+            }
+            None => break,
+        }
+    }
+    Ok(values)
+}
+
+fn parse_number(text: &str, location: Location) -> Result<f32, ObjError>
+{
+    text.parse().map_err(|_| ObjError::ParseNumber {
+        location,
+        text: text.to_string(),
+    })
+}
+
+// Resto de la línea a partir del siguiente token (nombres que pueden contener espacios)
+fn rest_of_line<'a>(
+    line: &'a str,
+    mut tokens: impl Iterator<Item = (usize, &'a str)>,
+    location: Location,
+    directive: &str,
+) -> Result<&'a str, ObjError>
+{
+    match tokens.next() {
+        Some((column, _)) => Ok(line[column - 1..].trim_end()),
+        None => Err(ObjError::MissingValue {
+            location,
+            directive: directive.to_string(),
+        }),
+    }
+}
@@ -0,0 +1,146 @@
+use std::{fs::File, io::{self, BufRead}};
+
+use crate::material::Material;
+
+use super::{parse_floats, parse_number, rest_of_line, tokens, FileResolver, Location, ObjError, ResourceResolver};
+
+pub fn parse_mtl(file_path: &str) -> Result<Vec<Material>, ObjError> {
+    let file = File::open(file_path)?;
+    let reader = io::BufReader::new(file);
+
+    parse_mtl_from_reader(reader, &FileResolver::for_file(file_path))
+}
+
+pub fn parse_mtl_from_reader<R: BufRead>(reader: R, resolver: &dyn ResourceResolver) -> Result<Vec<Material>, ObjError> {
+    let mut materials: Vec<Material> = Vec::new();
+
+    for (line_index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let mut tokens = tokens(&line);
+
+        let (column, keyword) = match tokens.next() {
+            Some((_, keyword)) if keyword.starts_with('#') => continue,
+            Some(token) => token,
+            None => continue,
+        };
+        let location = Location {
+            line: line_index + 1,
+            column,
+        };
+
+        if keyword == "newmtl" {
+            materials.push(Material::new(rest_of_line(&line, tokens, location, keyword)?));
+            continue;
+        }
+
+        // Las directivas anteriores al primer newmtl no pertenecen a ningún material
+        let material = match materials.last_mut() {
+            Some(material) => material,
+            None => continue,
+        };
+
+        match keyword {
+            "Ka" => material.ambient = parse_color(&mut tokens, material.ambient, location, keyword)?,
+            "Kd" => material.diffuse = parse_color(&mut tokens, material.diffuse, location, keyword)?,
+            "Ks" => material.specular = parse_color(&mut tokens, material.specular, location, keyword)?,
+            "Ns" => material.shininess = parse_floats::<1>(&mut tokens, 1, location, keyword)?[0],
+            "d" => material.dissolve = parse_floats::<1>(&mut tokens, 1, location, keyword)?[0],
+            "Tr" => material.dissolve = 1.0 - parse_floats::<1>(&mut tokens, 1, location, keyword)?[0],
+            "illum" => {
+                let (column, text) = tokens.next().ok_or_else(|| ObjError::MissingValue {
+                    location,
+                    directive: keyword.to_string(),
+                })?;
+                material.illumination = text.parse().map_err(|_| ObjError::ParseNumber {
+                    location: Location { line: location.line, column },
+                    text: text.to_string(),
+                })?;
+            }
+            "map_Ka" | "map_Kd" | "map_Ks" | "map_Ns" | "map_d" | "map_Bump" | "map_bump" | "bump" => {
+                let path = resolver.resolve_texture(&parse_map_path(&line, tokens, location, keyword)?);
+                let map = match keyword {
+                    "map_Ka" => &mut material.ambient_map,
+                    "map_Kd" => &mut material.diffuse_map,
+                    "map_Ks" => &mut material.specular_map,
+                    "map_Ns" => &mut material.shininess_map,
+                    "map_d" => &mut material.dissolve_map,
+                    _ => &mut material.bump_map,
+                };
+                *map = Some(path);
+            }
+            // Directivas que no usamos (Ke, Ni, Tf, refl...)
+            _ => (),
+        }
+    }
+
+    Ok(materials)
+}
+
+fn parse_color<'a>(
+    tokens: &mut impl Iterator<Item = (usize, &'a str)>,
+    current: [f32; 3],
+    location: Location,
+    directive: &str,
+) -> Result<[f32; 3], ObjError> {
+    let args: Vec<(usize, &str)> = tokens.collect();
+
+    // Formas "Ka spectral archivo.rfl" y "Ka xyz x y z" no soportadas: se ignora la directiva
+    if let Some((_, "spectral" | "xyz")) = args.first() {
+        return Ok(current);
+    }
+
+    let values = args
+        .iter()
+        .take(3)
+        .map(|&(column, text)| parse_number(text, Location { line: location.line, column }))
+        .collect::<Result<Vec<f32>, ObjError>>()?;
+
+    match values[..] {
+        [r, g, b] => Ok([r, g, b]),
+        // Si solo viene un componente se usa para los tres canales
+        [r, ..] => Ok([r; 3]),
+        [] => Err(ObjError::MissingValue {
+            location,
+            directive: directive.to_string(),
+        }),
+    }
+}
+
+// Descarta las opciones (-s 1 1 1, -bm 0.5, ...) y devuelve la ruta de la textura
+fn parse_map_path<'a>(
+    line: &'a str,
+    tokens: impl Iterator<Item = (usize, &'a str)>,
+    location: Location,
+    directive: &str,
+) -> Result<String, ObjError> {
+    let args: Vec<(usize, &str)> = tokens.collect();
+
+    let mut i = 0;
+    while i < args.len() && args[i].1.starts_with('-') {
+        let max_values = match args[i].1 {
+            "-o" | "-s" | "-t" => 3,
+            "-mm" => 2,
+            _ => 1,
+        };
+        i += 1;
+
+        let mut taken = 0;
+        while taken < max_values && i < args.len() {
+            let text = args[i].1;
+            let is_value = matches!(text, "on" | "off") || text.len() == 1 || text.parse::<f32>().is_ok();
+            if !is_value {
+                break;
+            }
+            i += 1;
+            taken += 1;
+        }
+    }
+
+    match args.get(i) {
+        Some(&(column, _)) => Ok(line[column - 1..].trim_end().to_string()),
+        None => Err(ObjError::MissingValue {
+            location,
+            directive: directive.to_string(),
+        }),
+    }
+}
@@ -0,0 +1,79 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Cursor},
+    path::{Path, PathBuf},
+};
+
+// Decide de dónde se leen las librerías de materiales (mtllib) y cómo quedan las rutas de
+// las texturas que referencian, para poder cargar modelos desde memoria o desde un archivo
+pub trait ResourceResolver {
+    // Abre una librería referenciada con mtllib; Ok(None) si no existe y debe ignorarse
+    fn open_material_library(&self, name: &str) -> io::Result<Option<Box<dyn BufRead + '_>>>;
+
+    // Ruta que se guarda en el material para una textura (map_Kd, map_Bump...)
+    fn resolve_texture(&self, path: &str) -> String
+    {
+        path.to_string()
+    }
+}
+
+// Busca las librerías relativas a un directorio, normalmente el del archivo .obj
+pub struct FileResolver
+{
+    base_dir: PathBuf,
+}
+
+impl FileResolver {
+    pub fn new<P: AsRef<Path>>(base_dir: P) -> FileResolver
+    {
+        FileResolver {
+            base_dir: base_dir.as_ref().to_path_buf(),
+        }
+    }
+
+    // Resolver para las rutas relativas a `file_path`
+    pub fn for_file(file_path: &str) -> FileResolver
+    {
+        FileResolver::new(Path::new(file_path).parent().unwrap_or(Path::new("")))
+    }
+}
+
+impl ResourceResolver for FileResolver {
+    fn open_material_library(&self, name: &str) -> io::Result<Option<Box<dyn BufRead + '_>>>
+    {
+        let path = self.base_dir.join(name);
+        // Una librería que falta no impide cargar la geometría
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(Box::new(BufReader::new(File::open(path)?))))
+    }
+
+    // Las texturas relativas también se buscan desde el directorio base; las absolutas no cambian
+    fn resolve_texture(&self, path: &str) -> String
+    {
+        self.base_dir.join(path).to_string_lossy().into_owned()
+    }
+}
+
+// No carga ninguna librería: los materiales usados quedan con los valores por defecto
+pub struct NoResolver;
+
+impl ResourceResolver for NoResolver {
+    fn open_material_library(&self, _name: &str) -> io::Result<Option<Box<dyn BufRead + '_>>>
+    {
+        Ok(None)
+    }
+}
+
+// Cualquier función que devuelva el contenido de una librería a partir de su nombre,
+// por ejemplo para modelos incrustados con include_str! o leídos de un archivo comprimido
+impl<F> ResourceResolver for F
+where
+    F: Fn(&str) -> Option<String>,
+{
+    fn open_material_library(&self, name: &str) -> io::Result<Option<Box<dyn BufRead + '_>>>
+    {
+        Ok(self(name).map(|source| Box::new(Cursor::new(source.into_bytes())) as Box<dyn BufRead>))
+    }
+}
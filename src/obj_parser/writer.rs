@@ -0,0 +1,192 @@
+use std::{collections::HashMap, fs::File, io::{self, BufWriter, Write}, path::{self, Path, PathBuf}};
+
+use crate::{material::Material, mesh::float_key, model::Model};
+
+use super::NO_MATERIAL;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ObjWriteOptions
+{
+    // Decimales de cada número; None escribe la representación más corta que se lee igual
+    pub precision: Option<usize>,
+}
+
+// Escribe el modelo en `file_path` y, si tiene materiales, la librería .mtl junto a él
+pub fn write_obj(model: &Model, file_path: &str, options: &ObjWriteOptions) -> io::Result<()> {
+    let path = Path::new(file_path);
+
+    let library = if model.materials.is_empty() {
+        None
+    } else {
+        let library_path = path.with_extension("mtl");
+        // En el material las texturas son relativas al directorio de trabajo (así las deja
+        // FileResolver); en la librería tienen que serlo a su propio directorio
+        let directory = library_path.parent().filter(|directory| !directory.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let materials = model.materials.iter().map(|material| relative_maps(material, directory)).collect::<io::Result<Vec<_>>>()?;
+        let mut writer = BufWriter::new(File::create(&library_path)?);
+        write_mtl_to(&materials, &mut writer, options)?;
+        writer.flush()?;
+        library_path.file_name().map(|name| name.to_string_lossy().to_string())
+    };
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_obj_to(model, &mut writer, library.as_deref(), options)?;
+    writer.flush()
+}
+
+pub fn write_obj_to<W: Write>(model: &Model, writer: &mut W, library: Option<&str>, options: &ObjWriteOptions) -> io::Result<()> {
+    let mesh = &model.mesh;
+
+    if let Some(library) = library {
+        writeln!(writer, "mtllib {}", library)?;
+    }
+
+    // Cada atributo se escribe una sola vez aunque lo compartan varios vértices
+    let mut positions: HashMap<[u32; 3], usize> = HashMap::new();
+    let mut textures: HashMap<[u32; 2], usize> = HashMap::new();
+    let mut normals: HashMap<[u32; 3], usize> = HashMap::new();
+    let mut corners: Vec<[usize; 3]> = Vec::with_capacity(mesh.vertices.len());
+
+    for vertex in mesh.vertices.iter() {
+        let next = positions.len() + 1;
+        let v = *positions.entry(float_key(vertex.position)).or_insert_with(|| next);
+        if v == next {
+            write_values(writer, "v", &vertex.position, options)?;
+        }
+
+        let next = textures.len() + 1;
+        let vt = *textures.entry(float_key(vertex.uv)).or_insert_with(|| next);
+        if vt == next {
+            write_values(writer, "vt", &vertex.uv, options)?;
+        }
+
+        let next = normals.len() + 1;
+        let vn = *normals.entry(float_key(vertex.normal)).or_insert_with(|| next);
+        if vn == next {
+            write_values(writer, "vn", &vertex.normal, options)?;
+        }
+
+        corners.push([v, vt, vn]);
+    }
+
+    let mut object: Option<&str> = None;
+    let mut group: Option<&str> = None;
+    let mut material: Option<usize> = None;
+
+    for submesh in model.submeshes.iter() {
+        if submesh.object.is_some() && submesh.object.as_deref() != object {
+            object = submesh.object.as_deref();
+            writeln!(writer, "o {}", object.unwrap())?;
+            // "o" reinicia el grupo al leerlo
+            group = None;
+        }
+
+        // El nombre de la submalla es el del grupo, o el del objeto si no hay grupo
+        let wanted_group = if submesh.name.is_empty() || Some(submesh.name.as_str()) == object {
+            None
+        } else {
+            Some(submesh.name.as_str())
+        };
+        if wanted_group != group {
+            group = wanted_group;
+            writeln!(writer, "g {}", group.unwrap_or(""))?;
+        }
+
+        if submesh.material != material {
+            material = submesh.material;
+            // Sin material después de uno que sí lo tenía: hay que quitarlo explícitamente
+            let name = material.map_or(NO_MATERIAL, |index| model.materials[index].name.as_str());
+            writeln!(writer, "usemtl {}", name)?;
+        }
+
+        for triangle in mesh.indices[submesh.index_range()].chunks_exact(3) {
+            write!(writer, "f")?;
+            for &index in triangle {
+                let [v, vt, vn] = corners[index as usize];
+                write!(writer, " {}/{}/{}", v, vt, vn)?;
+            }
+            writeln!(writer)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Las rutas de las texturas se escriben tal cual están en los materiales
+pub fn write_mtl_to<W: Write>(materials: &[Material], writer: &mut W, options: &ObjWriteOptions) -> io::Result<()> {
+    for (i, material) in materials.iter().enumerate() {
+        if i > 0 {
+            writeln!(writer)?;
+        }
+        writeln!(writer, "newmtl {}", material.name)?;
+        write_values(writer, "Ns", &[material.shininess], options)?;
+        write_values(writer, "Ka", &material.ambient, options)?;
+        write_values(writer, "Kd", &material.diffuse, options)?;
+        write_values(writer, "Ks", &material.specular, options)?;
+        write_values(writer, "d", &[material.dissolve], options)?;
+        writeln!(writer, "illum {}", material.illumination)?;
+
+        let maps = [
+            ("map_Ka", &material.ambient_map),
+            ("map_Kd", &material.diffuse_map),
+            ("map_Ks", &material.specular_map),
+            ("map_Ns", &material.shininess_map),
+            ("map_d", &material.dissolve_map),
+            ("map_Bump", &material.bump_map),
+        ];
+        for (directive, map) in maps {
+            if let Some(map) = map {
+                writeln!(writer, "{} {}", directive, map)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Copia del material con las texturas relativas a `directory`; las absolutas no cambian
+fn relative_maps(material: &Material, directory: &Path) -> io::Result<Material> {
+    let mut material = material.clone();
+    let maps = [
+        &mut material.ambient_map,
+        &mut material.diffuse_map,
+        &mut material.specular_map,
+        &mut material.shininess_map,
+        &mut material.dissolve_map,
+        &mut material.bump_map,
+    ];
+    for map in maps.into_iter().flatten() {
+        if Path::new(map.as_str()).is_relative() {
+            *map = relative_path(Path::new(map.as_str()), directory)?;
+        }
+    }
+    Ok(material)
+}
+
+// Ruta que lleva desde `directory` hasta `path`, los dos relativos al directorio de trabajo
+fn relative_path(path: &Path, directory: &Path) -> io::Result<String> {
+    let (path, directory) = (path::absolute(path)?, path::absolute(directory)?);
+    let common = path.components().zip(directory.components()).take_while(|(a, b)| a == b).count();
+    // Sin nada en común (otra unidad en Windows) solo sirve la ruta absoluta
+    if common == 0 {
+        return Ok(path.to_string_lossy().into_owned());
+    }
+
+    let mut relative = PathBuf::new();
+    for _ in directory.components().skip(common) {
+        relative.push("..");
+    }
+    relative.extend(path.components().skip(common));
+    Ok(relative.to_string_lossy().into_owned())
+}
+
+fn write_values<W: Write>(writer: &mut W, directive: &str, values: &[f32], options: &ObjWriteOptions) -> io::Result<()> {
+    write!(writer, "{}", directive)?;
+    for value in values {
+        match options.precision {
+            Some(precision) => write!(writer, " {:.*}", precision, value)?,
+            None => write!(writer, " {}", value)?,
+        }
+    }
+    writeln!(writer)
+}
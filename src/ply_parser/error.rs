@@ -0,0 +1,58 @@
+use std::{error::Error, fmt, io};
+
+#[derive(Debug)]
+pub enum PlyError
+{
+    Io(io::Error),
+    // Línea (base 1) de la cabecera con el problema
+    InvalidHeader { line: usize, message: String },
+    // Solo en archivos ASCII; `line` cuenta desde el principio del archivo
+    ParseNumber { line: usize, text: String },
+    // Los datos terminan antes de leer todos los elementos que declara la cabecera
+    UnexpectedEnd { element: String },
+    IndexOutOfRange { face: usize, index: i64, count: usize },
+}
+
+impl PlyError {
+    pub(crate) fn header(line: usize, message: &str) -> PlyError
+    {
+        PlyError::InvalidHeader {
+            line,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for PlyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            PlyError::Io(e) => write!(f, "I/O error: {}", e),
+            PlyError::InvalidHeader { line, message } => write!(f, "line {}: invalid header: {}", line, message),
+            PlyError::ParseNumber { line, text } => write!(f, "line {}: invalid number '{}'", line, text),
+            PlyError::UnexpectedEnd { element } => write!(f, "data ends in the middle of element '{}'", element),
+            PlyError::IndexOutOfRange { face, index, count } => write!(
+                f,
+                "face {}: vertex index {} is out of range ({} vertices defined)",
+                face, index, count
+            ),
+        }
+    }
+}
+
+impl Error for PlyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)>
+    {
+        match self {
+            PlyError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PlyError {
+    fn from(e: io::Error) -> PlyError
+    {
+        PlyError::Io(e)
+    }
+}
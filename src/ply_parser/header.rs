@@ -0,0 +1,184 @@
+use super::PlyError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Format
+{
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ScalarType
+{
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+impl ScalarType {
+    fn parse(name: &str) -> Option<ScalarType>
+    {
+        // Los nombres antiguos (char, uchar...) y los de la revisión con tamaño explícito
+        match name {
+            "char" | "int8" => Some(ScalarType::Int8),
+            "uchar" | "uint8" => Some(ScalarType::UInt8),
+            "short" | "int16" => Some(ScalarType::Int16),
+            "ushort" | "uint16" => Some(ScalarType::UInt16),
+            "int" | "int32" => Some(ScalarType::Int32),
+            "uint" | "uint32" => Some(ScalarType::UInt32),
+            "float" | "float32" => Some(ScalarType::Float32),
+            "double" | "float64" => Some(ScalarType::Float64),
+            _ => None,
+        }
+    }
+
+    pub fn size(self) -> usize
+    {
+        match self {
+            ScalarType::Int8 | ScalarType::UInt8 => 1,
+            ScalarType::Int16 | ScalarType::UInt16 => 2,
+            ScalarType::Int32 | ScalarType::UInt32 | ScalarType::Float32 => 4,
+            ScalarType::Float64 => 8,
+        }
+    }
+
+    // Valor que corresponde a 1.0 en los colores guardados como enteros
+    pub fn color_scale(self) -> f64
+    {
+        match self {
+            ScalarType::Int8 => i8::MAX as f64,
+            ScalarType::UInt8 => u8::MAX as f64,
+            ScalarType::Int16 => i16::MAX as f64,
+            ScalarType::UInt16 => u16::MAX as f64,
+            ScalarType::Int32 => i32::MAX as f64,
+            ScalarType::UInt32 => u32::MAX as f64,
+            ScalarType::Float32 | ScalarType::Float64 => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum PropertyKind
+{
+    Scalar(ScalarType),
+    // Número de elementos seguido de los elementos
+    List { count: ScalarType, item: ScalarType },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Property
+{
+    pub name: String,
+    pub kind: PropertyKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Element
+{
+    pub name: String,
+    pub count: usize,
+    pub properties: Vec<Property>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Header
+{
+    pub format: Format,
+    pub elements: Vec<Element>,
+    pub comments: Vec<String>,
+    // Posición del primer byte de datos y número de líneas de la cabecera
+    pub body_offset: usize,
+    pub line_count: usize,
+}
+
+pub(super) fn parse_header(bytes: &[u8]) -> Result<Header, PlyError> {
+    let mut format: Option<Format> = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut comments: Vec<String> = Vec::new();
+
+    let mut offset = 0;
+    let mut line_number = 0;
+    loop {
+        line_number += 1;
+        let end = match bytes[offset..].iter().position(|&b| b == b'\n') {
+            Some(end) => offset + end,
+            None => return Err(PlyError::header(line_number, "missing end_header")),
+        };
+        let line = std::str::from_utf8(&bytes[offset..end])
+            .map_err(|_| PlyError::header(line_number, "header is not valid text"))?
+            .trim_end_matches('\r');
+        offset = end + 1;
+
+        if line_number == 1 {
+            if line.trim() != "ply" {
+                return Err(PlyError::header(1, "not a PLY file"));
+            }
+            continue;
+        }
+
+        let mut tokens = line.split_whitespace();
+        let invalid = |message: &str| PlyError::header(line_number, message);
+        match tokens.next() {
+            Some("format") => {
+                format = Some(match tokens.next() {
+                    Some("ascii") => Format::Ascii,
+                    Some("binary_little_endian") => Format::BinaryLittleEndian,
+                    Some("binary_big_endian") => Format::BinaryBigEndian,
+                    _ => return Err(invalid("unknown format")),
+                });
+                if tokens.next() != Some("1.0") {
+                    return Err(invalid("unsupported format version"));
+                }
+            }
+            Some("comment") | Some("obj_info") => {
+                let text = line.trim_start().split_once(char::is_whitespace).map_or("", |(_, text)| text.trim());
+                comments.push(text.to_string());
+            }
+            Some("element") => {
+                let name = tokens.next().ok_or_else(|| invalid("missing element name"))?;
+                let count = tokens
+                    .next()
+                    .and_then(|count| count.parse().ok())
+                    .ok_or_else(|| invalid("invalid element count"))?;
+                elements.push(Element {
+                    name: name.to_string(),
+                    count,
+                    properties: Vec::new(),
+                });
+            }
+            Some("property") => {
+                let element = elements.last_mut().ok_or_else(|| invalid("property outside of an element"))?;
+                let scalar = |name: Option<&str>| name.and_then(ScalarType::parse).ok_or_else(|| invalid("unknown property type"));
+                let kind = match tokens.next() {
+                    Some("list") => PropertyKind::List {
+                        count: scalar(tokens.next())?,
+                        item: scalar(tokens.next())?,
+                    },
+                    name => PropertyKind::Scalar(scalar(name)?),
+                };
+                let name = tokens.next().ok_or_else(|| invalid("missing property name"))?;
+                element.properties.push(Property {
+                    name: name.to_string(),
+                    kind,
+                });
+            }
+            Some("end_header") => break,
+            None => (),
+            Some(_) => return Err(invalid("unknown header keyword")),
+        }
+    }
+
+    Ok(Header {
+        format: format.ok_or_else(|| PlyError::header(line_number, "missing format"))?,
+        elements,
+        comments,
+        body_offset: offset,
+        line_count: line_number,
+    })
+}
@@ -0,0 +1,375 @@
+use std::fs;
+
+use crate::{
+    mesh::{normals::{generate_corner_normals, NormalOptions}, triangulate::triangulate_polygon, Mesh, Vertex},
+    model::Model,
+};
+
+pub mod error;
+mod header;
+
+pub use error::PlyError;
+
+use header::{parse_header, Element, Format, PropertyKind, ScalarType};
+
+// Propiedad de vértice sin equivalente en Vertex (intensidad, calidad, curvatura...), con un
+// valor por vértice en el mismo orden que Mesh::vertices
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexProperty
+{
+    pub name: String,
+    pub values: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlyModel
+{
+    // Una nube de puntos (sin elemento face) da una malla sin índices
+    pub model: Model,
+    pub properties: Vec<VertexProperty>,
+    pub comments: Vec<String>,
+}
+
+impl PlyModel {
+    pub fn property(&self, name: &str) -> Option<&[f64]>
+    {
+        self.properties.iter().find(|property| property.name == name).map(|property| property.values.as_slice())
+    }
+}
+
+// Qué campo de Vertex (o de Mesh::colors) rellena cada propiedad escalar del elemento vertex
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Target
+{
+    Position(usize),
+    Normal(usize),
+    Uv(usize),
+    Color(usize, f64),
+    Extra(usize),
+    Ignored,
+}
+
+fn vertex_target(name: &str) -> Option<Target> {
+    match name {
+        "x" => Some(Target::Position(0)),
+        "y" => Some(Target::Position(1)),
+        "z" => Some(Target::Position(2)),
+        "nx" => Some(Target::Normal(0)),
+        "ny" => Some(Target::Normal(1)),
+        "nz" => Some(Target::Normal(2)),
+        "u" | "s" | "texture_u" | "texture_s" => Some(Target::Uv(0)),
+        "v" | "t" | "texture_v" | "texture_t" => Some(Target::Uv(1)),
+        "red" | "r" | "diffuse_red" => Some(Target::Color(0, 1.0)),
+        "green" | "g" | "diffuse_green" => Some(Target::Color(1, 1.0)),
+        "blue" | "b" | "diffuse_blue" => Some(Target::Color(2, 1.0)),
+        "alpha" | "a" => Some(Target::Color(3, 1.0)),
+        _ => None,
+    }
+}
+
+pub fn parse_ply(file_path: &str) -> Result<PlyModel, PlyError> {
+    parse_ply_from_slice(&fs::read(file_path)?)
+}
+
+pub fn parse_ply_from_slice(bytes: &[u8]) -> Result<PlyModel, PlyError> {
+    let header = parse_header(bytes)?;
+    let mut body = match header.format {
+        Format::Ascii => Body::Ascii(AsciiTokens {
+            text: std::str::from_utf8(&bytes[header.body_offset..]).map_err(|_| PlyError::ParseNumber {
+                line: header.line_count + 1,
+                text: String::from("<binary data>"),
+            })?,
+            position: 0,
+            line: header.line_count + 1,
+        }),
+        format => Body::Binary {
+            bytes: &bytes[header.body_offset..],
+            position: 0,
+            big_endian: format == Format::BinaryBigEndian,
+        },
+    };
+
+    let mut vertices: Vec<Vertex> = Vec::new();
+    let mut colors: Option<Vec<[f32; 4]>> = None;
+    let mut properties: Vec<VertexProperty> = Vec::new();
+    let mut has_normals = false;
+    let mut polygons: Vec<Vec<i64>> = Vec::new();
+
+    for element in header.elements.iter() {
+        // El número de registros viene del archivo: antes de reservar memoria se comprueba que
+        // caben en los datos que quedan
+        body.check_count(element.count, body.record_size(element), &element.name)?;
+        match element.name.as_str() {
+            "vertex" => {
+                let targets = vertex_targets(element, &mut properties);
+                has_normals = targets.iter().any(|target| matches!(target, Target::Normal(_)));
+                if targets.iter().any(|target| matches!(target, Target::Color(..))) {
+                    colors = Some(Vec::with_capacity(element.count));
+                }
+                vertices.reserve(element.count);
+
+                for _ in 0..element.count {
+                    let mut vertex = Vertex::default();
+                    let mut color = [1.0; 4];
+                    for (property, target) in element.properties.iter().zip(targets.iter()) {
+                        let value = match property.kind {
+                            PropertyKind::Scalar(scalar) => body.read(scalar, &element.name)?,
+                            // Las listas en los vértices no tienen equivalente en la malla
+                            PropertyKind::List { count, item } => {
+                                body.skip_list(count, item, &element.name)?;
+                                continue;
+                            }
+                        };
+                        match *target {
+                            Target::Position(i) => vertex.position[i] = value as f32,
+                            Target::Normal(i) => vertex.normal[i] = value as f32,
+                            Target::Uv(i) => vertex.uv[i] = value as f32,
+                            Target::Color(i, scale) => color[i] = (value / scale) as f32,
+                            Target::Extra(i) => properties[i].values.push(value),
+                            Target::Ignored => (),
+                        }
+                    }
+                    vertices.push(vertex);
+                    if let Some(colors) = colors.as_mut() {
+                        colors.push(color);
+                    }
+                }
+            }
+            "face" => {
+                polygons.reserve(element.count);
+                for _ in 0..element.count {
+                    let mut polygon: Option<Vec<i64>> = None;
+                    for property in element.properties.iter() {
+                        match property.kind {
+                            PropertyKind::List { count, item } if is_face_indices(&property.name) => {
+                                let length = body.read(count, &element.name)? as usize;
+                                body.check_count(length, body.min_size(item), &element.name)?;
+                                let mut indices = Vec::with_capacity(length);
+                                for _ in 0..length {
+                                    indices.push(body.read(item, &element.name)? as i64);
+                                }
+                                polygon = Some(indices);
+                            }
+                            PropertyKind::List { count, item } => body.skip_list(count, item, &element.name)?,
+                            PropertyKind::Scalar(scalar) => {
+                                body.read(scalar, &element.name)?;
+                            }
+                        }
+                    }
+                    polygons.push(polygon.unwrap_or_default());
+                }
+            }
+            // Otros elementos (edge, material...) se leen para poder seguir, pero no se usan
+            _ => {
+                for _ in 0..element.count {
+                    for property in element.properties.iter() {
+                        match property.kind {
+                            PropertyKind::Scalar(scalar) => {
+                                body.read(scalar, &element.name)?;
+                            }
+                            PropertyKind::List { count, item } => body.skip_list(count, item, &element.name)?,
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut indices: Vec<u32> = Vec::new();
+    for (face, polygon) in polygons.iter().enumerate() {
+        if let Some(&index) = polygon.iter().find(|&&index| index < 0 || index as usize >= vertices.len()) {
+            return Err(PlyError::IndexOutOfRange {
+                face,
+                index,
+                count: vertices.len(),
+            });
+        }
+        let polygon: Vec<u32> = polygon.iter().map(|&index| index as u32).collect();
+
+        match polygon.len() {
+            0..=2 => (),
+            3 => indices.extend_from_slice(&polygon),
+            _ => {
+                let points: Vec<[f32; 3]> = polygon.iter().map(|&index| vertices[index as usize].position).collect();
+                for triangle in triangulate_polygon(&points) {
+                    indices.extend(triangle.iter().map(|&corner| polygon[corner]));
+                }
+            }
+        }
+    }
+
+    let mut mesh = Mesh::new(vertices, indices);
+    mesh.colors = colors;
+    if !has_normals && !mesh.indices.is_empty() {
+        smooth_vertex_normals(&mut mesh);
+    }
+
+    Ok(PlyModel {
+        model: Model::from_mesh(mesh),
+        properties,
+        comments: header.comments,
+    })
+}
+
+fn is_face_indices(name: &str) -> bool {
+    name == "vertex_indices" || name == "vertex_index"
+}
+
+// Destino de cada propiedad del elemento vertex; las desconocidas se añaden a `properties`
+fn vertex_targets(element: &Element, properties: &mut Vec<VertexProperty>) -> Vec<Target> {
+    element
+        .properties
+        .iter()
+        .map(|property| match (property.kind, vertex_target(&property.name)) {
+            (PropertyKind::List { .. }, _) => Target::Ignored,
+            (PropertyKind::Scalar(scalar), Some(Target::Color(channel, _))) => Target::Color(channel, scalar.color_scale()),
+            (_, Some(target)) => target,
+            (_, None) => {
+                properties.push(VertexProperty {
+                    name: property.name.clone(),
+                    values: Vec::with_capacity(element.count),
+                });
+                Target::Extra(properties.len() - 1)
+            }
+        })
+        .collect()
+}
+
+// Normales suavizadas sin cambiar el orden de los vértices, para que sigan alineados con las
+// propiedades extra y los colores
+fn smooth_vertex_normals(mesh: &mut Mesh) {
+    let corners: Vec<[f32; 3]> = mesh.indices.iter().map(|&index| mesh.vertices[index as usize].position).collect();
+    let groups = vec![1; mesh.triangle_count()];
+    let normals = generate_corner_normals(&corners, &groups, &NormalOptions::default());
+    for (&index, normal) in mesh.indices.iter().zip(normals) {
+        mesh.vertices[index as usize].normal = normal;
+    }
+}
+
+enum Body<'a>
+{
+    Ascii(AsciiTokens<'a>),
+    Binary { bytes: &'a [u8], position: usize, big_endian: bool },
+}
+
+impl Body<'_> {
+    fn remaining(&self) -> usize
+    {
+        match self {
+            Body::Ascii(tokens) => tokens.text.len() - tokens.position,
+            Body::Binary { bytes, position, .. } => bytes.len().saturating_sub(*position),
+        }
+    }
+
+    // Bytes que ocupa como mínimo un valor: en ASCII, al menos un carácter
+    fn min_size(&self, scalar: ScalarType) -> usize
+    {
+        match self {
+            Body::Ascii(_) => 1,
+            Body::Binary { .. } => scalar.size(),
+        }
+    }
+
+    // Lo mínimo que ocupa un registro del elemento, con todas sus listas vacías
+    fn record_size(&self, element: &Element) -> usize
+    {
+        element
+            .properties
+            .iter()
+            .map(|property| match property.kind {
+                PropertyKind::Scalar(scalar) => self.min_size(scalar),
+                PropertyKind::List { count, .. } => self.min_size(count),
+            })
+            .sum()
+    }
+
+    // Error si `count` valores de al menos `size` bytes no caben en lo que queda por leer. Un
+    // elemento sin propiedades tampoco puede declarar más registros que bytes quedan.
+    fn check_count(&self, count: usize, size: usize, element: &str) -> Result<(), PlyError>
+    {
+        if count > self.remaining() / size.max(1) {
+            return Err(PlyError::UnexpectedEnd {
+                element: element.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn read(&mut self, scalar: ScalarType, element: &str) -> Result<f64, PlyError>
+    {
+        let end_of_data = || PlyError::UnexpectedEnd {
+            element: element.to_string(),
+        };
+
+        match self {
+            Body::Ascii(tokens) => {
+                let (line, token) = tokens.next().ok_or_else(end_of_data)?;
+                token.parse().map_err(|_| PlyError::ParseNumber {
+                    line,
+                    text: token.to_string(),
+                })
+            }
+            Body::Binary { bytes, position, big_endian } => {
+                let size = scalar.size();
+                let mut raw = [0u8; 8];
+                raw[..size].copy_from_slice(bytes.get(*position..*position + size).ok_or_else(end_of_data)?);
+                *position += size;
+                if *big_endian {
+                    raw[..size].reverse();
+                }
+
+                Ok(match scalar {
+                    ScalarType::Int8 => raw[0] as i8 as f64,
+                    ScalarType::UInt8 => raw[0] as f64,
+                    ScalarType::Int16 => i16::from_le_bytes([raw[0], raw[1]]) as f64,
+                    ScalarType::UInt16 => u16::from_le_bytes([raw[0], raw[1]]) as f64,
+                    ScalarType::Int32 => i32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+                    ScalarType::UInt32 => u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+                    ScalarType::Float32 => f32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+                    ScalarType::Float64 => f64::from_le_bytes(raw),
+                })
+            }
+        }
+    }
+
+    fn skip_list(&mut self, count: ScalarType, item: ScalarType, element: &str) -> Result<(), PlyError>
+    {
+        let length = self.read(count, element)? as usize;
+        self.check_count(length, self.min_size(item), element)?;
+        for _ in 0..length {
+            self.read(item, element)?;
+        }
+        Ok(())
+    }
+}
+
+// Palabras de los datos ASCII junto con su línea, sin tener en cuenta los saltos de línea
+// entre elementos
+struct AsciiTokens<'a>
+{
+    text: &'a str,
+    position: usize,
+    line: usize,
+}
+
+impl<'a> AsciiTokens<'a> {
+    fn next(&mut self) -> Option<(usize, &'a str)>
+    {
+        let bytes = self.text.as_bytes();
+        while self.position < bytes.len() && bytes[self.position].is_ascii_whitespace() {
+            if bytes[self.position] == b'\n' {
+                self.line += 1;
+            }
+            self.position += 1;
+        }
+        if self.position == bytes.len() {
+            return None;
+        }
+
+        let start = self.position;
+        while self.position < bytes.len() && !bytes[self.position].is_ascii_whitespace() {
+            self.position += 1;
+        }
+        Some((self.line, &self.text[start..self.position]))
+    }
+}
@@ -0,0 +1,110 @@
+use cgmath::{InnerSpace, Matrix3, Matrix4, One, Quaternion, SquareMatrix, Vector3, Zero};
+
+use crate::model::Model;
+
+// Nodo de una jerarquía de escena; la transformación es relativa al nodo padre
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node
+{
+    pub name: Option<String>,
+    pub translation: Vector3<f32>,
+    pub rotation: Quaternion<f32>,
+    pub scale: Vector3<f32>,
+    // Índice en Scene::meshes
+    pub mesh: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+impl Default for Node {
+    fn default() -> Node
+    {
+        Node {
+            name: None,
+            translation: Vector3::zero(),
+            rotation: Quaternion::one(),
+            scale: Vector3::new(1.0, 1.0, 1.0),
+            mesh: None,
+            children: Vec::new(),
+        }
+    }
+}
+
+impl Node {
+    // T * R * S
+    pub fn local_transform(&self) -> Matrix4<f32>
+    {
+        Matrix4::from_translation(self.translation)
+            * Matrix4::from(self.rotation)
+            * Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
+    }
+
+    // Descompone una matriz sin cizalla (la única que admite glTF en un nodo) en T, R y S
+    pub fn set_transform(&mut self, matrix: Matrix4<f32>)
+    {
+        self.translation = matrix.w.truncate();
+
+        let mut scale = Vector3::new(
+            matrix.x.truncate().magnitude(),
+            matrix.y.truncate().magnitude(),
+            matrix.z.truncate().magnitude(),
+        );
+        // Una matriz con determinante negativo refleja la geometría: se lleva a la escala
+        if matrix.determinant() < 0.0 {
+            scale.x = -scale.x;
+        }
+        self.scale = scale;
+
+        let safe = |value: f32| if value == 0.0 { 1.0 } else { value };
+        let rotation = Matrix3::from_cols(
+            matrix.x.truncate() / safe(scale.x),
+            matrix.y.truncate() / safe(scale.y),
+            matrix.z.truncate() / safe(scale.z),
+        );
+        self.rotation = Quaternion::from(rotation);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Scene
+{
+    pub meshes: Vec<Model>,
+    pub nodes: Vec<Node>,
+    // Nodos sin padre desde los que se recorre la jerarquía
+    pub roots: Vec<usize>,
+}
+
+impl Scene {
+    // Transformación de cada nodo al espacio del mundo, en el mismo orden que Scene::nodes;
+    // los nodos que no cuelgan de ninguna raíz quedan con la identidad
+    pub fn world_transforms(&self) -> Vec<Matrix4<f32>>
+    {
+        let mut transforms = vec![Matrix4::identity(); self.nodes.len()];
+        let mut visited = vec![false; self.nodes.len()];
+        let mut pending: Vec<(usize, Matrix4<f32>)> = self.roots.iter().map(|&root| (root, Matrix4::identity())).collect();
+
+        while let Some((index, parent)) = pending.pop() {
+            // Una jerarquía mal formada (con ciclos) no debe colgar el recorrido
+            if visited[index] {
+                continue;
+            }
+            visited[index] = true;
+
+            let node = &self.nodes[index];
+            transforms[index] = parent * node.local_transform();
+            pending.extend(node.children.iter().map(|&child| (child, transforms[index])));
+        }
+
+        transforms
+    }
+
+    // Índice de malla y transformación al mundo de cada nodo que dibuja algo
+    pub fn mesh_instances(&self) -> Vec<(usize, Matrix4<f32>)>
+    {
+        let transforms = self.world_transforms();
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, node)| node.mesh.map(|mesh| (mesh, transforms[index])))
+            .collect()
+    }
+}
@@ -0,0 +1,54 @@
+use std::{error::Error, fmt, io};
+
+#[derive(Debug)]
+pub enum StlError
+{
+    Io(io::Error),
+    // Los errores del formato ASCII indican la línea (base 1)
+    ParseNumber { line: usize, text: String },
+    Unexpected { line: usize, expected: &'static str, found: String },
+    // Archivo vacío o demasiado corto para la cabecera binaria (80 bytes y el número de triángulos)
+    MissingHeader { found: usize },
+    // Archivo binario con menos triángulos de los que indica la cabecera
+    Truncated { expected: usize, found: usize },
+}
+
+impl fmt::Display for StlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            StlError::Io(e) => write!(f, "I/O error: {}", e),
+            StlError::ParseNumber { line, text } => write!(f, "line {}: invalid number '{}'", line, text),
+            StlError::Unexpected { line, expected, found } if found.is_empty() => {
+                write!(f, "line {}: expected '{}', found end of file", line, expected)
+            }
+            StlError::Unexpected { line, expected, found } => {
+                write!(f, "line {}: expected '{}', found '{}'", line, expected, found)
+            }
+            StlError::MissingHeader { found: 0 } => write!(f, "empty STL file"),
+            StlError::MissingHeader { found } => {
+                write!(f, "binary STL header needs 84 bytes, found {}", found)
+            }
+            StlError::Truncated { expected, found } => {
+                write!(f, "binary STL declares {} triangles but only contains {}", expected, found)
+            }
+        }
+    }
+}
+
+impl Error for StlError {
+    fn source(&self) -> Option<&(dyn Error + 'static)>
+    {
+        match self {
+            StlError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for StlError {
+    fn from(e: io::Error) -> StlError
+    {
+        StlError::Io(e)
+    }
+}
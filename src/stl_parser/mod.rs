@@ -0,0 +1,252 @@
+use std::{collections::HashMap, fs};
+
+use cgmath::{InnerSpace, Vector3};
+
+use crate::{mesh::{float_key, normals::NormalOptions, Mesh, Vertex}, model::{Model, SubMesh}};
+
+pub mod error;
+pub mod writer;
+
+pub use error::StlError;
+
+const HEADER_SIZE: usize = 80;
+// Normal, tres vértices y dos bytes de atributos
+const BINARY_TRIANGLE_SIZE: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StlOptions
+{
+    // None usa la normal de cada cara; con Some se suavizan entre caras vecinas
+    // (normalmente con un crease_angle para conservar las aristas vivas de las piezas CAD)
+    pub smooth_normals: Option<NormalOptions>,
+}
+
+// Lee un .stl ASCII o binario. Los vértices repetidos se unen y el resultado tiene la misma
+// disposición que parse_obj, con las coordenadas de textura a 0
+pub fn parse_stl(file_path: &str) -> Result<Model, StlError> {
+    parse_stl_with_options(file_path, &StlOptions::default())
+}
+
+pub fn parse_stl_with_options(file_path: &str, options: &StlOptions) -> Result<Model, StlError> {
+    parse_stl_from_slice(&fs::read(file_path)?, options)
+}
+
+pub fn parse_stl_from_slice(bytes: &[u8], options: &StlOptions) -> Result<Model, StlError> {
+    let mut builder = MeshBuilder {
+        weld_by_position: options.smooth_normals.is_some(),
+        ..MeshBuilder::default()
+    };
+
+    if is_binary(bytes) {
+        parse_binary(bytes, &mut builder)?;
+    } else {
+        parse_ascii(&String::from_utf8_lossy(bytes), &mut builder)?;
+    }
+
+    let mut model = builder.finish();
+    if let Some(normals) = &options.smooth_normals {
+        model.mesh.compute_smooth_normals(normals);
+    }
+    Ok(model)
+}
+
+// Muchos programas empiezan la cabecera binaria con "solid", así que eso solo no basta: también
+// es binario si el número de triángulos que declara cabe en el archivo (en uno de texto esos
+// cuatro bytes son letras o espacios y piden gigas) o si después de la cabecera hay bytes de
+// control que no pueden aparecer en un archivo de texto. Los bytes por encima de 127 no cuentan:
+// son los de los nombres en UTF-8 (solid pièce).
+fn is_binary(bytes: &[u8]) -> bool {
+    let start = bytes.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(bytes.len());
+    if !bytes[start..].starts_with(b"solid") {
+        return true;
+    }
+
+    let count = match bytes.get(HEADER_SIZE..HEADER_SIZE + 4) {
+        Some(count) => u32::from_le_bytes(count.try_into().unwrap()) as u64,
+        None => return false,
+    };
+    let body = &bytes[HEADER_SIZE + 4..];
+    count * BINARY_TRIANGLE_SIZE as u64 <= body.len() as u64
+        || body.iter().any(|&b| b.is_ascii_control() && !b.is_ascii_whitespace())
+}
+
+fn parse_binary(bytes: &[u8], builder: &mut MeshBuilder) -> Result<(), StlError> {
+    let count = match bytes.get(HEADER_SIZE..HEADER_SIZE + 4) {
+        Some(count) => u32::from_le_bytes(count.try_into().unwrap()) as usize,
+        None => return Err(StlError::MissingHeader { found: bytes.len() }),
+    };
+    let data = &bytes[HEADER_SIZE + 4..];
+    if data.len() / BINARY_TRIANGLE_SIZE < count {
+        return Err(StlError::Truncated {
+            expected: count,
+            found: data.len() / BINARY_TRIANGLE_SIZE,
+        });
+    }
+
+    for triangle in data.chunks_exact(BINARY_TRIANGLE_SIZE).take(count) {
+        let f = |i: usize| f32::from_le_bytes(triangle[i * 4..i * 4 + 4].try_into().unwrap());
+        let normal = [f(0), f(1), f(2)];
+        let corners = [[f(3), f(4), f(5)], [f(6), f(7), f(8)], [f(9), f(10), f(11)]];
+        builder.push_triangle(normal, corners);
+    }
+
+    Ok(())
+}
+
+fn parse_ascii(source: &str, builder: &mut MeshBuilder) -> Result<(), StlError> {
+    let mut normal: Option<[f32; 3]> = None;
+    let mut corners: Vec<[f32; 3]> = Vec::with_capacity(3);
+    let mut last_line = 0;
+
+    for (line_index, line) in source.lines().enumerate() {
+        let line_number = line_index + 1;
+        last_line = line_number;
+        let mut tokens = line.split_whitespace();
+        let unexpected = |expected: &'static str, found: &str| StlError::Unexpected {
+            line: line_number,
+            expected,
+            found: found.to_string(),
+        };
+
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+
+        match (keyword, normal.is_some()) {
+            ("solid", false) => {
+                let name = line.trim_start()["solid".len()..].trim();
+                builder.start_solid(name);
+            }
+            ("endsolid", false) => (),
+            ("facet", false) => {
+                match tokens.next() {
+                    Some("normal") => (),
+                    found => return Err(unexpected("normal", found.unwrap_or(""))),
+                }
+                normal = Some(parse_vector(&mut tokens, line_number)?);
+                corners.clear();
+            }
+            ("outer", true) => match tokens.next() {
+                Some("loop") => (),
+                found => return Err(unexpected("loop", found.unwrap_or(""))),
+            },
+            ("vertex", true) => corners.push(parse_vector(&mut tokens, line_number)?),
+            ("endloop", true) => (),
+            ("endfacet", true) => {
+                if corners.len() < 3 {
+                    return Err(unexpected("vertex", keyword));
+                }
+                // Algunos programas escriben polígonos convexos en lugar de triángulos
+                for i in 1..corners.len() - 1 {
+                    builder.push_triangle(normal.unwrap(), [corners[0], corners[i], corners[i + 1]]);
+                }
+                normal = None;
+            }
+            (_, true) => return Err(unexpected("endfacet", keyword)),
+            (_, false) => return Err(unexpected("facet", keyword)),
+        }
+    }
+
+    if normal.is_some() {
+        return Err(StlError::Unexpected {
+            line: last_line,
+            expected: "endfacet",
+            found: String::new(),
+        });
+    }
+
+    Ok(())
+}
+
+fn parse_vector<'a>(tokens: &mut impl Iterator<Item = &'a str>, line: usize) -> Result<[f32; 3], StlError> {
+    let mut values = [0.0; 3];
+    for value in values.iter_mut() {
+        let text = tokens.next().ok_or_else(|| StlError::Unexpected {
+            line,
+            expected: "number",
+            found: String::new(),
+        })?;
+        *value = text.parse().map_err(|_| StlError::ParseNumber {
+            line,
+            text: text.to_string(),
+        })?;
+    }
+    Ok(values)
+}
+
+// Normal de la cara según el orden de sus vértices; None si el triángulo es degenerado
+pub(crate) fn face_normal(corners: [[f32; 3]; 3]) -> Option<[f32; 3]> {
+    let [a, b, c] = corners.map(Vector3::from);
+    let cross = (b - a).cross(c - a);
+    if cross.magnitude2() > 0.0 {
+        Some(cross.normalize().into())
+    } else {
+        None
+    }
+}
+
+#[derive(Default)]
+struct MeshBuilder
+{
+    mesh: Mesh,
+    submeshes: Vec<SubMesh>,
+    welded: HashMap<([u32; 3], [u32; 3]), u32>,
+    solid: Option<String>,
+    // Une los vértices solo por posición; las normales se calculan después
+    weld_by_position: bool,
+}
+
+impl MeshBuilder {
+    // En ASCII cada "solid" se convierte en una submalla
+    fn start_solid(&mut self, name: &str)
+    {
+        self.solid = Some(name.to_string());
+    }
+
+    fn push_triangle(&mut self, normal: [f32; 3], corners: [[f32; 3]; 3])
+    {
+        // La normal del archivo suele venir a 0; entonces se calcula a partir del orden de los vértices
+        let stored = Vector3::from(normal);
+        let normal = if self.weld_by_position {
+            [0.0; 3]
+        } else if stored.magnitude2() > 0.0 {
+            stored.normalize().into()
+        } else {
+            face_normal(corners).unwrap_or([0.0; 3])
+        };
+
+        for position in corners {
+            let index = *self.welded.entry((float_key(position), float_key(normal))).or_insert_with(|| {
+                self.mesh.vertices.push(Vertex::new(position, normal, [0.0; 2]));
+                (self.mesh.vertices.len() - 1) as u32
+            });
+            self.mesh.indices.push(index);
+        }
+
+        let name = self.solid.clone().unwrap_or_default();
+        match self.submeshes.last_mut() {
+            Some(submesh) if submesh.name == name => submesh.triangle_count += 1,
+            _ => self.submeshes.push(SubMesh {
+                name,
+                object: None,
+                material: None,
+                first_triangle: self.mesh.triangle_count() - 1,
+                triangle_count: 1,
+            }),
+        }
+    }
+
+    fn finish(self) -> Model
+    {
+        if self.submeshes.is_empty() {
+            return Model::from_mesh(self.mesh);
+        }
+
+        Model {
+            mesh: self.mesh,
+            materials: Vec::new(),
+            submeshes: self.submeshes,
+        }
+    }
+}
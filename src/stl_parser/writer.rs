@@ -0,0 +1,75 @@
+use std::{fs::File, io::{self, BufWriter, Write}};
+
+use super::{face_normal, HEADER_SIZE};
+use crate::model::Model;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StlFormat
+{
+    Ascii,
+    Binary,
+}
+
+// STL no guarda normales por vértice, coordenadas de textura ni materiales: cada triángulo
+// se escribe con la normal de su cara
+pub fn write_stl(model: &Model, file_path: &str, format: StlFormat) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(file_path)?);
+    write_stl_to(model, &mut writer, format)?;
+    writer.flush()
+}
+
+pub fn write_stl_to<W: Write>(model: &Model, writer: &mut W, format: StlFormat) -> io::Result<()> {
+    match format {
+        StlFormat::Ascii => write_ascii(model, writer),
+        StlFormat::Binary => write_binary(model, writer),
+    }
+}
+
+fn triangle_corners(model: &Model, triangle: [u32; 3]) -> [[f32; 3]; 3] {
+    triangle.map(|index| model.mesh.vertices[index as usize].position)
+}
+
+fn write_ascii<W: Write>(model: &Model, writer: &mut W) -> io::Result<()> {
+    // Cada submalla es un "solid"; las que no tienen nombre se agrupan bajo el mismo
+    for submesh in model.submeshes.iter() {
+        let name = if submesh.name.is_empty() { "model" } else { submesh.name.as_str() };
+        writeln!(writer, "solid {}", name)?;
+
+        for triangle in model.mesh.indices[submesh.index_range()].chunks_exact(3) {
+            let corners = triangle_corners(model, [triangle[0], triangle[1], triangle[2]]);
+            let [x, y, z] = face_normal(corners).unwrap_or([0.0; 3]);
+            writeln!(writer, "  facet normal {} {} {}", x, y, z)?;
+            writeln!(writer, "    outer loop")?;
+            for [x, y, z] in corners {
+                writeln!(writer, "      vertex {} {} {}", x, y, z)?;
+            }
+            writeln!(writer, "    endloop")?;
+            writeln!(writer, "  endfacet")?;
+        }
+
+        writeln!(writer, "endsolid {}", name)?;
+    }
+
+    Ok(())
+}
+
+fn write_binary<W: Write>(model: &Model, writer: &mut W) -> io::Result<()> {
+    // La cabecera no puede empezar por "solid" o algunos lectores la tomarían por ASCII
+    let mut header = [b' '; HEADER_SIZE];
+    let title = b"binary STL written by graphics-playground";
+    header[..title.len()].copy_from_slice(title);
+    writer.write_all(&header)?;
+    writer.write_all(&(model.mesh.triangle_count() as u32).to_le_bytes())?;
+
+    for triangle in model.mesh.triangles() {
+        let corners = triangle_corners(model, triangle);
+        let normal = face_normal(corners).unwrap_or([0.0; 3]);
+        for value in normal.iter().chain(corners.iter().flatten()) {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        // Bytes de atributos, sin uso en el formato estándar
+        writer.write_all(&[0, 0])?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,300 @@
+use std::{error::Error, ffi::CString, fmt};
+
+use gl::types::{GLchar, GLenum, GLint};
+
+use crate::mesh::{Mesh, Vertex};
+
+// Ubicaciones que usan los shaders del proyecto para cada atributo de Mesh
+pub const POSITION_LOCATION: u32 = 0;
+pub const NORMAL_LOCATION: u32 = 1;
+pub const UV_LOCATION: u32 = 2;
+pub const TANGENT_LOCATION: u32 = 3;
+pub const COLOR_LOCATION: u32 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeType
+{
+    Float,
+    Byte,
+    UnsignedByte,
+    Short,
+    UnsignedShort,
+    Int,
+    UnsignedInt,
+}
+
+impl AttributeType {
+    // Tamaño en bytes de un componente
+    pub fn size(self) -> usize
+    {
+        match self {
+            AttributeType::Byte | AttributeType::UnsignedByte => 1,
+            AttributeType::Short | AttributeType::UnsignedShort => 2,
+            AttributeType::Float | AttributeType::Int | AttributeType::UnsignedInt => 4,
+        }
+    }
+
+    pub fn gl_type(self) -> GLenum
+    {
+        match self {
+            AttributeType::Float => gl::FLOAT,
+            AttributeType::Byte => gl::BYTE,
+            AttributeType::UnsignedByte => gl::UNSIGNED_BYTE,
+            AttributeType::Short => gl::SHORT,
+            AttributeType::UnsignedShort => gl::UNSIGNED_SHORT,
+            AttributeType::Int => gl::INT,
+            AttributeType::UnsignedInt => gl::UNSIGNED_INT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VertexAttribute
+{
+    pub name: String,
+    pub location: u32,
+    pub components: u32,
+    pub kind: AttributeType,
+    // Los enteros normalizados llegan al shader entre 0 y 1 (o -1 y 1)
+    pub normalized: bool,
+    // Desplazamiento en bytes desde el principio de cada vértice
+    pub offset: usize,
+}
+
+// Disposición de los atributos dentro de un buffer de vértices intercalados
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VertexLayout
+{
+    pub attributes: Vec<VertexAttribute>,
+    // Bytes por vértice
+    pub stride: usize,
+}
+
+// Atributo activo de un programa de shaders, tal como lo devuelve glGetActiveAttrib
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderAttribute
+{
+    pub name: String,
+    pub location: i32,
+    // Tipo GLSL (gl::FLOAT_VEC3...)
+    pub kind: GLenum,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError
+{
+    // Dos atributos de la disposición en la misma ubicación
+    DuplicateLocation { location: u32, names: [String; 2] },
+    // El shader lee un atributo que la disposición no tiene
+    MissingAttribute { name: String, location: i32 },
+    ComponentMismatch { name: String, location: u32, layout: u32, shader: u32 },
+    // Tipo de atributo del shader que no se puede alimentar desde VertexAttribPointer
+    UnsupportedShaderType { name: String, kind: GLenum },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            LayoutError::DuplicateLocation { location, names } => {
+                write!(f, "attributes '{}' and '{}' share location {}", names[0], names[1], location)
+            }
+            LayoutError::MissingAttribute { name, location } => {
+                write!(f, "shader attribute '{}' at location {} is not provided by the vertex layout", name, location)
+            }
+            LayoutError::ComponentMismatch { name, location, layout, shader } => write!(
+                f,
+                "attribute '{}' at location {} has {} components in the vertex layout but {} in the shader",
+                name, location, layout, shader
+            ),
+            LayoutError::UnsupportedShaderType { name, kind } => {
+                write!(f, "shader attribute '{}' has unsupported type 0x{:04x}", name, kind)
+            }
+        }
+    }
+}
+
+impl Error for LayoutError {}
+
+impl VertexLayout {
+    pub fn new() -> VertexLayout
+    {
+        VertexLayout::default()
+    }
+
+    // Añade un atributo justo detrás de los anteriores
+    pub fn with(mut self, name: &str, location: u32, components: u32, kind: AttributeType, normalized: bool) -> VertexLayout
+    {
+        self.attributes.push(VertexAttribute {
+            name: name.to_string(),
+            location,
+            components,
+            kind,
+            normalized,
+            offset: self.stride,
+        });
+        self.stride += components as usize * kind.size();
+        self
+    }
+
+    // La disposición de Vertex: posición, normal y coordenadas de textura
+    pub fn for_vertex() -> VertexLayout
+    {
+        let layout = VertexLayout::new()
+            .with("position", POSITION_LOCATION, 3, AttributeType::Float, false)
+            .with("normal", NORMAL_LOCATION, 3, AttributeType::Float, false)
+            .with("uv", UV_LOCATION, 2, AttributeType::Float, false);
+        debug_assert_eq!(layout.stride, std::mem::size_of::<Vertex>());
+        layout
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&VertexAttribute>
+    {
+        self.attributes.iter().find(|attribute| attribute.name == name)
+    }
+
+    // Llama a VertexAttribPointer y habilita cada atributo. Necesita un contexto de OpenGL con
+    // el VAO y el buffer de vértices ya enlazados.
+    pub fn configure(&self)
+    {
+        for attribute in self.attributes.iter() {
+            unsafe {
+                gl::VertexAttribPointer(
+                    attribute.location,
+                    attribute.components as GLint,
+                    attribute.kind.gl_type(),
+                    if attribute.normalized { gl::TRUE } else { gl::FALSE },
+                    self.stride as GLint,
+                    attribute.offset as *const _,
+                );
+                gl::EnableVertexAttribArray(attribute.location);
+            }
+        }
+    }
+
+    // Comprueba que la disposición alimenta todos los atributos que lee el shader con el
+    // número de componentes que espera. Los atributos que el shader no usa se permiten.
+    pub fn validate(&self, shader_attributes: &[ShaderAttribute]) -> Result<(), LayoutError>
+    {
+        for (i, attribute) in self.attributes.iter().enumerate() {
+            if let Some(other) = self.attributes[i + 1..].iter().find(|other| other.location == attribute.location) {
+                return Err(LayoutError::DuplicateLocation {
+                    location: attribute.location,
+                    names: [attribute.name.clone(), other.name.clone()],
+                });
+            }
+        }
+
+        for shader_attribute in shader_attributes {
+            let components = shader_components(shader_attribute.kind).ok_or_else(|| LayoutError::UnsupportedShaderType {
+                name: shader_attribute.name.clone(),
+                kind: shader_attribute.kind,
+            })?;
+            let attribute = self
+                .attributes
+                .iter()
+                .find(|attribute| attribute.location as i32 == shader_attribute.location)
+                .ok_or_else(|| LayoutError::MissingAttribute {
+                    name: shader_attribute.name.clone(),
+                    location: shader_attribute.location,
+                })?;
+            // Un vec4 puede leer menos componentes (el resto se completa con 0, 0, 1), pero no
+            // al revés
+            if attribute.components > components || (attribute.components < components && components != 4) {
+                return Err(LayoutError::ComponentMismatch {
+                    name: shader_attribute.name.clone(),
+                    location: attribute.location,
+                    layout: attribute.components,
+                    shader: components,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    // Como validate, consultando los atributos activos del programa enlazado
+    pub fn validate_program(&self, program: u32) -> Result<(), LayoutError>
+    {
+        self.validate(&active_attributes(program))
+    }
+}
+
+// Atributos que lee un programa enlazado, sin los predefinidos de GLSL (gl_VertexID...)
+pub fn active_attributes(program: u32) -> Vec<ShaderAttribute> {
+    let mut attributes = Vec::new();
+    unsafe {
+        let mut count = 0;
+        gl::GetProgramiv(program, gl::ACTIVE_ATTRIBUTES, &mut count);
+        let mut max_length = 0;
+        gl::GetProgramiv(program, gl::ACTIVE_ATTRIBUTE_MAX_LENGTH, &mut max_length);
+
+        for index in 0..count.max(0) as u32 {
+            let mut buffer = vec![0u8; max_length.max(1) as usize];
+            let (mut length, mut size, mut kind) = (0, 0, 0);
+            gl::GetActiveAttrib(
+                program,
+                index,
+                buffer.len() as i32,
+                &mut length,
+                &mut size,
+                &mut kind,
+                buffer.as_mut_ptr() as *mut GLchar,
+            );
+            let name = String::from_utf8_lossy(&buffer[..length.max(0) as usize]).into_owned();
+            if name.starts_with("gl_") {
+                continue;
+            }
+
+            let c_name = CString::new(name.as_str()).unwrap();
+            let location = gl::GetAttribLocation(program, c_name.as_ptr());
+            attributes.push(ShaderAttribute { name, location, kind });
+        }
+    }
+    attributes
+}
+
+// Componentes de los tipos GLSL que admite un atributo de vértice escalar o vectorial. Los
+// int/uint del shader necesitan VertexAttribIPointer: con VertexAttribPointer leerían los bits
+// de un float, así que no se admiten.
+fn shader_components(kind: GLenum) -> Option<u32> {
+    match kind {
+        gl::FLOAT => Some(1),
+        gl::FLOAT_VEC2 => Some(2),
+        gl::FLOAT_VEC3 => Some(3),
+        gl::FLOAT_VEC4 => Some(4),
+        _ => None,
+    }
+}
+
+impl Mesh {
+    // Disposición de los datos de interleaved(): la de Vertex seguida de los atributos
+    // opcionales que tenga la malla
+    pub fn layout(&self) -> VertexLayout
+    {
+        let mut layout = VertexLayout::for_vertex();
+        if self.tangents.is_some() {
+            layout = layout.with("tangent", TANGENT_LOCATION, 4, AttributeType::Float, false);
+        }
+        if self.colors.is_some() {
+            layout = layout.with("color", COLOR_LOCATION, 4, AttributeType::Float, false);
+        }
+        layout
+    }
+
+    // Vértices con todos sus atributos intercalados, listos para BufferData
+    pub fn interleaved(&self) -> Vec<f32>
+    {
+        let stride = self.layout().stride / std::mem::size_of::<f32>();
+        let mut data: Vec<f32> = Vec::with_capacity(self.vertices.len() * stride);
+        for (i, vertex) in self.vertices.iter().enumerate() {
+            data.extend_from_slice(&vertex.position);
+            data.extend_from_slice(&vertex.normal);
+            data.extend_from_slice(&vertex.uv);
+            for attribute in [&self.tangents, &self.colors].into_iter().flatten() {
+                data.extend_from_slice(&attribute[i]);
+            }
+        }
+        data
+    }
+}
@@ -0,0 +1,78 @@
+use cgmath::{InnerSpace, Vector3, Vector4};
+use graphics_playground::mesh::{
+    bounds::{Aabb, BoundingSphere},
+    Mesh, Vertex,
+};
+
+// Cubo de lado 2 con el centro en (3, -1, 5), con la normal de cada esquina hacia fuera
+fn offset_cube() -> Mesh {
+    let vertices = (0..8)
+        .map(|corner| {
+            let direction = [0, 1, 2].map(|axis| if (corner >> axis) & 1 == 1 { 1.0 } else { -1.0 });
+            let position = [3.0 + direction[0], -1.0 + direction[1], 5.0 + direction[2]];
+            Vertex::new(position, Vector3::from(direction).normalize().into(), [0.0, 0.0])
+        })
+        .collect();
+    let indices = vec![
+        0, 2, 3, 0, 3, 1, 4, 5, 7, 4, 7, 6, 0, 1, 5, 0, 5, 4, 2, 6, 7, 2, 7, 3, 0, 4, 6, 0, 6, 2, 1, 3, 7, 1, 7, 5,
+    ];
+    Mesh::new(vertices, indices)
+}
+
+#[test]
+fn aabb_of_an_offset_cube() {
+    let bounds = offset_cube().bounds().unwrap();
+    assert_eq!(bounds, Aabb { min: [2.0, -2.0, 4.0], max: [4.0, 0.0, 6.0] });
+    assert_eq!(bounds.center(), [3.0, -1.0, 5.0]);
+    assert_eq!(bounds.size(), [2.0; 3]);
+    assert_eq!(bounds.max_extent(), 2.0);
+    assert_eq!(bounds.surface_area(), 24.0);
+    assert!((bounds.diagonal() - 12f32.sqrt()).abs() < 1e-6);
+    assert!(bounds.contains([2.0, 0.0, 5.0]));
+    assert!(!bounds.contains([3.0, 0.1, 5.0]));
+
+    let other = Aabb { min: [-1.0; 3], max: [0.0; 3] };
+    assert_eq!(bounds.union(&other), Aabb { min: [-1.0, -2.0, -1.0], max: [4.0, 0.0, 6.0] });
+    assert_eq!(Mesh::default().bounds(), None);
+}
+
+#[test]
+fn bounding_sphere_of_an_offset_cube() {
+    let mesh = offset_cube();
+    let sphere = mesh.bounding_sphere().unwrap();
+    // La esfera mínima pasa por las ocho esquinas
+    let minimum = 3f32.sqrt();
+    assert!(sphere.radius >= minimum && sphere.radius <= minimum * 1.1, "{:?}", sphere);
+    assert!(mesh.vertices.iter().all(|vertex| sphere.contains(vertex.position)));
+    let center = Vector3::from(sphere.center) - Vector3::new(3.0, -1.0, 5.0);
+    assert!(center.x.abs().max(center.y.abs()).max(center.z.abs()) <= minimum * 0.1);
+
+    // Un solo punto da una esfera de radio 0 en él
+    let point = BoundingSphere::from_points(&[[1.0, 2.0, 3.0]]).unwrap();
+    assert_eq!(point, BoundingSphere { center: [1.0, 2.0, 3.0], radius: 0.0 });
+    assert_eq!(Mesh::default().bounding_sphere(), None);
+}
+
+#[test]
+fn recenter_and_normalize() {
+    let mut mesh = offset_cube();
+    assert_eq!(mesh.recenter(), [-3.0, 1.0, -5.0]);
+    assert_eq!(mesh.bounds().unwrap(), Aabb { min: [-1.0; 3], max: [1.0; 3] });
+    // Ya centrada no se mueve
+    assert_eq!(mesh.recenter(), [0.0; 3]);
+
+    let original = offset_cube();
+    let mut mesh = original.clone();
+    let matrix = mesh.normalize_to_unit_box();
+    assert_eq!(mesh.bounds().unwrap(), Aabb { min: [-0.5; 3], max: [0.5; 3] });
+    // La matriz devuelta lleva la malla original a la normalizada
+    for (before, after) in original.vertices.iter().zip(mesh.vertices.iter()) {
+        let [x, y, z] = before.position;
+        let moved = matrix * Vector4::new(x, y, z, 1.0);
+        assert_eq!([moved.x, moved.y, moved.z], after.position);
+        assert_eq!(before.normal, after.normal);
+    }
+
+    let mut empty = Mesh::default();
+    assert_eq!(empty.recenter(), [0.0; 3]);
+}
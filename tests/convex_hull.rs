@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+
+use cgmath::{InnerSpace, Vector3};
+use graphics_playground::{
+    mesh::{hull::convex_hull, Mesh},
+    obj_parser::parse_obj,
+};
+
+// Generador congruencial para que las nubes de puntos sean siempre las mismas
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> f32
+    {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+// Comprueba que la envolvente es una malla válida y cerrada, que todas sus caras son convexas y
+// que deja dentro todos los puntos
+fn check_hull(hull: &Mesh, points: &[[f32; 3]]) {
+    let report = hull.validate();
+    assert!(report.is_valid(), "{}", report);
+
+    let edges: HashSet<(u32, u32)> = hull.triangles().flat_map(|[a, b, c]| [(a, b), (b, c), (c, a)]).collect();
+    assert_eq!(edges.len(), hull.indices.len());
+    assert!(edges.iter().all(|&(a, b)| edges.contains(&(b, a))));
+    // Euler: V - E + F = 2 en una superficie cerrada sin agujeros
+    assert_eq!(hull.vertices.len() as i64 - (edges.len() / 2) as i64 + hull.triangle_count() as i64, 2);
+
+    // En f64: en las caras muy alargadas la normal calculada en f32 se desvía demasiado
+    let to_f64 = |point: [f32; 3]| Vector3::new(point[0] as f64, point[1] as f64, point[2] as f64);
+    let scale = points.iter().flatten().fold(0.0f32, |max, value| max.max(value.abs())) as f64;
+    let tolerance = scale * 1e-5;
+    let position = |index: u32| to_f64(hull.vertices[index as usize].position);
+    for [a, b, c] in hull.triangles() {
+        let normal = (position(b) - position(a)).cross(position(c) - position(a)).normalize();
+        for point in points.iter().chain(hull.vertices.iter().map(|vertex| &vertex.position)) {
+            let distance = normal.dot(to_f64(*point) - position(a));
+            assert!(distance <= tolerance, "point {:?} is {} outside the hull", point, distance);
+        }
+    }
+}
+
+fn volume(mesh: &Mesh) -> f32 {
+    let position = |index: u32| Vector3::from(mesh.vertices[index as usize].position);
+    mesh.triangles().map(|[a, b, c]| position(a).dot(position(b).cross(position(c))) / 6.0).sum()
+}
+
+#[test]
+fn hull_of_a_cube_with_inner_and_coplanar_points() {
+    let mut points: Vec<[f32; 3]> = Vec::new();
+    // Rejilla de 5x5x5: las esquinas, y muchos puntos sobre las caras y las aristas del cubo
+    for x in 0..5 {
+        for y in 0..5 {
+            for z in 0..5 {
+                points.push([x as f32 * 0.25 - 0.5, y as f32 * 0.25 - 0.5, z as f32 * 0.25 - 0.5]);
+            }
+        }
+    }
+    let mut random = Random(7);
+    for _ in 0..500 {
+        points.push([random.next() - 0.5, random.next() - 0.5, random.next() - 0.5]);
+    }
+    // Puntos repetidos
+    points.extend_from_within(0..50);
+
+    let hull = convex_hull(&points).unwrap();
+    check_hull(&hull, &points);
+    // Las caras coplanarias no dejan vértices de más
+    assert_eq!(hull.vertices.len(), 8);
+    assert_eq!(hull.triangle_count(), 12);
+    assert!((volume(&hull) - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn hull_of_points_on_a_sphere() {
+    // Todos los vértices de una esfera están en su envolvente
+    let sphere = Mesh::icosphere(2.0, 3);
+    let points: Vec<[f32; 3]> = sphere.vertices.iter().map(|vertex| vertex.position).collect();
+    let hull = convex_hull(&points).unwrap();
+    check_hull(&hull, &points);
+    // Los vértices de la costura de las UV están repetidos
+    let unique: HashSet<[u32; 3]> = points.iter().map(|point| point.map(f32::to_bits)).collect();
+    assert_eq!(hull.vertices.len(), unique.len());
+
+    let mut random = Random(42);
+    let points: Vec<[f32; 3]> = (0..2000)
+        .map(|_| {
+            let direction = Vector3::new(random.next() - 0.5, random.next() - 0.5, random.next() - 0.5).normalize();
+            (direction * (100.0 + random.next())).into()
+        })
+        .collect();
+    check_hull(&convex_hull(&points).unwrap(), &points);
+}
+
+#[test]
+fn hull_of_a_model() {
+    for path in ["flower.obj", "person.obj"] {
+        let mesh = parse_obj(path).unwrap().mesh;
+        let points: Vec<[f32; 3]> = mesh.vertices.iter().map(|vertex| vertex.position).collect();
+        let hull = mesh.convex_hull().unwrap();
+        check_hull(&hull, &points);
+        assert!(hull.vertices.len() < mesh.vertices.len());
+        assert!(volume(&hull) > 0.0);
+    }
+}
+
+#[test]
+fn degenerate_inputs_have_no_hull() {
+    assert!(convex_hull(&[]).is_none());
+    assert!(convex_hull(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]).is_none());
+    // El mismo punto muchas veces
+    assert!(convex_hull(&[[1.0, 2.0, 3.0]; 20]).is_none());
+    // Alineados
+    let line: Vec<[f32; 3]> = (0..20).map(|i| [i as f32, i as f32 * 2.0, -(i as f32)]).collect();
+    assert!(convex_hull(&line).is_none());
+    // Coplanarios, en un plano inclinado
+    let plane: Vec<[f32; 3]> = (0..100).map(|i| [(i % 10) as f32, (i / 10) as f32, (i % 10 + i / 10) as f32]).collect();
+    assert!(convex_hull(&plane).is_none());
+    // Los puntos no finitos no cuentan
+    let mut points = vec![[f32::NAN, 0.0, 0.0], [0.0, f32::INFINITY, 0.0]];
+    points.extend_from_slice(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+    assert!(convex_hull(&points).is_none());
+    points.push([0.0, 0.0, 1.0]);
+    let hull = convex_hull(&points).unwrap();
+    check_hull(&hull, &points[2..]);
+    assert_eq!(hull.vertices.len(), 4);
+}
+
+#[test]
+fn nearly_degenerate_inputs() {
+    // Una lámina casi plana: sigue teniendo volumen
+    let mut random = Random(3);
+    let slab: Vec<[f32; 3]> = (0..1000).map(|_| [random.next() * 10.0, random.next() * 10.0, random.next() * 0.01]).collect();
+    check_hull(&convex_hull(&slab).unwrap(), &slab);
+
+    // Un plano con un punto fuera y ruido por debajo de la precisión de f32 en el plano
+    let mut points: Vec<[f32; 3]> = (0..400)
+        .map(|i| {
+            let (x, y) = ((i % 20) as f32 * 0.1, (i / 20) as f32 * 0.1);
+            [x, y, 0.3 * x + 0.7 * y]
+        })
+        .collect();
+    points.push([1.0, 1.0, 5.0]);
+    check_hull(&convex_hull(&points).unwrap(), &points);
+
+    // Lejos del origen, donde la separación entre floats es mayor
+    let far: Vec<[f32; 3]> = (0..500)
+        .map(|_| [1000.0 + random.next(), -2000.0 + random.next(), 500.0 + random.next()])
+        .collect();
+    check_hull(&convex_hull(&far).unwrap(), &far);
+}
@@ -0,0 +1,202 @@
+use std::path::Path;
+
+use cgmath::{Matrix4, Point3, Quaternion, Transform, Vector3};
+use graphics_playground::{
+    gltf_parser::{load_gltf, load_gltf_from_slice, GltfError},
+    material::AlphaMode,
+};
+
+const FIXTURES: &str = "tests/fixtures";
+
+// Empaqueta un documento JSON y su buffer binario en un .glb
+fn glb(json: &str, bin: &[u8]) -> Vec<u8> {
+    let pad = |bytes: &mut Vec<u8>, fill: u8| {
+        while bytes.len() % 4 != 0 {
+            bytes.push(fill);
+        }
+    };
+    let mut json = json.as_bytes().to_vec();
+    pad(&mut json, b' ');
+    let mut bin = bin.to_vec();
+    pad(&mut bin, 0);
+
+    let mut bytes = b"glTF".to_vec();
+    bytes.extend_from_slice(&2u32.to_le_bytes());
+    bytes.extend_from_slice(&((12 + 8 + json.len() + 8 + bin.len()) as u32).to_le_bytes());
+    bytes.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(b"JSON");
+    bytes.extend_from_slice(&json);
+    bytes.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(b"BIN\0");
+    bytes.extend_from_slice(&bin);
+    bytes
+}
+
+fn positions_buffer(positions: &[[f32; 3]]) -> Vec<u8> {
+    positions.iter().flatten().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+// Documento con una sola primitiva sin índices ni normales y el modo de dibujo indicado
+fn primitive_document(count: usize, mode: u32) -> String {
+    format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "mode": {mode} }}] }}],
+            "nodes": [{{ "mesh": 0 }}],
+            "accessors": [{{ "bufferView": 0, "componentType": 5126, "count": {count}, "type": "VEC3" }}],
+            "bufferViews": [{{ "buffer": 0, "byteLength": {length} }}],
+            "buffers": [{{ "byteLength": {length} }}]
+        }}"#,
+        mode = mode,
+        count = count,
+        length = count * 12
+    )
+}
+
+fn assert_close(a: [f32; 3], b: [f32; 3]) {
+    for i in 0..3 {
+        assert!((a[i] - b[i]).abs() < 1e-5, "{:?} != {:?}", a, b);
+    }
+}
+
+#[test]
+fn quad_with_external_buffer() {
+    let scene = load_gltf("tests/fixtures/quad.gltf").unwrap();
+    assert_eq!(scene.roots, vec![0]);
+    assert_eq!(scene.nodes[0].name.as_deref(), Some("Quad"));
+    assert_eq!(scene.nodes[0].translation, Vector3::new(0.0, 0.0, -2.0));
+
+    let model = &scene.meshes[0];
+    assert_eq!(model.mesh.vertices.len(), 4);
+    assert_eq!(model.mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+    assert_eq!(model.submeshes.len(), 1);
+    assert_eq!(model.submeshes[0].name, "Quad");
+    assert_eq!(model.mesh.vertices[2].position, [1.0, 1.0, 0.0]);
+    assert_eq!(model.mesh.vertices[2].normal, [0.0, 0.0, 1.0]);
+    // v se invierte para usar el mismo origen que OBJ
+    assert_eq!(model.mesh.vertices[0].uv, [0.0, 0.0]);
+    assert_eq!(model.mesh.vertices[2].uv, [1.0, 1.0]);
+
+    let material = model.material_for(&model.submeshes[0]).unwrap();
+    assert_eq!(material.name, "Painted metal");
+    assert_eq!(material.diffuse, [0.8, 0.2, 0.1]);
+    assert_eq!(material.dissolve, 0.5);
+
+    let pbr = material.pbr.as_ref().unwrap();
+    assert_eq!(pbr.base_color, [0.8, 0.2, 0.1, 0.5]);
+    assert_eq!(pbr.metallic, 0.9);
+    assert_eq!(pbr.roughness, 0.3);
+    assert_eq!(pbr.emissive, [0.1, 0.0, 0.0]);
+    assert_eq!(pbr.alpha_mode, AlphaMode::Mask(0.25));
+    assert!(pbr.double_sided);
+    let texture = |name: &str| Some(Path::new(FIXTURES).join("textures").join(name).to_string_lossy().into_owned());
+    assert_eq!(pbr.base_color_map, texture("base color.png"));
+    assert_eq!(pbr.normal_map, texture("normal.png"));
+    assert_eq!(pbr.metallic_roughness_map, None);
+    assert_eq!(material.diffuse_map, pbr.base_color_map);
+}
+
+#[test]
+fn glb_matches_gltf() {
+    let json = std::fs::read_to_string("tests/fixtures/quad.gltf").unwrap();
+    let json = json.replace(r#""uri": "quad.bin", "#, "");
+    let bin = std::fs::read("tests/fixtures/quad.bin").unwrap();
+
+    let from_glb = load_gltf_from_slice(&glb(&json, &bin), Some(Path::new(FIXTURES))).unwrap();
+    let from_gltf = load_gltf("tests/fixtures/quad.gltf").unwrap();
+    assert_eq!(from_glb, from_gltf);
+}
+
+#[test]
+fn node_hierarchy() {
+    let scene = load_gltf("tests/fixtures/hierarchy.gltf").unwrap();
+    assert_eq!(scene.roots, vec![0]);
+    assert_eq!(scene.nodes.len(), 4);
+    assert_eq!(scene.nodes[0].children, vec![1, 2]);
+    assert_eq!(scene.nodes[0].scale, Vector3::new(2.0, 2.0, 2.0));
+
+    // La matriz del nodo hijo se descompone en TRS
+    let child = &scene.nodes[1];
+    assert_eq!(child.translation, Vector3::new(5.0, 0.0, 0.0));
+    assert_eq!(child.scale, Vector3::new(1.0, 1.0, 1.0));
+    assert_eq!(child.rotation, Quaternion::new(1.0, 0.0, 0.0, 0.0));
+
+    // Raíz: escala 2, giro de 90 grados en Y y traslación (1, 2, 3)
+    let transforms = scene.world_transforms();
+    let origin = |matrix: Matrix4<f32>| -> [f32; 3] { matrix.transform_point(Point3::new(0.0, 0.0, 0.0)).into() };
+    assert_close(origin(transforms[0]), [1.0, 2.0, 3.0]);
+    assert_close(origin(transforms[1]), [1.0, 2.0, -7.0]);
+    let corner: [f32; 3] = transforms[3].transform_point(Point3::new(0.0, 0.0, 1.0)).into();
+    assert_close(corner, [-1.0, 2.0, 3.0]);
+
+    let instances = scene.mesh_instances();
+    assert_eq!(instances.len(), 2);
+    assert!(instances.iter().all(|&(mesh, _)| mesh == 0));
+
+    // Sin normales en el archivo se generan normales planas
+    let mesh = &scene.meshes[0].mesh;
+    assert_eq!(mesh.indices.len(), 3);
+    assert!(mesh.vertices.iter().all(|vertex| vertex.normal == [0.0, 0.0, 1.0]));
+}
+
+#[test]
+fn strips_and_fans() {
+    let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 1.0, 0.0], [0.0, 2.0, 0.0]];
+    let bin = positions_buffer(&positions);
+
+    let strip = load_gltf_from_slice(&glb(&primitive_document(5, 5), &bin), None).unwrap();
+    let mesh = &strip.meshes[0].mesh;
+    assert_eq!(mesh.triangle_count(), 3);
+    // Todos los triángulos de la tira conservan el sentido antihorario
+    assert!(mesh.vertices.iter().all(|vertex| vertex.normal == [0.0, 0.0, 1.0]));
+
+    let fan = load_gltf_from_slice(&glb(&primitive_document(5, 6), &bin), None).unwrap();
+    assert_eq!(fan.meshes[0].mesh.triangle_count(), 3);
+    // Sin escenas, las raíces son los nodos que no son hijos de nadie
+    assert_eq!(fan.roots, vec![0]);
+
+    let points = load_gltf_from_slice(&glb(&primitive_document(5, 0), &bin), None);
+    assert!(matches!(points, Err(GltfError::Unsupported { .. })));
+}
+
+#[test]
+fn invalid_documents() {
+    let bin = positions_buffer(&[[0.0; 3]; 3]);
+
+    let old = primitive_document(3, 4).replace("2.0", "1.0");
+    assert!(matches!(load_gltf_from_slice(&glb(&old, &bin), None), Err(GltfError::UnsupportedVersion(_))));
+
+    // El accessor pide más datos de los que tiene su bufferView
+    let overflow = primitive_document(3, 4).replace(r#""count": 3"#, r#""count": 4"#);
+    match load_gltf_from_slice(&glb(&overflow, &bin), None) {
+        Err(GltfError::Invalid { path, .. }) => assert_eq!(path, "accessors[0]"),
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    // Un count que desborda el tamaño en bytes, con y sin bufferView
+    let huge = primitive_document(3, 4).replace(r#""count": 3"#, r#""count": 4000000000000000000"#);
+    let without_view = huge.replace(r#""bufferView": 0, "#, "");
+    for document in [huge, without_view] {
+        match load_gltf_from_slice(&glb(&document, &bin), None) {
+            Err(GltfError::Invalid { path, message }) => {
+                assert_eq!(path, "accessors[0]");
+                assert_eq!(message, "accessor size overflows");
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+    // Un byteStride enorme desborda la posición del último elemento
+    let stride = primitive_document(3, 4).replace(r#""byteLength": 36 }],"#, r#""byteLength": 36, "byteStride": 9223372036854775807 }],"#);
+    assert!(stride.contains("byteStride"));
+    assert!(matches!(load_gltf_from_slice(&glb(&stride, &bin), None), Err(GltfError::Invalid { .. })));
+
+    let extension = primitive_document(3, 4).replacen('{', r#"{ "extensionsRequired": ["KHR_draco_mesh_compression"],"#, 1);
+    assert!(matches!(load_gltf_from_slice(&glb(&extension, &bin), None), Err(GltfError::Unsupported { .. })));
+
+    // Un buffer externo necesita un directorio desde el que leerlo
+    let quad = std::fs::read("tests/fixtures/quad.gltf").unwrap();
+    assert!(matches!(load_gltf_from_slice(&quad, None), Err(GltfError::Resource { .. })));
+
+    assert!(matches!(load_gltf_from_slice(b"{ not json", None), Err(GltfError::Json(_))));
+    assert!(matches!(load_gltf_from_slice(b"glTF\x02\0\0\0", None), Err(GltfError::InvalidGlb(_))));
+}
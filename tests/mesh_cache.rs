@@ -0,0 +1,144 @@
+use std::{
+    fs::{self, File},
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use graphics_playground::{
+    mesh_cache::{read_model, write_model, CacheError, MeshCache, SourceKey},
+    obj_parser::{parse_obj, ObjOptions},
+};
+
+const OBJ: &str = "mtllib scene.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl red\nf 1 2 3\n";
+const MTL: &str = "newmtl red\nKd 1 0 0\n";
+
+// Directorio temporal propio de cada prueba con un .obj y su librería de materiales
+fn scene(name: &str) -> (PathBuf, String) {
+    let directory = std::env::temp_dir().join(format!("graphics_playground_cache_{}", name));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("scene.obj"), OBJ).unwrap();
+    fs::write(directory.join("scene.mtl"), MTL).unwrap();
+    let obj = directory.join("scene.obj").to_str().unwrap().to_string();
+    (directory, obj)
+}
+
+// Cambia la fecha de modificación sin tocar el tamaño
+fn touch(path: PathBuf, seconds: u64) {
+    let time = SystemTime::UNIX_EPOCH + Duration::from_secs(seconds);
+    File::options().write(true).open(path).unwrap().set_modified(time).unwrap();
+}
+
+#[test]
+fn person_round_trip() {
+    let model = parse_obj("person.obj").unwrap();
+    let key = SourceKey::from_file("person.obj", &ObjOptions::default()).unwrap();
+    // La librería no está en el repositorio, pero el nombre se guarda igualmente
+    assert_eq!(key.libraries, [("FinalBaseMesh.mtl".to_string(), None)]);
+
+    let mut bytes = Vec::new();
+    write_model(&model, Some(&key), &mut bytes).unwrap();
+    let (cached_key, cached) = read_model(&bytes).unwrap();
+    assert_eq!(cached_key, Some(key));
+    assert_eq!(cached, model);
+
+    let mut bytes = Vec::new();
+    write_model(&model, None, &mut bytes).unwrap();
+    assert_eq!(read_model(&bytes).unwrap(), (None, model));
+}
+
+#[test]
+fn changed_sources_are_parsed_again() {
+    let (directory, obj) = scene("changed");
+    let cache = MeshCache::new(directory.join("cache"));
+    let options = ObjOptions::default();
+
+    let model = cache.load_obj(&obj, &options).unwrap();
+    assert_eq!(model.materials[0].diffuse, [1.0, 0.0, 0.0]);
+    let key = SourceKey::from_file(&obj, &options).unwrap();
+    assert!(cache.entry_path(&key).exists());
+    assert_eq!(cache.load_obj(&obj, &options).unwrap(), model);
+
+    // Otro tamaño: una cara más
+    fs::write(&obj, format!("{}f 3 2 1\n", OBJ)).unwrap();
+    assert_eq!(cache.load_obj(&obj, &options).unwrap().mesh.triangle_count(), 2);
+
+    // Mismo tamaño, otra fecha
+    fs::write(&obj, OBJ.replace("f 1 2 3", "f 3 2 1")).unwrap();
+    touch(directory.join("scene.obj"), 1_000_000);
+    let reversed = cache.load_obj(&obj, &options).unwrap();
+    assert_eq!(reversed.mesh.triangle_count(), 1);
+    assert_eq!(reversed.mesh.vertices[0].position, [0.0, 1.0, 0.0]);
+
+    // Solo cambia la librería de materiales
+    fs::write(directory.join("scene.mtl"), MTL.replace("Kd 1 0 0", "Kd 0 0 1")).unwrap();
+    touch(directory.join("scene.mtl"), 2_000_000);
+    assert_eq!(cache.load_obj(&obj, &options).unwrap().materials[0].diffuse, [0.0, 0.0, 1.0]);
+
+    // Una librería que aparece después también invalida la entrada
+    fs::remove_file(directory.join("scene.mtl")).unwrap();
+    assert_eq!(cache.load_obj(&obj, &options).unwrap().materials[0].diffuse, [0.8, 0.8, 0.8]);
+    fs::write(directory.join("scene.mtl"), MTL).unwrap();
+    assert_eq!(cache.load_obj(&obj, &options).unwrap().materials[0].diffuse, [1.0, 0.0, 0.0]);
+
+    // Con otras opciones la entrada tampoco vale
+    let tangents = ObjOptions { tangents: true, ..ObjOptions::default() };
+    assert!(cache.load_obj(&obj, &tangents).unwrap().mesh.tangents.is_some());
+}
+
+#[test]
+fn invalid_headers_are_rejected() {
+    let model = parse_obj("cube.obj").unwrap();
+    let mut bytes = Vec::new();
+    write_model(&model, None, &mut bytes).unwrap();
+
+    let mut bad_magic = bytes.clone();
+    bad_magic[0] = b'X';
+    assert!(matches!(read_model(&bad_magic), Err(CacheError::BadMagic)));
+
+    let mut bad_version = bytes.clone();
+    bad_version[4..8].copy_from_slice(&99u32.to_le_bytes());
+    assert!(matches!(read_model(&bad_version), Err(CacheError::UnsupportedVersion(99))));
+
+    // Una entrada que no se puede leer se sustituye por el modelo recién parseado
+    let (directory, obj) = scene("invalid");
+    let cache = MeshCache::new(directory.join("cache"));
+    let options = ObjOptions::default();
+    let model = cache.load_obj(&obj, &options).unwrap();
+    let entry = cache.entry_path(&SourceKey::from_file(&obj, &options).unwrap());
+    for contents in [&bad_magic, &bad_version] {
+        fs::write(&entry, contents).unwrap();
+        assert_eq!(cache.load_obj(&obj, &options).unwrap(), model);
+        assert_eq!(read_model(&fs::read(&entry).unwrap()).unwrap().1, model);
+    }
+}
+
+#[test]
+fn corrupted_bytes_do_not_panic() {
+    let model = parse_obj("cube.obj").unwrap();
+    let key = SourceKey::from_file("cube.obj", &ObjOptions::default()).unwrap();
+    let mut bytes = Vec::new();
+    write_model(&model, Some(&key), &mut bytes).unwrap();
+
+    // Cualquier archivo cortado es un error
+    for length in 0..bytes.len() {
+        assert!(read_model(&bytes[..length]).is_err(), "{} bytes", length);
+    }
+
+    // Bytes cambiados: puede salir un error o un modelo distinto, pero nunca un pánico
+    for position in 8..bytes.len() {
+        for value in [0x00, 0x7f, 0xff] {
+            let mut corrupted = bytes.clone();
+            corrupted[position] = value;
+            if let Ok((_, model)) = read_model(&corrupted) {
+                assert!(model.mesh.indices.iter().all(|&index| (index as usize) < model.mesh.vertices.len()));
+            }
+        }
+    }
+
+    // Contadores enormes no reservan memoria antes de comprobar que los datos existen
+    let mut huge = Vec::new();
+    write_model(&model, None, &mut huge).unwrap();
+    huge[9..13].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(read_model(&huge), Err(CacheError::Truncated)));
+}
@@ -0,0 +1,147 @@
+use std::{fs, path::Path};
+
+use graphics_playground::{
+    model::Model,
+    obj_parser::{
+        parse_obj,
+        writer::{write_obj, ObjWriteOptions},
+    },
+};
+
+fn round_trip(file_path: &str, output_name: &str, options: &ObjWriteOptions) -> (Model, Model) {
+    let model = parse_obj(file_path).unwrap();
+
+    let name = format!("graphics_playground_{}", output_name);
+    let output = std::env::temp_dir().join(name);
+    write_obj(&model, output.to_str().unwrap(), options).unwrap();
+
+    let reparsed = parse_obj(output.to_str().unwrap()).unwrap();
+    (model, reparsed)
+}
+
+#[test]
+fn cube_round_trip() {
+    let (model, reparsed) = round_trip("cube.obj", "cube.obj", &ObjWriteOptions::default());
+    assert_eq!(model, reparsed);
+    assert_eq!(reparsed.materials.len(), 1);
+    assert_eq!(reparsed.submeshes[0].name, "Cube");
+}
+
+#[test]
+fn sphere_round_trip() {
+    let (model, reparsed) = round_trip("sphere.obj", "sphere.obj", &ObjWriteOptions::default());
+    assert_eq!(model, reparsed);
+}
+
+#[test]
+fn fixed_precision_round_trip() {
+    // cube.obj ya viene con 6 decimales, así que no se pierde nada
+    let (model, reparsed) = round_trip("cube.obj", "cube_fixed.obj", &ObjWriteOptions { precision: Some(6) });
+    assert_eq!(model, reparsed);
+
+    let output = std::env::temp_dir().join("graphics_playground_cube_fixed.obj");
+    let text = std::fs::read_to_string(output).unwrap();
+    assert!(text.contains("v 1.000000 -1.000000 -1.000000"));
+}
+
+#[test]
+fn groups_and_materials_round_trip() {
+    let source = std::env::temp_dir().join("graphics_playground_groups.obj");
+    std::fs::write(
+        &source,
+        "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nvn 0 0 1\n\
+         f 1//1 2//1 3//1\n\
+         o A\ng x y\nf 2//1 4//1 3//1\nusemtl red\nf 1//1 2//1 4//1\ng\nf 1//1 4//1 3//1\n",
+    )
+    .unwrap();
+    let model = parse_obj(source.to_str().unwrap()).unwrap();
+
+    let output = std::env::temp_dir().join("graphics_playground_groups_out.obj");
+    write_obj(&model, output.to_str().unwrap(), &ObjWriteOptions::default()).unwrap();
+    let reparsed = parse_obj(output.to_str().unwrap()).unwrap();
+
+    assert_eq!(model, reparsed);
+    assert_eq!(reparsed.submeshes.len(), 4);
+}
+
+#[test]
+fn procedural_mesh_round_trip() {
+    let mut model = parse_obj("cube.obj").unwrap();
+    model.mesh.compute_flat_normals();
+    let model = Model::from_mesh(model.mesh);
+
+    let output = std::env::temp_dir().join("graphics_playground_procedural.obj");
+    write_obj(&model, output.to_str().unwrap(), &ObjWriteOptions::default()).unwrap();
+    let reparsed = parse_obj(output.to_str().unwrap()).unwrap();
+
+    assert_eq!(model.mesh, reparsed.mesh);
+}
+
+#[test]
+fn material_is_cleared_after_a_materialed_submesh() {
+    // La primera y la última cara no tienen material; la del medio sí
+    let source = std::env::temp_dir().join("graphics_playground_no_material.obj");
+    std::fs::write(
+        &source,
+        "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nvn 0 0 1\n\
+         f 1//1 2//1 3//1\n\
+         g x\nusemtl red\nf 2//1 4//1 3//1\n\
+         g y\nusemtl (null)\nf 1//1 4//1 3//1\n",
+    )
+    .unwrap();
+    let model = parse_obj(source.to_str().unwrap()).unwrap();
+    assert_eq!(model.materials.len(), 1);
+    assert_eq!(model.submeshes.iter().map(|submesh| submesh.material).collect::<Vec<_>>(), [None, Some(0), None]);
+
+    let output = std::env::temp_dir().join("graphics_playground_no_material_out.obj");
+    write_obj(&model, output.to_str().unwrap(), &ObjWriteOptions::default()).unwrap();
+    let reparsed = parse_obj(output.to_str().unwrap()).unwrap();
+    assert_eq!(model, reparsed);
+
+    // También sin pasar por el archivo: una submalla sin material detrás de otra con material
+    let mut model = Model::from_mesh(model.mesh);
+    model.materials = reparsed.materials.clone();
+    model.submeshes[0].triangle_count = 1;
+    model.submeshes[0].material = Some(0);
+    let mut rest = model.submeshes[0].clone();
+    rest.name = "rest".to_string();
+    rest.first_triangle = 1;
+    rest.triangle_count = 2;
+    rest.material = None;
+    model.submeshes.push(rest);
+    write_obj(&model, output.to_str().unwrap(), &ObjWriteOptions::default()).unwrap();
+    let reparsed = parse_obj(output.to_str().unwrap()).unwrap();
+    assert_eq!(reparsed.submeshes.len(), 2);
+    assert_eq!(reparsed.submeshes[1].material, None);
+    assert_eq!(reparsed.materials.len(), 1);
+}
+
+#[test]
+fn texture_paths_round_trip_through_a_subdirectory() {
+    // Rutas relativas al directorio de trabajo, como las de parse_obj("cube.obj")
+    let root = Path::new("target").join("graphics_playground_textures");
+    let (models, output) = (root.join("models"), root.join("out"));
+    fs::create_dir_all(models.join("tex")).unwrap();
+    fs::create_dir_all(&output).unwrap();
+    fs::write(models.join("tex/wood.png"), b"").unwrap();
+    fs::write(models.join("scene.mtl"), "newmtl wood\nmap_Kd tex/wood.png\nmap_Bump /absolute/normal.png\n").unwrap();
+    fs::write(models.join("scene.obj"), "mtllib scene.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl wood\nf 1 2 3\n").unwrap();
+
+    let model = parse_obj(models.join("scene.obj").to_str().unwrap()).unwrap();
+    let texture = models.join("tex/wood.png");
+    assert_eq!(model.materials[0].diffuse_map.as_deref().map(Path::new), Some(texture.as_path()));
+
+    // Junto al original la ruta queda como estaba; en otro directorio sube hasta el de los modelos
+    for (directory, written) in [(&models, "tex/wood.png"), (&output, "../models/tex/wood.png")] {
+        let path = directory.join("scene_out.obj");
+        write_obj(&model, path.to_str().unwrap(), &ObjWriteOptions::default()).unwrap();
+        let library = fs::read_to_string(directory.join("scene_out.mtl")).unwrap();
+        assert!(library.contains(&format!("map_Kd {}\n", Path::new(written).display())), "{}", library);
+        assert!(library.contains("map_Bump /absolute/normal.png\n"));
+
+        let reparsed = parse_obj(path.to_str().unwrap()).unwrap();
+        let reparsed_texture = reparsed.materials[0].diffuse_map.clone().unwrap();
+        assert_eq!(fs::canonicalize(reparsed_texture).unwrap(), fs::canonicalize(&texture).unwrap());
+        assert_eq!(reparsed.materials[0].bump_map.as_deref(), Some("/absolute/normal.png"));
+    }
+}
@@ -0,0 +1,100 @@
+use graphics_playground::obj_parser::{
+    parse_obj_from_str, parse_obj_from_str_parallel, parse_obj_parallel, parse_obj_with_options, FileResolver, Location,
+    NoResolver, ObjOptions,
+};
+
+const THREADS: [usize; 5] = [1, 2, 3, 7, 16];
+
+fn assert_same_as_serial(file_path: &str) {
+    let options = ObjOptions::default();
+    let serial = parse_obj_with_options(file_path, &options).unwrap();
+    let source = std::fs::read_to_string(file_path).unwrap();
+
+    for threads in THREADS {
+        let parallel = parse_obj_from_str_parallel(&source, &FileResolver::for_file(file_path), &options, threads).unwrap();
+        assert_eq!(serial, parallel, "{} with {} threads", file_path, threads);
+    }
+    assert_eq!(serial, parse_obj_parallel(file_path, &options).unwrap());
+}
+
+#[test]
+fn cube_matches_serial() {
+    assert_same_as_serial("cube.obj");
+}
+
+#[test]
+fn sphere_matches_serial() {
+    assert_same_as_serial("sphere.obj");
+}
+
+#[test]
+fn flower_matches_serial() {
+    assert_same_as_serial("flower.obj");
+}
+
+#[test]
+fn person_matches_serial() {
+    assert_same_as_serial("person.obj");
+}
+
+// Índices negativos, grupos y materiales repartidos entre varios trozos
+const MIXED: &str = "\
+o First
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 1
+usemtl red
+s 1
+f -4/1 -3/2 -2/1 -1/2
+g second
+v 0 0 1
+v 1 0 1
+v 1 1 1
+vn 0 0 1
+usemtl blue
+f 5//1 6//1 7//1
+f -1//-1 -2//-1 1//-1
+s off
+o Second
+f 1 -1 -2 2
+";
+
+#[test]
+fn relative_indices_match_serial() {
+    let options = ObjOptions::default();
+    let serial = parse_obj_from_str(MIXED, &options).unwrap();
+    assert_eq!(serial.submeshes.len(), 3);
+
+    for threads in 1..=MIXED.lines().count() + 1 {
+        let parallel = parse_obj_from_str_parallel(MIXED, &NoResolver, &options, threads).unwrap();
+        assert_eq!(serial, parallel, "{} threads", threads);
+    }
+}
+
+#[test]
+fn errors_match_serial() {
+    let sources = [
+        // Índice fuera de rango en el último trozo
+        "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\nv 1 1 1\nf 1 2 9\n",
+        // Número mal escrito lejos del principio
+        "v 0 0 0\nv 1 0 0\nv 0 1 0\n\n# comentario\nf 1 2 3\nv 1 x 1\n",
+        // El índice relativo no puede llegar a vértices posteriores
+        "v 0 0 0\nv 1 0 0\nf -1 -2 -3\nv 0 1 0\n",
+        "v 0 0 0\nfoo 1\n",
+    ];
+
+    for source in sources {
+        let serial = parse_obj_from_str(source, &ObjOptions::default()).unwrap_err();
+        for threads in 2..=8 {
+            let parallel = parse_obj_from_str_parallel(source, &NoResolver, &ObjOptions::default(), threads).unwrap_err();
+            assert_eq!(serial.to_string(), parallel.to_string());
+            assert_eq!(serial.location(), parallel.location());
+        }
+    }
+
+    let error = parse_obj_from_str_parallel(sources[1], &NoResolver, &ObjOptions::default(), 4).unwrap_err();
+    assert_eq!(error.location(), Some(Location { line: 7, column: 5 }));
+}
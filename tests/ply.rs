@@ -0,0 +1,163 @@
+use graphics_playground::ply_parser::{parse_ply_from_slice, PlyError, PlyModel};
+
+const VERTICES: [([f32; 3], [u8; 3], f32); 5] = [
+    ([0.0, 0.0, 0.0], [255, 0, 0], 0.5),
+    ([1.0, 0.0, 0.0], [0, 255, 0], 1.0),
+    ([1.0, 1.0, 0.0], [0, 0, 255], 1.5),
+    ([0.0, 1.0, 0.0], [255, 255, 255], 2.0),
+    ([0.5, 0.5, 1.0], [0, 0, 0], 2.5),
+];
+const FACES: [&[i32]; 2] = [&[0, 1, 2, 3], &[1, 4, 2]];
+
+fn header(format: &str, vertex_count: usize, face_count: Option<usize>) -> String {
+    let mut header = format!(
+        "ply\nformat {} 1.0\ncomment made by hand\nelement vertex {}\n\
+         property float x\nproperty float y\nproperty float z\n\
+         property uchar red\nproperty uchar green\nproperty uchar blue\nproperty float quality\n",
+        format, vertex_count
+    );
+    if let Some(face_count) = face_count {
+        header += &format!("element face {}\nproperty list uchar int vertex_indices\n", face_count);
+    }
+    header + "end_header\n"
+}
+
+fn ascii(faces: bool) -> Vec<u8> {
+    let mut text = header("ascii", VERTICES.len(), faces.then_some(FACES.len()));
+    for (position, color, quality) in VERTICES {
+        text += &format!("{} {} {} {} {} {} {}\n", position[0], position[1], position[2], color[0], color[1], color[2], quality);
+    }
+    for face in FACES.iter().filter(|_| faces) {
+        text += &face.len().to_string();
+        for index in face.iter() {
+            text += &format!(" {}", index);
+        }
+        text += "\n";
+    }
+    text.into_bytes()
+}
+
+fn binary(big_endian: bool) -> Vec<u8> {
+    let format = if big_endian { "binary_big_endian" } else { "binary_little_endian" };
+    let mut bytes = header(format, VERTICES.len(), Some(FACES.len())).into_bytes();
+    let word = |value: [u8; 4]| if big_endian { [value[3], value[2], value[1], value[0]] } else { value };
+    for (position, color, quality) in VERTICES {
+        for value in position {
+            bytes.extend(word(value.to_le_bytes()));
+        }
+        bytes.extend(color);
+        bytes.extend(word(quality.to_le_bytes()));
+    }
+    for face in FACES {
+        bytes.push(face.len() as u8);
+        for &index in face {
+            bytes.extend(word(index.to_le_bytes()));
+        }
+    }
+    bytes
+}
+
+fn parse(bytes: &[u8]) -> PlyModel {
+    parse_ply_from_slice(bytes).unwrap()
+}
+
+fn assert_unexpected_end(bytes: &[u8], expected: &str) {
+    match parse_ply_from_slice(bytes) {
+        Err(PlyError::UnexpectedEnd { element }) => assert_eq!(element, expected),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn ascii_mesh() {
+    let ply = parse(&ascii(true));
+    let mesh = &ply.model.mesh;
+    assert_eq!(ply.comments, ["made by hand"]);
+
+    // El orden de los vértices no cambia; el cuadrado da dos triángulos
+    assert_eq!(mesh.vertices.len(), 5);
+    for (vertex, (position, _, _)) in mesh.vertices.iter().zip(VERTICES) {
+        assert_eq!(vertex.position, position);
+    }
+    assert_eq!(mesh.triangle_count(), 3);
+    assert_eq!(&mesh.indices[6..], [1, 4, 2]);
+
+    // Sin nx/ny/nz las normales se calculan, y la del vértice 0 solo ve el cuadrado
+    assert_eq!(mesh.vertices[0].normal, [0.0, 0.0, 1.0]);
+    assert!(mesh.validate().is_valid());
+}
+
+#[test]
+fn binary_matches_ascii() {
+    let ascii = parse(&ascii(true));
+    assert_eq!(parse(&binary(false)), ascii);
+    assert_eq!(parse(&binary(true)), ascii);
+}
+
+#[test]
+fn colors_and_extra_properties() {
+    let ply = parse(&ascii(true));
+    let colors = ply.model.mesh.colors.as_ref().unwrap();
+    assert_eq!(colors[0], [1.0, 0.0, 0.0, 1.0]);
+    assert_eq!(colors[3], [1.0; 4]);
+    assert_eq!(colors[4], [0.0, 0.0, 0.0, 1.0]);
+
+    assert_eq!(ply.properties.len(), 1);
+    assert_eq!(ply.property("quality"), Some(&[0.5, 1.0, 1.5, 2.0, 2.5][..]));
+    assert_eq!(ply.property("red"), None);
+
+    // Colores en coma flotante, sin escalar
+    let text = "ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nproperty float y\nproperty float z\n\
+                property float red\nproperty float green\nproperty float blue\nend_header\n0 0 0 0.25 0.5 0.75\n";
+    assert_eq!(parse(text.as_bytes()).model.mesh.colors, Some(vec![[0.25, 0.5, 0.75, 1.0]]));
+}
+
+#[test]
+fn point_cloud_without_faces() {
+    let ply = parse(&ascii(false));
+    let mesh = &ply.model.mesh;
+    assert_eq!(mesh.vertices.len(), 5);
+    assert!(mesh.indices.is_empty());
+    assert!(mesh.vertices.iter().all(|vertex| vertex.normal == [0.0; 3]));
+    assert_eq!(ply.property("quality").unwrap().len(), 5);
+}
+
+#[test]
+fn truncated_data() {
+    let bytes = binary(false);
+    assert_unexpected_end(&bytes[..bytes.len() - 2], "face");
+    let header_length = header("binary_little_endian", 5, Some(2)).len();
+    assert_unexpected_end(&bytes[..header_length + 30], "vertex");
+
+    let text = ascii(true);
+    let cut = text.len() - " 4 2\n".len();
+    assert_unexpected_end(&text[..cut], "face");
+
+    // Índices fuera de rango
+    let text = String::from_utf8(ascii(true)).unwrap().replace("3 1 4 2", "3 1 5 2");
+    assert!(matches!(parse_ply_from_slice(text.as_bytes()), Err(PlyError::IndexOutOfRange { face: 1, index: 5, count: 5 })));
+}
+
+#[test]
+fn oversized_counts_are_rejected() {
+    // Los contadores enormes se rechazan antes de reservar memoria para ellos
+    for format in ["ascii", "binary_little_endian"] {
+        let mut bytes = header(format, 4000000000000000000, None).into_bytes();
+        bytes.extend_from_slice(b"0 0 0 0 0 0 0\n");
+        assert_unexpected_end(&bytes, "vertex");
+    }
+
+    let mut bytes = binary(false);
+    let header_length = header("binary_little_endian", 5, Some(2)).len();
+    let mut oversized = header("binary_little_endian", 5, Some(4000000000000000000)).into_bytes();
+    oversized.extend_from_slice(&bytes[header_length..]);
+    assert_unexpected_end(&oversized, "face");
+
+    // Una lista con más índices de los que caben en el archivo
+    let face_start = bytes.len() - (1 + 4 * 4) - (1 + 4 * 3);
+    bytes[face_start] = u8::MAX;
+    assert_unexpected_end(&bytes, "face");
+    let mut bytes = header("binary_little_endian", 0, Some(1)).replace("uchar int", "uint int").into_bytes();
+    bytes.extend(u32::MAX.to_le_bytes());
+    assert_unexpected_end(&bytes, "face");
+}
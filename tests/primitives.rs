@@ -0,0 +1,92 @@
+use std::{collections::HashMap, f32::consts::PI};
+
+use cgmath::{InnerSpace, Vector3};
+use graphics_playground::mesh::Mesh;
+
+// Volumen con signo: positivo si los triángulos miran hacia fuera
+fn signed_volume(mesh: &Mesh) -> f32 {
+    mesh.triangles()
+        .map(|triangle| {
+            let [a, b, c] = triangle.map(|index| Vector3::from(mesh.vertices[index as usize].position));
+            a.dot(b.cross(c)) / 6.0
+        })
+        .sum()
+}
+
+// Cada arista entre posiciones la usan exactamente dos triángulos
+fn is_closed(mesh: &Mesh) -> bool {
+    let mut edges: HashMap<([u32; 3], [u32; 3]), usize> = HashMap::new();
+    for triangle in mesh.triangles() {
+        let corners = triangle.map(|index| mesh.vertices[index as usize].position.map(|value| (value + 0.0).to_bits()));
+        for k in 0..3 {
+            let (a, b) = (corners[k], corners[(k + 1) % 3]);
+            *edges.entry(if a < b { (a, b) } else { (b, a) }).or_default() += 1;
+        }
+    }
+    edges.values().all(|&count| count == 2)
+}
+
+fn check(name: &str, mesh: &Mesh, triangles: usize, volume: Option<f32>) {
+    let report = mesh.validate();
+    assert!(report.is_valid(), "{}: {:?}", name, report);
+    assert_eq!(mesh.triangle_count(), triangles, "{}", name);
+    match volume {
+        Some(volume) => {
+            assert!(is_closed(mesh), "{}", name);
+            let signed = signed_volume(mesh);
+            assert!(signed > 0.0, "{}: {}", name, signed);
+            assert!((signed - volume).abs() <= volume * 0.02, "{}: {} != {}", name, signed, volume);
+        }
+        None => assert!(!is_closed(mesh), "{}", name),
+    }
+    for vertex in mesh.vertices.iter() {
+        assert!((Vector3::from(vertex.normal).magnitude() - 1.0).abs() < 1e-5, "{}: {:?}", name, vertex);
+    }
+}
+
+#[test]
+fn generators_are_valid_closed_and_face_outwards() {
+    check("cube", &Mesh::cube(2.0), 12, Some(8.0));
+    check("plane", &Mesh::plane(2.0, 3.0, 4, 5), 2 * 4 * 5, None);
+    check("uv_sphere", &Mesh::uv_sphere(1.0, 64, 32), 2 * 64 * 31, Some(4.0 / 3.0 * PI));
+    check("icosphere", &Mesh::icosphere(1.0, 3), 20 * 4usize.pow(3), Some(4.0 / 3.0 * PI));
+    check("cylinder", &Mesh::cylinder(1.0, 2.0, 64), 4 * 64, Some(2.0 * PI));
+    check("cone", &Mesh::cone(1.0, 3.0, 64), 2 * 64, Some(PI));
+    check("torus", &Mesh::torus(2.0, 0.5, 64, 32), 2 * 64 * 32, Some(2.0 * PI * PI * 2.0 * 0.25));
+    check("capsule", &Mesh::capsule(1.0, 2.0, 64, 16), 4 * 64 * 16, Some(2.0 * PI + 4.0 / 3.0 * PI));
+}
+
+#[test]
+fn triangle_counts_follow_the_segments() {
+    for segments in [3, 4, 7, 16] {
+        assert_eq!(Mesh::plane(1.0, 1.0, segments, 2).triangle_count(), 4 * segments);
+        assert_eq!(Mesh::uv_sphere(1.0, segments, 5).triangle_count(), 2 * segments * 4);
+        assert_eq!(Mesh::cylinder(1.0, 1.0, segments).triangle_count(), 4 * segments);
+        assert_eq!(Mesh::cone(1.0, 1.0, segments).triangle_count(), 2 * segments);
+        assert_eq!(Mesh::torus(1.0, 0.25, segments, 5).triangle_count(), 2 * segments * 5);
+        assert_eq!(Mesh::capsule(1.0, 1.0, segments, 3).triangle_count(), 4 * segments * 3);
+    }
+    for subdivisions in 0..4 {
+        assert_eq!(Mesh::icosphere(1.0, subdivisions).triangle_count(), 20 << (2 * subdivisions));
+    }
+}
+
+#[test]
+fn zero_segments_are_clamped() {
+    // Rejilla de un cuadrado, tres meridianos, dos franjas de esfera y una por semiesfera
+    let counts = [
+        ("plane", Mesh::plane(1.0, 1.0, 0, 0), 2),
+        ("uv_sphere", Mesh::uv_sphere(1.0, 0, 0), 2 * 3),
+        ("cylinder", Mesh::cylinder(1.0, 1.0, 0), 4 * 3),
+        ("cone", Mesh::cone(1.0, 1.0, 0), 2 * 3),
+        ("torus", Mesh::torus(1.0, 0.25, 0, 0), 2 * 3 * 3),
+        ("capsule", Mesh::capsule(1.0, 1.0, 0, 0), 4 * 3),
+    ];
+    for (name, mesh, triangles) in counts {
+        assert_eq!(mesh.triangle_count(), triangles, "{}", name);
+        assert!(mesh.validate().is_valid(), "{}", name);
+        if name != "plane" {
+            assert!(is_closed(&mesh) && signed_volume(&mesh) > 0.0, "{}", name);
+        }
+    }
+}
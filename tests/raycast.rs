@@ -0,0 +1,158 @@
+use cgmath::{InnerSpace, Vector3};
+use graphics_playground::{
+    mesh::{
+        bvh::{intersect_triangle, Ray, RayHit},
+        Mesh, Vertex,
+    },
+    obj_parser::parse_obj,
+};
+
+// Generador congruencial para que los rayos sean siempre los mismos
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> f32
+    {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn point_in(&mut self, min: [f32; 3], max: [f32; 3]) -> [f32; 3]
+    {
+        [0, 1, 2].map(|i| min[i] + (max[i] - min[i]) * self.next())
+    }
+}
+
+// Prueba todos los triángulos en orden, como Bvh::intersect pero sin jerarquía
+fn brute_force(mesh: &Mesh, ray: &Ray, max_distance: f32) -> Option<(usize, f32)> {
+    let mut closest: Option<(usize, f32)> = None;
+    for (t, triangle) in mesh.triangles().enumerate() {
+        let corners = triangle.map(|index| mesh.vertices[index as usize].position);
+        let limit = closest.map_or(max_distance, |(_, distance)| distance);
+        if let Some((distance, _, _)) = intersect_triangle(ray, &corners, limit) {
+            if closest.is_none_or(|(_, closest)| distance < closest) {
+                closest = Some((t, distance));
+            }
+        }
+    }
+    closest
+}
+
+fn check_hit(mesh: &Mesh, ray: &Ray, hit: Option<RayHit>, expected: Option<(usize, f32)>) {
+    match (hit, expected) {
+        (None, None) => {}
+        (Some(hit), Some((triangle, distance))) => {
+            assert_eq!(hit.distance, distance, "{:?}", ray);
+            // Con empates (aristas compartidas) puede salir otro triángulo a la misma distancia
+            if hit.triangle != triangle {
+                let corners = mesh.triangle(hit.triangle).map(|index| mesh.vertices[index as usize].position);
+                assert_eq!(intersect_triangle(ray, &corners, f32::INFINITY).map(|(distance, _, _)| distance), Some(distance));
+            }
+
+            // Los pesos reconstruyen el punto de corte
+            let corners = mesh.triangle(hit.triangle).map(|index| Vector3::from(mesh.vertices[index as usize].position));
+            let point = corners[0] * hit.barycentrics[0] + corners[1] * hit.barycentrics[1] + corners[2] * hit.barycentrics[2];
+            let scale = corners.iter().map(|corner| corner.magnitude()).fold(1.0, f32::max);
+            assert!((point - Vector3::from(ray.at(hit.distance))).magnitude() <= scale * 1e-4, "{:?} {:?}", ray, hit);
+            assert!((hit.barycentrics.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+            assert!(hit.barycentrics.iter().all(|&weight| weight >= -1e-6));
+        }
+        (hit, expected) => panic!("{:?}: bvh {:?}, brute force {:?}", ray, hit, expected),
+    }
+}
+
+#[test]
+fn bvh_matches_brute_force_on_models() {
+    let mut random = Random(11);
+    // Pocos rayos en person.obj: la fuerza bruta prueba sus 49k triángulos en cada uno
+    for (path, ray_count) in [("person.obj", 60), ("flower.obj", 300)] {
+        let mesh = parse_obj(path).unwrap().mesh;
+        let bvh = mesh.bvh();
+        let bounds = mesh.bounds().unwrap();
+        assert_eq!(bvh.bounds(), Some(bounds));
+
+        // Rayos desde fuera hacia un punto de la caja, desde dentro en cualquier dirección, y
+        // algunos que no llegan a la malla
+        let diagonal = bounds.diagonal();
+        let center = Vector3::from(bounds.center());
+        let mut hits = 0;
+        for i in 0..ray_count {
+            let target = Vector3::from(random.point_in(bounds.min, bounds.max));
+            let ray = match i % 3 {
+                0 => {
+                    let direction = Vector3::new(random.next() - 0.5, random.next() - 0.5, random.next() - 0.5).normalize();
+                    Ray::new((center + direction * diagonal).into(), (target - (center + direction * diagonal)).into())
+                }
+                1 => Ray::new(target.into(), [random.next() - 0.5, random.next() - 0.5, random.next() - 0.5]),
+                _ => Ray::new((center + Vector3::new(0.0, diagonal, 0.0)).into(), [random.next() - 0.5, 0.1, random.next() - 0.5]),
+            };
+
+            let hit = bvh.intersect(&ray, f32::INFINITY);
+            hits += hit.is_some() as usize;
+            check_hit(&mesh, &ray, hit, brute_force(&mesh, &ray, f32::INFINITY));
+
+            // Con una distancia máxima solo cuenta lo que queda antes
+            let limit = diagonal * random.next() * 0.5;
+            check_hit(&mesh, &ray, bvh.intersect(&ray, limit), brute_force(&mesh, &ray, limit));
+        }
+        assert!(hits > ray_count / 6, "{}: {} hits", path, hits);
+    }
+}
+
+#[test]
+fn axis_aligned_rays_through_edges_and_corners() {
+    // Rayos paralelos a los ejes que pasan justo por aristas y esquinas compartidas
+    let mesh = Mesh::plane(4.0, 4.0, 4, 4);
+    let bvh = mesh.bvh();
+    for x in -4..=4 {
+        for z in -4..=4 {
+            let origin = [x as f32 * 0.5, 1.0, z as f32 * 0.5];
+            let ray = Ray::new(origin, [0.0, -1.0, 0.0]);
+            let hit = bvh.intersect(&ray, f32::INFINITY);
+            check_hit(&mesh, &ray, hit, brute_force(&mesh, &ray, f32::INFINITY));
+            assert!(hit.is_some_and(|hit| (hit.distance - 1.0).abs() < 1e-6));
+        }
+    }
+
+    // Un rayo en el mismo plano que la malla no la corta
+    assert!(bvh.intersect(&Ray::new([-3.0, 0.0, 0.0], [1.0, 0.0, 0.0]), f32::INFINITY).is_none());
+
+    // Un cubo visto a lo largo de cada eje, por los dos lados y desde dentro
+    let cube = Mesh::cube(2.0);
+    let bvh = cube.bvh();
+    for axis in 0..3 {
+        for sign in [-1.0, 1.0] {
+            let mut direction = [0.0; 3];
+            direction[axis] = sign;
+            let origin = direction.map(|value| -value * 5.0);
+            for ray in [Ray::new(origin, direction), Ray::new([0.0; 3], direction), Ray::new(origin.map(|v| v + 1.0), direction)] {
+                check_hit(&cube, &ray, bvh.intersect(&ray, f32::INFINITY), brute_force(&cube, &ray, f32::INFINITY));
+            }
+            assert_eq!(bvh.intersect(&Ray::new(origin, direction), f32::INFINITY).map(|hit| hit.distance), Some(4.0));
+            assert_eq!(bvh.intersect(&Ray::new([0.0; 3], direction), f32::INFINITY).map(|hit| hit.distance), Some(1.0));
+        }
+    }
+}
+
+#[test]
+fn degenerate_meshes() {
+    let ray = Ray::new([0.0, 0.0, -1.0], [0.0, 0.0, 1.0]);
+    assert!(Mesh::default().bvh().intersect(&ray, f32::INFINITY).is_none());
+    assert!(Mesh::default().bvh().bounds().is_none());
+
+    // Triángulos sin área, con coordenadas no finitas y todos los centros en el mismo punto
+    let vertex = |position: [f32; 3]| Vertex::new(position, [0.0, 0.0, 1.0], [0.0, 0.0]);
+    let mut vertices = vec![vertex([0.0; 3]), vertex([1.0, 0.0, 0.0]), vertex([2.0, 0.0, 0.0]), vertex([f32::NAN, 0.0, 0.0])];
+    let mut indices = vec![0, 1, 2, 0, 1, 3];
+    for _ in 0..20 {
+        let first = vertices.len() as u32;
+        vertices.extend([vertex([-1.0, -1.0, 0.0]), vertex([1.0, -1.0, 0.0]), vertex([0.0, 2.0, 0.0])]);
+        indices.extend([first, first + 1, first + 2]);
+    }
+    let mesh = Mesh::new(vertices, indices);
+    let hit = mesh.bvh().intersect(&ray, f32::INFINITY).unwrap();
+    assert_eq!(hit.distance, 1.0);
+    // Los 20 triángulos iguales empatan
+    assert!((2..22).contains(&hit.triangle));
+    check_hit(&mesh, &ray, Some(hit), brute_force(&mesh, &ray, f32::INFINITY));
+}
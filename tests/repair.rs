@@ -0,0 +1,140 @@
+use cgmath::{InnerSpace, Vector3};
+use graphics_playground::{
+    mesh::{
+        validate::{RepairOptions, RepairSummary},
+        Mesh, Vertex,
+    },
+    model::{Model, SubMesh},
+};
+
+// Plano de 2x2 cuadrados en y = 0, con los vértices por filas y las caras hacia arriba
+fn plane() -> Mesh {
+    let mut vertices = Vec::new();
+    for row in 0..=2 {
+        for column in 0..=2 {
+            let (u, v) = (column as f32 / 2.0, row as f32 / 2.0);
+            vertices.push(Vertex::new([u * 2.0 - 1.0, 0.0, v * 2.0 - 1.0], [0.0, 1.0, 0.0], [u, v]));
+        }
+    }
+    let mut indices = Vec::new();
+    for row in 0..2 {
+        for column in 0..2 {
+            let a = row * 3 + column;
+            indices.extend([a, a + 3, a + 4, a, a + 4, a + 1]);
+        }
+    }
+    Mesh::new(vertices, indices)
+}
+
+// Producto vectorial de cada triángulo proyectado sobre la normal del plano (+y)
+fn facing_up(mesh: &Mesh) -> Vec<bool> {
+    mesh.triangles()
+        .map(|triangle| {
+            let [a, b, c] = triangle.map(|index| Vector3::from(mesh.vertices[index as usize].position));
+            (b - a).cross(c - a).dot(Vector3::unit_y()) > 0.0
+        })
+        .collect()
+}
+
+// Plano de 2x2 cuadrados (8 triángulos, 9 vértices) con todos los problemas que se pueden
+// reparar, repartido en tres submallas:
+// - 0..6: los cuatro primeros triángulos, un duplicado del primero y uno con un índice fuera de rango
+// - 6..13: uno con un vértice NaN, uno con dos esquinas iguales, uno con las tres alineadas y los
+//   cuatro últimos del plano, el primero de ellos girado
+// - 13..14: otro degenerado, que deja la submalla vacía
+fn broken_model() -> Model {
+    let plane = plane();
+    assert!(facing_up(&plane).iter().all(|&up| up));
+    let original: Vec<[u32; 3]> = plane.triangles().collect();
+
+    let mut vertices = plane.vertices.clone();
+    vertices.push(Vertex::new([f32::NAN, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0; 2]));
+    vertices.push(Vertex::new([5.0, 0.0, 5.0], [0.0, 1.0, 0.0], [0.0; 2]));
+
+    let [a, b, c] = original[0];
+    let [d, e, f] = original[4];
+    let triangles: Vec<[u32; 3]> = original[..4]
+        .iter()
+        .copied()
+        .chain([[b, c, a], [0, 1, 99], [9, 1, 2], [0, 0, 1], [0, 1, 2], [d, f, e]])
+        .chain(original[5..].iter().copied())
+        .chain([[3, 4, 4]])
+        .collect();
+    let mut indices: Vec<u32> = triangles.into_iter().flatten().collect();
+    // Dos índices sueltos al final
+    indices.extend([1, 2]);
+
+    let submesh = |name: &str, first_triangle: usize, triangle_count: usize| SubMesh {
+        name: name.to_string(),
+        object: None,
+        material: None,
+        first_triangle,
+        triangle_count,
+    };
+    Model {
+        mesh: Mesh::new(vertices, indices),
+        materials: Vec::new(),
+        submeshes: vec![submesh("first", 0, 6), submesh("second", 6, 7), submesh("third", 13, 1)],
+    }
+}
+
+#[test]
+fn validate_reports_every_problem() {
+    let report = broken_model().mesh.validate();
+    assert!(!report.is_valid());
+    assert_eq!(report.out_of_range_indices, [17]);
+    assert_eq!(report.trailing_indices, 2);
+    assert_eq!(report.non_finite_vertices, [9]);
+    assert_eq!(report.unreferenced_vertices, [10]);
+    assert_eq!(report.degenerate_triangles, [5, 6, 7, 8, 13]);
+    assert_eq!(report.duplicate_triangles, [4]);
+    assert!(report.non_manifold_edges.is_empty());
+    // El triángulo girado recorre en el mismo sentido las aristas que comparte con sus vecinos
+    assert!(!report.inconsistent_edges.is_empty());
+}
+
+#[test]
+fn repair_fixes_the_mesh_and_reports_what_it_did() {
+    let mut mesh = broken_model().mesh;
+    let summary = mesh.repair(&RepairOptions::default());
+    assert_eq!(
+        summary,
+        RepairSummary {
+            removed_triangles: 6,
+            flipped_triangles: 1,
+            removed_vertices: 2,
+        }
+    );
+
+    assert!(mesh.validate().is_valid(), "{:?}", mesh.validate());
+    assert_eq!(mesh.triangle_count(), 8);
+    assert_eq!(mesh.vertices, plane().vertices);
+    assert!(facing_up(&mesh).iter().all(|&up| up));
+
+    // Con todo desactivado solo se quitan los índices sueltos
+    let mut untouched = broken_model().mesh;
+    let nothing = RepairOptions {
+        remove_degenerate: false,
+        remove_duplicates: false,
+        unify_winding: false,
+        remove_unreferenced_vertices: false,
+    };
+    assert_eq!(untouched.repair(&nothing), RepairSummary::default());
+    assert_eq!(untouched.indices.len(), 14 * 3);
+}
+
+#[test]
+fn model_repair_adjusts_submesh_ranges() {
+    let mut model = broken_model();
+    let summary = model.repair(&RepairOptions::default());
+    assert_eq!(summary.removed_triangles, 6);
+
+    let ranges: Vec<(usize, usize)> = model.submeshes.iter().map(|submesh| (submesh.first_triangle, submesh.triangle_count)).collect();
+    assert_eq!(ranges, [(0, 4), (4, 4), (8, 0)]);
+    // Cada submalla conserva sus triángulos
+    let plane = plane();
+    assert_eq!(model.mesh.indices[..12], plane.indices[..12]);
+    let second: Vec<[u32; 3]> = (4..8).map(|t| model.mesh.triangle(t)).collect();
+    let expected: Vec<[u32; 3]> = (4..8).map(|t| plane.triangle(t)).collect();
+    assert_eq!(second, expected);
+}
@@ -0,0 +1,38 @@
+use std::{fs, path::Path};
+
+use graphics_playground::obj_parser::{mtl::parse_mtl_from_reader, parse_obj, FileResolver, NoResolver, ResourceResolver};
+
+const LIBRARY: &str = "newmtl textured\nmap_Kd textures/wood.png\nmap_Bump /absolute/normal.png\n";
+
+#[test]
+fn file_resolver_joins_textures_to_the_base_dir() {
+    let resolver = FileResolver::new("models/house");
+    let expected = Path::new("models/house").join("textures/wood.png");
+    assert_eq!(resolver.resolve_texture("textures/wood.png"), expected.to_string_lossy());
+    assert_eq!(resolver.resolve_texture("/absolute/normal.png"), "/absolute/normal.png");
+
+    // Junto a un archivo sin directorio las rutas quedan igual
+    assert_eq!(FileResolver::for_file("cube.obj").resolve_texture("wood.png"), "wood.png");
+
+    let materials = parse_mtl_from_reader(LIBRARY.as_bytes(), &resolver).unwrap();
+    assert_eq!(materials[0].diffuse_map.as_deref(), Some(expected.to_str().unwrap()));
+    assert_eq!(materials[0].bump_map.as_deref(), Some("/absolute/normal.png"));
+
+    // Los demás resolvers no tocan la ruta
+    let materials = parse_mtl_from_reader(LIBRARY.as_bytes(), &NoResolver).unwrap();
+    assert_eq!(materials[0].diffuse_map.as_deref(), Some("textures/wood.png"));
+}
+
+#[test]
+fn obj_textures_are_relative_to_the_obj_file() {
+    let directory = std::env::temp_dir().join("graphics_playground_resolver");
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("scene.mtl"), LIBRARY).unwrap();
+    let obj = directory.join("scene.obj");
+    fs::write(&obj, "mtllib scene.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl textured\nf 1 2 3\n").unwrap();
+
+    let model = parse_obj(obj.to_str().unwrap()).unwrap();
+    let texture = model.materials[0].diffuse_map.as_deref().unwrap();
+    assert_eq!(Path::new(texture), directory.join("textures/wood.png"));
+    assert!(Path::new(texture).is_absolute());
+}
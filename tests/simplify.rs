@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use cgmath::{InnerSpace, Vector3};
+use graphics_playground::{
+    mesh::{
+        simplify::{LodOptions, SimplifyOptions},
+        Mesh, Vertex,
+    },
+    obj_parser::parse_obj,
+};
+
+fn sphere() -> Mesh {
+    parse_obj("sphere.obj").unwrap().mesh
+}
+
+// Índices dentro de rango, ningún triángulo sin área y cada arista entre posiciones recorrida
+// una sola vez en cada sentido: variedad y con todos los triángulos orientados igual
+fn assert_well_formed(mesh: &Mesh) {
+    let mut edges: HashMap<([u32; 3], [u32; 3]), usize> = HashMap::new();
+    for triangle in mesh.triangles() {
+        assert!(triangle.iter().all(|&index| (index as usize) < mesh.vertices.len()), "{:?}", triangle);
+        let [a, b, c] = triangle.map(|index| Vector3::from(mesh.vertices[index as usize].position));
+        assert!((b - a).cross(c - a).magnitude() > 0.0, "{:?}", triangle);
+        let keys = triangle.map(|index| mesh.vertices[index as usize].position.map(|value| (value + 0.0).to_bits()));
+        for k in 0..3 {
+            *edges.entry((keys[k], keys[(k + 1) % 3])).or_default() += 1;
+        }
+    }
+    assert!(edges.values().all(|&count| count == 1));
+}
+
+fn area(mesh: &Mesh) -> f32 {
+    mesh.triangles()
+        .map(|triangle| {
+            let [a, b, c] = triangle.map(|index| Vector3::from(mesh.vertices[index as usize].position));
+            (b - a).cross(c - a).magnitude() * 0.5
+        })
+        .sum()
+}
+
+// Plano de 2x2 con una costura de UVs en x = 0: los triángulos de la derecha usan copias de
+// los vértices de la costura con otra u
+fn plane_with_seam() -> Mesh {
+    let mut vertices = Vec::new();
+    for row in 0..=8 {
+        for column in 0..=8 {
+            let (u, v) = (column as f32 / 8.0, row as f32 / 8.0);
+            vertices.push(Vertex::new([u * 2.0 - 1.0, 0.0, v * 2.0 - 1.0], [0.0, 1.0, 0.0], [u, v]));
+        }
+    }
+    let mut indices = Vec::new();
+    for row in 0..8 {
+        for column in 0..8 {
+            let a = row * 9 + column;
+            indices.extend([a, a + 9, a + 10, a, a + 10, a + 1]);
+        }
+    }
+    let mut mesh = Mesh::new(vertices, indices);
+    let mut copies: Vec<Option<u32>> = vec![None; mesh.vertices.len()];
+    for t in 0..mesh.triangle_count() {
+        let triangle = mesh.triangle(t);
+        if triangle.iter().all(|&index| mesh.vertices[index as usize].position[0] >= 0.0) {
+            for (corner, &index) in triangle.iter().enumerate() {
+                let index = index as usize;
+                if mesh.vertices[index].position[0] == 0.0 {
+                    let copy = *copies[index].get_or_insert_with(|| {
+                        let vertex = mesh.vertices[index];
+                        mesh.vertices.push(Vertex::new(vertex.position, vertex.normal, [vertex.uv[0] + 1.0, vertex.uv[1]]));
+                        (mesh.vertices.len() - 1) as u32
+                    });
+                    mesh.indices[t * 3 + corner] = copy;
+                }
+            }
+        }
+    }
+    mesh
+}
+
+#[test]
+fn target_triangle_count_is_reached() {
+    let mesh = sphere();
+    for target in [mesh.triangle_count() / 4, 100, 10] {
+        let lod = mesh.simplify(&SimplifyOptions { target_triangle_count: target, max_error: f32::INFINITY });
+        assert!(lod.mesh.triangle_count() <= target && lod.mesh.triangle_count() + 2 >= target, "{}", target);
+        assert!(lod.error > 0.0);
+        assert_well_formed(&lod.mesh);
+    }
+}
+
+#[test]
+fn boundaries_and_seams_stay_in_place() {
+    let mesh = plane_with_seam();
+    assert_well_formed(&mesh);
+    // El plano es llano: con cualquier error máximo se simplifica todo lo posible
+    let lod = mesh.simplify(&SimplifyOptions { target_triangle_count: 0, max_error: 0.01 });
+    let simplified = &lod.mesh;
+    assert!(simplified.triangle_count() < 16, "{}", simplified.triangle_count());
+    assert_eq!(lod.error, 0.0);
+    assert_well_formed(simplified);
+
+    // Los vértices son copias de los originales, y están las esquinas y los extremos de la costura
+    for vertex in simplified.vertices.iter() {
+        assert!(mesh.vertices.contains(vertex), "{:?}", vertex);
+    }
+    for corner in [[-1.0, 0.0, -1.0], [1.0, 0.0, -1.0], [1.0, 0.0, 1.0], [-1.0, 0.0, 1.0], [0.0, 0.0, -1.0], [0.0, 0.0, 1.0]] {
+        assert!(simplified.vertices.iter().any(|vertex| vertex.position == corner), "{:?}", corner);
+    }
+
+    // El contorno no cambia, así que el área tampoco, y la costura sigue recta: ningún triángulo
+    // la cruza y sus copias siguen en x = 0
+    assert!((area(simplified) - 4.0).abs() < 1e-5);
+    for triangle in simplified.triangles() {
+        let xs = triangle.map(|index| simplified.vertices[index as usize].position[0]);
+        assert!(xs.iter().all(|&x| x <= 0.0) || xs.iter().all(|&x| x >= 0.0), "{:?}", xs);
+    }
+    for vertex in simplified.vertices.iter().filter(|vertex| vertex.uv[0] > 1.0) {
+        assert_eq!(vertex.position[0], 0.0);
+    }
+}
+
+#[test]
+fn max_error_stops_the_collapses() {
+    let mesh = sphere();
+    let strict = mesh.simplify(&SimplifyOptions { target_triangle_count: 0, max_error: 0.001 });
+    assert_eq!(strict.mesh.triangle_count(), mesh.triangle_count());
+    assert_eq!(strict.error, 0.0);
+
+    let loose = mesh.simplify(&SimplifyOptions { target_triangle_count: 0, max_error: 0.03 });
+    assert!(loose.mesh.triangle_count() < mesh.triangle_count());
+    assert!(loose.error > 0.0 && loose.error <= 0.03);
+
+    let looser = mesh.simplify(&SimplifyOptions { target_triangle_count: 0, max_error: 0.05 });
+    assert!(looser.mesh.triangle_count() < loose.mesh.triangle_count());
+    assert!(looser.error <= 0.05);
+    assert_well_formed(&looser.mesh);
+}
+
+#[test]
+fn lod_chain_is_monotonic() {
+    let mesh = sphere();
+    let lods = mesh.lod_chain(&LodOptions::default());
+    assert!(lods.len() > 2);
+    assert_eq!(lods[0].mesh, mesh);
+    assert_eq!(lods[0].error, 0.0);
+
+    for pair in lods.windows(2) {
+        assert!(pair[1].mesh.triangle_count() < pair[0].mesh.triangle_count());
+        assert!(pair[1].error >= pair[0].error);
+    }
+    for lod in lods.iter() {
+        assert!(lod.error <= LodOptions::default().max_error);
+        assert_well_formed(&lod.mesh);
+    }
+
+    // Sin margen de error no hay más niveles que el original
+    let lods = mesh.lod_chain(&LodOptions { max_error: 0.0, ..LodOptions::default() });
+    assert_eq!(lods.len(), 1);
+}
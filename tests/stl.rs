@@ -0,0 +1,123 @@
+use graphics_playground::{
+    mesh::{normals::NormalOptions, Mesh},
+    model::Model,
+    obj_parser::parse_obj,
+    stl_parser::{
+        parse_stl_from_slice,
+        writer::{write_stl_to, StlFormat},
+        StlError, StlOptions,
+    },
+};
+
+fn write(model: &Model, format: StlFormat) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write_stl_to(model, &mut bytes, format).unwrap();
+    bytes
+}
+
+fn triangle_positions(model: &Model) -> Vec<[[f32; 3]; 3]> {
+    model.mesh.triangles().map(|triangle| triangle.map(|index| model.mesh.vertices[index as usize].position)).collect()
+}
+
+fn assert_same_triangles(model: &Model, parsed: &Model) {
+    assert_eq!(triangle_positions(model), triangle_positions(parsed));
+    // Las normales de cada cara salen del orden de sus vértices
+    for triangle in parsed.mesh.triangles() {
+        let normals = triangle.map(|index| parsed.mesh.vertices[index as usize].normal);
+        assert!(normals.iter().all(|&normal| normal == normals[0]));
+    }
+}
+
+#[test]
+fn binary_and_ascii_round_trips() {
+    let model = parse_obj("cube.obj").unwrap();
+    let options = StlOptions::default();
+
+    let binary = write(&model, StlFormat::Binary);
+    assert_eq!(binary.len(), 84 + 12 * 50);
+    let parsed = parse_stl_from_slice(&binary, &options).unwrap();
+    assert_same_triangles(&model, &parsed);
+    assert_eq!(parsed.submeshes.len(), 1);
+
+    let ascii = write(&model, StlFormat::Ascii);
+    assert!(ascii.starts_with(b"solid Cube\n"));
+    let parsed = parse_stl_from_slice(&ascii, &options).unwrap();
+    assert_same_triangles(&model, &parsed);
+    assert_eq!(parsed.submeshes[0].name, "Cube");
+}
+
+#[test]
+fn binary_header_starting_with_solid() {
+    let model = parse_obj("cube.obj").unwrap();
+    let mut binary = write(&model, StlFormat::Binary);
+    binary[..11].copy_from_slice(b"solid cube ");
+    let parsed = parse_stl_from_slice(&binary, &StlOptions::default()).unwrap();
+    assert_same_triangles(&model, &parsed);
+
+    // Con bytes de más al final el tamaño ya no cuadra, pero el número de triángulos cabe
+    binary.extend_from_slice(b"\n\n");
+    let parsed = parse_stl_from_slice(&binary, &StlOptions::default()).unwrap();
+    assert_same_triangles(&model, &parsed);
+
+    // Truncado: no caben los triángulos, pero los datos no son texto
+    binary.truncate(84 + 11 * 50 + 20);
+    assert!(matches!(
+        parse_stl_from_slice(&binary, &StlOptions::default()),
+        Err(StlError::Truncated { expected: 12, found: 11 })
+    ));
+}
+
+#[test]
+fn duplicate_vertices_are_welded() {
+    // Las coordenadas de cube.obj no son exactas y las dos mitades de cada lado tendrían normales
+    // algo distintas
+    let model = Model::from_mesh(Mesh::cube(2.0));
+    let binary = write(&model, StlFormat::Binary);
+
+    // 36 esquinas: con normales por cara quedan las 4 de cada lado, suavizando solo las 8 esquinas
+    let flat = parse_stl_from_slice(&binary, &StlOptions::default()).unwrap();
+    assert_eq!(flat.mesh.indices.len(), 36);
+    assert_eq!(flat.mesh.vertices.len(), 24);
+
+    let options = StlOptions { smooth_normals: Some(NormalOptions::default()) };
+    let smooth = parse_stl_from_slice(&binary, &options).unwrap();
+    assert_eq!(smooth.mesh.vertices.len(), 8);
+    assert_eq!(triangle_positions(&model), triangle_positions(&smooth));
+}
+
+#[test]
+fn truncated_and_empty_input() {
+    let model = parse_obj("cube.obj").unwrap();
+    let binary = write(&model, StlFormat::Binary);
+    assert!(matches!(
+        parse_stl_from_slice(&binary[..binary.len() - 10], &StlOptions::default()),
+        Err(StlError::Truncated { expected: 12, found: 11 })
+    ));
+
+    assert!(matches!(parse_stl_from_slice(b"", &StlOptions::default()), Err(StlError::MissingHeader { found: 0 })));
+    assert!(matches!(parse_stl_from_slice(&binary[..40], &StlOptions::default()), Err(StlError::MissingHeader { found: 40 })));
+    assert_eq!(StlError::MissingHeader { found: 0 }.to_string(), "empty STL file");
+
+    // ASCII a medias
+    let ascii = write(&model, StlFormat::Ascii);
+    let text = String::from_utf8(ascii).unwrap();
+    let cut = text.find("endloop").unwrap();
+    assert!(matches!(
+        parse_stl_from_slice(&text.as_bytes()[..cut], &StlOptions::default()),
+        Err(StlError::Unexpected { expected: "endfacet", .. })
+    ));
+}
+
+#[test]
+fn ascii_with_a_non_ascii_name() {
+    // Los bytes UTF-8 del nombre no son bytes de control: sigue siendo texto
+    let mut model = parse_obj("cube.obj").unwrap();
+    model.submeshes[0].name = "pièce".to_string();
+    let ascii = write(&model, StlFormat::Ascii);
+    assert!(ascii.starts_with("solid pièce\n".as_bytes()));
+    assert!(ascii.ends_with("endsolid pièce\n".as_bytes()));
+
+    let parsed = parse_stl_from_slice(&ascii, &StlOptions::default()).unwrap();
+    assert_same_triangles(&model, &parsed);
+    assert_eq!(parsed.submeshes[0].name, "pièce");
+}
@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+
+use cgmath::{InnerSpace, Vector3};
+use graphics_playground::{
+    mesh::{subdivide::SubdivisionOptions, Mesh, Vertex},
+    obj_parser::parse_obj,
+};
+
+fn levels(levels: usize) -> SubdivisionOptions {
+    SubdivisionOptions {
+        levels,
+        ..SubdivisionOptions::default()
+    }
+}
+
+fn position_count(mesh: &Mesh) -> usize {
+    mesh.vertices.iter().map(|vertex| vertex.position.map(f32::to_bits)).collect::<HashSet<_>>().len()
+}
+
+// Cerrada y con un solo sentido: cada arista entre posiciones la recorren dos triángulos, uno en
+// cada sentido
+fn assert_closed_and_consistent(mesh: &Mesh) {
+    let report = mesh.validate();
+    assert!(report.is_valid(), "{:?}", report);
+    let mut directed: HashMap<([u32; 3], [u32; 3]), usize> = HashMap::new();
+    for triangle in mesh.triangles() {
+        let corners = triangle.map(|index| mesh.vertices[index as usize].position.map(f32::to_bits));
+        for k in 0..3 {
+            *directed.entry((corners[k], corners[(k + 1) % 3])).or_default() += 1;
+        }
+    }
+    for (&(a, b), &count) in directed.iter() {
+        assert_eq!(count, 1);
+        assert_eq!(directed.get(&(b, a)), Some(&1));
+    }
+}
+
+// Radios menor y mayor de los vértices
+fn radius_range(mesh: &Mesh) -> (f32, f32) {
+    mesh.vertices
+        .iter()
+        .map(|vertex| Vector3::from(vertex.position).magnitude())
+        .fold((f32::MAX, 0.0), |(min, max), radius| (min.min(radius), max.max(radius)))
+}
+
+#[test]
+fn loop_on_an_icosahedron() {
+    let icosahedron = Mesh::icosphere(1.0, 0);
+    assert_eq!((position_count(&icosahedron), icosahedron.triangle_count()), (12, 20));
+
+    // V' = V + E y F' = 4F; con V - E + F = 2, E = 3F / 2
+    let mut previous = radius_range(&icosahedron);
+    let mut previous_change = f32::MAX;
+    for (level, (vertices, faces)) in [(42, 80), (162, 320), (642, 1280)].into_iter().enumerate() {
+        let subdivided = icosahedron.subdivide_loop(&levels(level + 1));
+        assert_eq!(position_count(&subdivided), vertices);
+        assert_eq!(subdivided.triangle_count(), faces);
+        assert_closed_and_consistent(&subdivided);
+
+        // La superficie límite es casi una esfera algo menor que el icosaedro, y cada nivel se
+        // acerca a ella: el radio cambia cada vez menos
+        let (min, max) = radius_range(&subdivided);
+        assert!((max - min) / max < 0.035, "level {}: {} {}", level + 1, min, max);
+        let change = (min - previous.0).abs().max((max - previous.1).abs());
+        assert!(change < previous_change * 0.5, "level {}: {} {}", level + 1, change, previous_change);
+        previous = (min, max);
+        previous_change = change;
+    }
+}
+
+#[test]
+fn catmull_clark_quad_counts() {
+    // flower.obj solo tiene caras de cuatro lados
+    let flower = parse_obj("flower.obj").unwrap().mesh;
+    let quads = flower.triangle_count() / 2;
+    let subdivided = flower.subdivide_catmull_clark(&levels(1));
+    assert_eq!(subdivided.triangle_count(), quads * 4 * 2);
+
+    // Indicando las caras se obtiene la misma superficie, aunque los vértices salgan en otro orden
+    let options = SubdivisionOptions {
+        polygons: Some(vec![4; quads]),
+        ..levels(1)
+    };
+    let explicit = flower.subdivide_catmull_clark(&options);
+    assert_eq!(explicit.triangle_count(), subdivided.triangle_count());
+    let positions = |mesh: &Mesh| mesh.vertices.iter().map(|vertex| vertex.position.map(f32::to_bits)).collect::<HashSet<_>>();
+    assert_eq!(positions(&explicit), positions(&subdivided));
+
+    // Un cubo de seis cuadrados: V' = V + E + F
+    let cube = Mesh::cube(2.0);
+    for (level, (vertices, quads)) in [(26, 24), (98, 96)].into_iter().enumerate() {
+        let subdivided = cube.subdivide_catmull_clark(&levels(level + 1));
+        assert_eq!(position_count(&subdivided), vertices);
+        assert_eq!(subdivided.triangle_count(), quads * 2);
+        assert_closed_and_consistent(&subdivided);
+    }
+}
+
+#[test]
+fn catmull_clark_with_explicit_polygons() {
+    // En una malla de triángulos el emparejamiento uniría triángulos vecinos; con las caras
+    // indicadas cada triángulo da tres cuadriláteros
+    let icosahedron = Mesh::icosphere(1.0, 0);
+    let options = SubdivisionOptions {
+        polygons: Some(vec![3; 20]),
+        ..levels(1)
+    };
+    let subdivided = icosahedron.subdivide_catmull_clark(&options);
+    assert_eq!(subdivided.triangle_count(), 20 * 3 * 2);
+    assert_eq!(position_count(&subdivided), 12 + 30 + 20);
+    assert_closed_and_consistent(&subdivided);
+
+    // Un pentágono en abanico es una sola cara: cinco cuadriláteros alrededor de su centro
+    let corner = |angle: f32| Vertex::new([angle.cos(), 0.0, -angle.sin()], [0.0, 1.0, 0.0], [0.0; 2]);
+    let vertices = (0..5).map(|k| corner(k as f32 * std::f32::consts::TAU / 5.0)).collect();
+    let pentagon = Mesh::new(vertices, vec![0, 1, 2, 0, 2, 3, 0, 3, 4]);
+    let options = SubdivisionOptions {
+        polygons: Some(vec![5]),
+        ..levels(1)
+    };
+    let subdivided = pentagon.subdivide_catmull_clark(&options);
+    assert_eq!(subdivided.triangle_count(), 5 * 2);
+    assert_eq!(position_count(&subdivided), 5 + 5 + 1);
+}
+
+#[test]
+fn uvs_are_interpolated_on_each_side_of_a_seam() {
+    // Dos cuadrados que comparten la arista x = 0 con UVs distintas a cada lado
+    let vertex = |x: f32, z: f32, u: f32| Vertex::new([x, 0.0, z], [0.0, 1.0, 0.0], [u, z]);
+    let vertices = vec![
+        vertex(-1.0, 0.0, 0.0),
+        vertex(0.0, 0.0, 1.0),
+        vertex(0.0, 1.0, 1.0),
+        vertex(-1.0, 1.0, 0.0),
+        vertex(0.0, 0.0, 2.0),
+        vertex(1.0, 0.0, 3.0),
+        vertex(1.0, 1.0, 3.0),
+        vertex(0.0, 1.0, 2.0),
+    ];
+    let mesh = Mesh::new(vertices, vec![0, 2, 1, 0, 3, 2, 4, 6, 5, 4, 7, 6]);
+    for subdivided in [mesh.subdivide_catmull_clark(&levels(2)), mesh.subdivide_loop(&levels(2))] {
+        // Las UVs de cada lado se interpolan solo entre las suyas: nada cae entre 1 y 2
+        for vertex in subdivided.vertices.iter() {
+            let [x, _, _] = vertex.position;
+            let [u, v] = vertex.uv;
+            assert!(x <= 0.0 && (0.0..=1.0).contains(&u) || x >= 0.0 && (2.0..=3.0).contains(&u), "{:?}", vertex);
+            assert!((0.0..=1.0).contains(&v));
+        }
+        let seam: Vec<&Vertex> = subdivided.vertices.iter().filter(|vertex| vertex.position[0] == 0.0).collect();
+        assert!(seam.iter().any(|vertex| vertex.uv[0] == 1.0) && seam.iter().any(|vertex| vertex.uv[0] == 2.0));
+    }
+}
+
+#[test]
+fn creases_stay_sharp() {
+    let cube = Mesh::cube(2.0);
+    let on_surface = |mesh: &Mesh| mesh.vertices.iter().all(|vertex| (vertex.position.map(f32::abs).into_iter().fold(0.0, f32::max) - 1.0).abs() < 1e-5);
+
+    // Sin pliegues el cubo se redondea y encoge
+    let smooth = cube.subdivide_catmull_clark(&levels(2));
+    assert!(!on_surface(&smooth));
+
+    // Con todas las aristas afiladas sigue siendo el mismo cubo, con las esquinas en su sitio
+    let options = SubdivisionOptions {
+        crease_angle: Some(30.0),
+        ..levels(2)
+    };
+    for sharp in [cube.subdivide_catmull_clark(&options), cube.subdivide_loop(&options)] {
+        assert!(on_surface(&sharp));
+        for corner in cube.vertices.iter() {
+            assert!(sharp.vertices.iter().any(|vertex| vertex.position == corner.position));
+        }
+        // Las normales siguen siendo las de las caras
+        for vertex in sharp.vertices.iter() {
+            let normal = Vector3::from(vertex.normal);
+            assert!([normal.x, normal.y, normal.z].iter().any(|value| value.abs() > 0.9999), "{:?}", vertex);
+        }
+    }
+}
+
+#[test]
+fn degenerate_faces_are_subdivided_as_boundaries() {
+    // Un triángulo con dos esquinas en el mismo punto, como una astilla de costura colapsada
+    let vertex = |position: [f32; 3], uv: [f32; 2]| Vertex::new(position, [0.0, 0.0, 1.0], uv);
+    let sliver = Mesh::new(
+        vec![vertex([0.0, 0.0, 0.0], [0.0, 0.0]), vertex([1.0, 0.0, 0.0], [1.0, 0.0]), vertex([0.0, 0.0, 0.0], [0.0, 1.0])],
+        vec![0, 1, 2],
+    );
+    for subdivided in [sliver.subdivide_loop(&levels(2)), sliver.subdivide_catmull_clark(&levels(2))] {
+        assert!(subdivided.triangle_count() > 0);
+        // Sin área no hay nada que redondear: los puntos siguen sobre la recta de la astilla
+        for vertex in subdivided.vertices.iter() {
+            assert!(vertex.position[0].is_finite() && vertex.position[1] == 0.0 && vertex.position[2] == 0.0, "{:?}", vertex);
+        }
+    }
+    assert_eq!(sliver.subdivide_loop(&levels(2)).triangle_count(), 16);
+
+    // La astilla pegada a una malla cerrada no la rompe
+    let mut icosahedron = Mesh::icosphere(1.0, 0);
+    let [a, b, _] = icosahedron.triangle(0);
+    icosahedron.indices.extend([a, b, a]);
+    assert_eq!(icosahedron.subdivide_loop(&levels(1)).triangle_count(), 21 * 4);
+}
@@ -0,0 +1,117 @@
+use cgmath::{InnerSpace, Vector3};
+use graphics_playground::mesh::{Mesh, Vertex};
+
+const NORMAL: [f32; 3] = [0.0, 0.0, 1.0];
+
+// Rejilla en el plano z = 0 con las UVs que da `uv` para cada posición
+fn grid(columns: usize, rows: usize, uv: impl Fn(f32, f32) -> [f32; 2]) -> Mesh {
+    let mut vertices = Vec::new();
+    for row in 0..=rows {
+        for column in 0..=columns {
+            let (x, y) = (column as f32, row as f32);
+            vertices.push(Vertex::new([x, y, 0.0], NORMAL, uv(x, y)));
+        }
+    }
+    let mut indices = Vec::new();
+    for row in 0..rows as u32 {
+        for column in 0..columns as u32 {
+            let a = row * (columns as u32 + 1) + column;
+            let (b, c, d) = (a + 1, a + columns as u32 + 2, a + columns as u32 + 1);
+            indices.extend([a, b, c, a, c, d]);
+        }
+    }
+    Mesh::new(vertices, indices)
+}
+
+fn tangent(mesh: &Mesh, vertex: usize) -> [f32; 4] {
+    mesh.tangents.as_ref().unwrap()[vertex]
+}
+
+fn assert_parallel(a: [f32; 3], b: Vector3<f32>) {
+    assert!(Vector3::from(a).normalize().dot(b.normalize()) > 0.9999, "{:?} is not parallel to {:?}", a, b);
+}
+
+#[test]
+fn tangent_and_bitangent_follow_the_uvs() {
+    // UVs giradas 30 grados y con escalas distintas: dP/du y dP/dv siguen siendo perpendiculares
+    let (sin, cos) = 30f32.to_radians().sin_cos();
+    let mut mesh = grid(3, 3, |x, y| [(x * cos + y * sin) * 0.5, (-x * sin + y * cos) * 2.0]);
+    mesh.compute_tangents();
+    let dp_du = Vector3::new(cos, sin, 0.0);
+    let dp_dv = Vector3::new(-sin, cos, 0.0);
+
+    assert_eq!(mesh.vertices.len(), 16);
+    for vertex in 0..mesh.vertices.len() {
+        let tangent = tangent(&mesh, vertex);
+        assert_eq!(tangent[3], 1.0);
+        assert_parallel([tangent[0], tangent[1], tangent[2]], dp_du);
+        assert_parallel(mesh.bitangent(vertex).unwrap(), dp_dv);
+    }
+}
+
+#[test]
+fn mirrored_uvs_flip_the_bitangent_sign() {
+    let mut mesh = grid(2, 2, |x, y| [1.0 - x * 0.5, y * 0.5]);
+    mesh.compute_tangents();
+    for vertex in 0..mesh.vertices.len() {
+        let tangent = tangent(&mesh, vertex);
+        assert_eq!(tangent[3], -1.0);
+        assert_parallel([tangent[0], tangent[1], tangent[2]], Vector3::new(-1.0, 0.0, 0.0));
+        // El bitangente reconstruido sigue apuntando hacia la v creciente
+        assert_parallel(mesh.bitangent(vertex).unwrap(), Vector3::new(0.0, 1.0, 0.0));
+    }
+}
+
+#[test]
+fn mirror_seam_splits_the_shared_vertices() {
+    // Dos cuadrados con las UVs reflejadas en la columna x = 1, que comparten sus vértices
+    let mut mesh = grid(2, 1, |x, y| [if x <= 1.0 { x } else { 2.0 - x }, y]);
+    assert_eq!(mesh.vertices.len(), 6);
+    mesh.compute_tangents();
+
+    // Los dos vértices de la costura se duplican, uno por lado
+    assert_eq!(mesh.vertices.len(), 8);
+    for t in 0..mesh.triangle_count() {
+        let left = mesh.triangle(t).iter().all(|&index| mesh.vertices[index as usize].position[0] <= 1.0);
+        for index in mesh.triangle(t) {
+            assert_eq!(tangent(&mesh, index as usize)[3], if left { 1.0 } else { -1.0 });
+        }
+    }
+}
+
+#[test]
+fn fans_that_only_share_a_vertex_are_not_averaged() {
+    // Pajarita: dos triángulos con la misma orientación de UVs que solo comparten el vértice 0
+    let vertex = |position: [f32; 3], uv: [f32; 2]| Vertex::new(position, NORMAL, uv);
+    let vertices = vec![
+        vertex([0.0, 0.0, 0.0], [0.5, 0.5]),
+        vertex([1.0, -1.0, 0.0], [1.0, 0.0]),
+        vertex([1.0, 1.0, 0.0], [1.0, 1.0]),
+        vertex([-1.0, 1.0, 0.0], [1.0, 0.0]),
+        vertex([-1.0, -1.0, 0.0], [1.0, 1.0]),
+    ];
+    let mut mesh = Mesh::new(vertices, vec![0, 1, 2, 0, 3, 4]);
+    mesh.compute_tangents();
+
+    assert_eq!(mesh.vertices.len(), 6);
+    let [first, second] = [0, 3].map(|corner| tangent(&mesh, mesh.indices[corner] as usize));
+    assert_parallel([first[0], first[1], first[2]], Vector3::new(1.0, 0.0, 0.0));
+    assert_parallel([second[0], second[1], second[2]], Vector3::new(-1.0, 0.0, 0.0));
+}
+
+#[test]
+fn degenerate_uvs_take_the_neighbour_tangent() {
+    let mut reference = grid(1, 1, |x, y| [x, y]);
+    reference.compute_tangents();
+
+    // Un triángulo más, con las UVs alineadas, que comparte los vértices 1 y 3 del cuadrado
+    let mut mesh = grid(1, 1, |x, y| [x, y]);
+    mesh.vertices.push(Vertex::new([2.0, 0.5, 0.0], NORMAL, [1.0, 0.5]));
+    mesh.indices.extend([1, 4, 3]);
+    mesh.compute_tangents();
+    // No se duplica nada y los vértices compartidos conservan el tangente del triángulo válido
+    assert_eq!(mesh.vertices.len(), 5);
+    for vertex in [1, 3] {
+        assert_eq!(tangent(&mesh, vertex), tangent(&reference, vertex));
+    }
+}
@@ -0,0 +1,146 @@
+use cgmath::{Deg, InnerSpace, Matrix4, Vector3};
+use graphics_playground::{
+    mesh::{transform::Axis, Mesh},
+    model::{Model, SubMesh},
+};
+
+fn signed_volume(mesh: &Mesh) -> f32 {
+    mesh.triangles()
+        .map(|triangle| {
+            let [a, b, c] = triangle.map(|index| Vector3::from(mesh.vertices[index as usize].position));
+            a.dot(b.cross(c)) / 6.0
+        })
+        .sum()
+}
+
+// Triángulos como posiciones, para comparar mallas con los vértices en otro orden
+fn triangle_positions(mesh: &Mesh) -> Vec<[[f32; 3]; 3]> {
+    mesh.triangles().map(|triangle| triangle.map(|index| mesh.vertices[index as usize].position)).collect()
+}
+
+// Las normales del cubo son planas: deben coincidir con la normal geométrica de cada triángulo
+fn assert_flat_normals(mesh: &Mesh) {
+    for triangle in mesh.triangles() {
+        let [a, b, c] = triangle.map(|index| Vector3::from(mesh.vertices[index as usize].position));
+        let face = (b - a).cross(c - a).normalize();
+        for index in triangle {
+            let normal = Vector3::from(mesh.vertices[index as usize].normal);
+            assert!((normal.magnitude() - 1.0).abs() < 1e-5);
+            assert!(normal.dot(face) > 0.9999, "{:?} != {:?}", normal, face);
+        }
+    }
+}
+
+#[test]
+fn normals_stay_perpendicular_under_non_uniform_scale() {
+    let mut mesh = Mesh::cube(1.0);
+    let matrix = Matrix4::from_translation(Vector3::new(1.0, -2.0, 0.5))
+        * Matrix4::from_angle_y(Deg(30.0))
+        * Matrix4::from_nonuniform_scale(4.0, 1.0, 0.25)
+        * Matrix4::from_angle_x(Deg(45.0));
+    mesh.transform(&matrix);
+    assert_flat_normals(&mesh);
+    assert!((signed_volume(&mesh) - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn mirror_keeps_the_faces_outwards() {
+    for axis in [Axis::X, Axis::Y, Axis::Z] {
+        let mut mesh = Mesh::cube(1.0);
+        mesh.transform(&Matrix4::from_translation(Vector3::new(2.0, 3.0, 4.0)));
+        mesh.mirror(axis);
+        assert!((signed_volume(&mesh) - 1.0).abs() < 1e-4, "{:?}", axis);
+        assert_flat_normals(&mesh);
+        assert!(mesh.validate().is_valid());
+    }
+
+    // Una escala negativa cualquiera también cuenta como reflejo
+    let mut mesh = Mesh::cube(1.0);
+    mesh.transform(&Matrix4::from_nonuniform_scale(-2.0, 1.0, 1.0));
+    assert!((signed_volume(&mesh) - 2.0).abs() < 1e-4);
+    assert_flat_normals(&mesh);
+
+    // Dar la vuelta a la malla sí deja las caras hacia dentro
+    mesh.flip_winding();
+    assert!((signed_volume(&mesh) + 2.0).abs() < 1e-4);
+    assert_flat_normals(&mesh);
+}
+
+#[test]
+fn reflections_flip_the_tangent_sign() {
+    let mut mesh = Mesh::cube(1.0);
+    mesh.compute_tangents();
+    let signs = |mesh: &Mesh| mesh.tangents.as_ref().unwrap().iter().map(|tangent| tangent[3]).collect::<Vec<_>>();
+    let original = signs(&mesh);
+
+    mesh.transform(&Matrix4::from_angle_z(Deg(90.0)));
+    assert_eq!(signs(&mesh), original);
+
+    mesh.mirror(Axis::X);
+    assert_eq!(signs(&mesh), original.iter().map(|sign| -sign).collect::<Vec<_>>());
+
+    // Dos reflejos se anulan, y flip_winding también cambia el signo
+    mesh.mirror(Axis::Y);
+    assert_eq!(signs(&mesh), original);
+    mesh.flip_winding();
+    assert_eq!(signs(&mesh), original.iter().map(|sign| -sign).collect::<Vec<_>>());
+
+    // El tangente sigue siendo perpendicular a la normal
+    for (vertex, tangent) in mesh.vertices.iter().zip(mesh.tangents.as_ref().unwrap()) {
+        assert!(Vector3::from(vertex.normal).dot(Vector3::new(tangent[0], tangent[1], tangent[2])).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn split_components_of_two_disjoint_cubes() {
+    let first = Mesh::cube(1.0);
+    let mut second = Mesh::cube(1.0);
+    second.transform(&Matrix4::from_translation(Vector3::new(3.0, 0.0, 0.0)));
+    let merged = Mesh::merge(&[first.clone(), second.clone()]);
+
+    let parts = merged.split_components();
+    assert_eq!(parts.len(), 2);
+    for (part, original) in parts.iter().zip([&first, &second]) {
+        assert_eq!(part.vertices.len(), 24);
+        assert_eq!(triangle_positions(part), triangle_positions(original));
+    }
+
+    // Las costuras de UVs de la esfera repiten vértices pero no la parten
+    assert_eq!(Mesh::uv_sphere(1.0, 16, 8).split_components().len(), 1);
+}
+
+#[test]
+fn merge_offsets_indices_and_ranges() {
+    let cube = Mesh::cube(1.0);
+    let mut plane = Mesh::plane(1.0, 1.0, 2, 2);
+    plane.colors = Some(vec![[0.0, 1.0, 0.0, 1.0]; plane.vertices.len()]);
+    let merged = Mesh::merge(&[cube.clone(), plane.clone()]);
+
+    assert_eq!(merged.vertices.len(), cube.vertices.len() + plane.vertices.len());
+    assert_eq!(merged.indices[..cube.indices.len()], cube.indices[..]);
+    let offset = cube.vertices.len() as u32;
+    assert_eq!(merged.indices[cube.indices.len()..], plane.indices.iter().map(|index| index + offset).collect::<Vec<_>>()[..]);
+    // El cubo no tenía colores: se completan con blanco
+    let colors = merged.colors.as_ref().unwrap();
+    assert!(colors[..cube.vertices.len()].iter().all(|&color| color == [1.0; 4]));
+    assert_eq!(colors[cube.vertices.len()..], plane.colors.as_ref().unwrap()[..]);
+    assert!(merged.tangents.is_none());
+
+    // Cada malla ocupa un rango de triángulos que se puede usar como submalla
+    let mut model = Model::from_mesh(merged);
+    model.submeshes = [("cube", 0, cube.triangle_count()), ("plane", cube.triangle_count(), plane.triangle_count())]
+        .map(|(name, first_triangle, triangle_count)| SubMesh {
+            name: name.to_string(),
+            object: None,
+            material: None,
+            first_triangle,
+            triangle_count,
+        })
+        .to_vec();
+    for (submesh, original) in model.submeshes.iter().zip([&cube, &plane]) {
+        let part = model.extract_submesh(submesh);
+        assert_eq!(part.vertices.len(), original.vertices.len());
+        assert_eq!(triangle_positions(&part), triangle_positions(original));
+        assert_eq!(model.mesh.indices[submesh.index_range()].len(), original.indices.len());
+    }
+}
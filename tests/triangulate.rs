@@ -0,0 +1,184 @@
+use graphics_playground::{
+    mesh::triangulate::triangulate_polygon,
+    obj_parser::{parse_obj_from_str, ObjOptions},
+};
+
+fn planar(points: &[[f32; 2]]) -> Vec<[f32; 3]> {
+    points.iter().map(|p| [p[0], p[1], 0.0]).collect()
+}
+
+fn signed_area(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> f32 {
+    0.5 * ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]))
+}
+
+fn polygon_area(polygon: &[[f32; 3]]) -> f32 {
+    let n = polygon.len();
+    (0..n)
+        .map(|i| {
+            let a = polygon[i];
+            let b = polygon[(i + 1) % n];
+            0.5 * (a[0] * b[1] - b[0] * a[1])
+        })
+        .sum()
+}
+
+fn contains(polygon: &[[f32; 3]], p: [f32; 2]) -> bool {
+    let n = polygon.len();
+    let mut inside = false;
+    for i in 0..n {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % n];
+        if (a[1] > p[1]) != (b[1] > p[1]) {
+            let x = a[0] + (p[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]);
+            if p[0] < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+// Comprueba que la triangulación de un polígono plano en XY (antihorario) lo cubre exactamente
+fn assert_valid_triangulation(polygon: &[[f32; 3]]) {
+    let triangles = triangulate_polygon(polygon);
+    assert_eq!(triangles.len(), polygon.len() - 2);
+
+    let mut area = 0.0;
+    for t in triangles.iter() {
+        let (a, b, c) = (polygon[t[0]], polygon[t[1]], polygon[t[2]]);
+        let triangle_area = signed_area(a, b, c);
+        assert!(triangle_area > 0.0, "triángulo invertido o degenerado: {:?}", t);
+        area += triangle_area;
+
+        let centroid = [(a[0] + b[0] + c[0]) / 3.0, (a[1] + b[1] + c[1]) / 3.0];
+        assert!(contains(polygon, centroid), "triángulo fuera del polígono: {:?}", t);
+    }
+    assert!((area - polygon_area(polygon)).abs() < 1e-4);
+}
+
+#[test]
+fn convex_pentagon() {
+    assert_valid_triangulation(&planar(&[[0.0, 0.0], [2.0, 0.0], [3.0, 1.5], [1.0, 3.0], [-1.0, 1.5]]));
+}
+
+#[test]
+fn concave_quad() {
+    // Punta de flecha: el abanico desde el vértice 0 deja un triángulo fuera
+    let polygon = planar(&[[0.0, 0.0], [2.0, 1.0], [4.0, 0.0], [2.0, 3.0]]);
+    assert_valid_triangulation(&polygon);
+
+    let polygon = planar(&[[2.0, 1.0], [4.0, 0.0], [2.0, 3.0], [0.0, 0.0]]);
+    assert_valid_triangulation(&polygon);
+}
+
+#[test]
+fn l_shape() {
+    assert_valid_triangulation(&planar(&[
+        [0.0, 0.0],
+        [3.0, 0.0],
+        [3.0, 1.0],
+        [1.0, 1.0],
+        [1.0, 3.0],
+        [0.0, 3.0],
+    ]));
+}
+
+#[test]
+fn comb() {
+    assert_valid_triangulation(&planar(&[
+        [0.0, 0.0],
+        [5.0, 0.0],
+        [5.0, 3.0],
+        [4.0, 3.0],
+        [4.0, 1.0],
+        [3.0, 1.0],
+        [3.0, 3.0],
+        [2.0, 3.0],
+        [2.0, 1.0],
+        [1.0, 1.0],
+        [1.0, 3.0],
+        [0.0, 3.0],
+    ]));
+}
+
+#[test]
+fn star() {
+    let points: Vec<[f32; 2]> = (0..10)
+        .map(|i| {
+            let angle = i as f32 * std::f32::consts::PI / 5.0;
+            let radius = if i % 2 == 0 { 2.0 } else { 0.7 };
+            [radius * angle.cos(), radius * angle.sin()]
+        })
+        .collect();
+    assert_valid_triangulation(&planar(&points));
+}
+
+#[test]
+fn collinear_vertices() {
+    assert_valid_triangulation(&planar(&[
+        [0.0, 0.0],
+        [1.0, 0.0],
+        [2.0, 0.0],
+        [2.0, 2.0],
+        [1.0, 1.0],
+        [0.0, 2.0],
+    ]));
+}
+
+#[test]
+fn clockwise_polygon_keeps_winding() {
+    // L en sentido horario: los triángulos también deben quedar en sentido horario
+    let polygon = planar(&[
+        [0.0, 3.0],
+        [1.0, 3.0],
+        [1.0, 1.0],
+        [3.0, 1.0],
+        [3.0, 0.0],
+        [0.0, 0.0],
+    ]);
+    let triangles = triangulate_polygon(&polygon);
+    assert_eq!(triangles.len(), 4);
+    for t in triangles {
+        assert!(signed_area(polygon[t[0]], polygon[t[1]], polygon[t[2]]) < 0.0);
+    }
+}
+
+#[test]
+fn non_planar_concave_polygon() {
+    // L ligeramente deformada en Z y girada fuera del plano XY
+    let polygon: Vec<[f32; 3]> = [
+        [0.0, 0.0, 0.0],
+        [3.0, 0.0, 0.1],
+        [3.0, 1.0, -0.1],
+        [1.0, 1.0, 0.05],
+        [1.0, 3.0, 0.1],
+        [0.0, 3.0, -0.05],
+    ]
+    .iter()
+    .map(|p| [p[0], p[2], -p[1]])
+    .collect();
+
+    let triangles = triangulate_polygon(&polygon);
+    assert_eq!(triangles.len(), 4);
+
+    // Proyectado de vuelta al plano original tiene que ser una triangulación válida
+    let projected: Vec<[f32; 3]> = polygon.iter().map(|p| [p[0], -p[2], 0.0]).collect();
+    for t in triangles {
+        assert!(signed_area(projected[t[0]], projected[t[1]], projected[t[2]]) > 0.0);
+    }
+}
+
+#[test]
+fn parse_obj_with_ngons() {
+    let source = "v 0 0 0\nv 3 0 0\nv 3 1 0\nv 1 1 0\nv 1 3 0\nv 0 3 0\n\
+                  vt 0 0\nvn 0 0 1\n\
+                  f 1/1/1 2/1/1 3/1/1 4/1/1 5/1/1 6/1/1\n";
+
+    let model = parse_obj_from_str(source, &ObjOptions::default()).unwrap();
+    assert_eq!(model.mesh.triangle_count(), 4);
+    assert_eq!(model.submeshes[0].triangle_count, 4);
+    for t in model.mesh.triangles() {
+        let [a, b, c] = t.map(|i| model.mesh.vertices[i as usize].position);
+        assert!(signed_area(a, b, c) > 0.0);
+    }
+}
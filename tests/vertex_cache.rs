@@ -0,0 +1,108 @@
+use graphics_playground::{
+    mesh::{optimize::acmr, Mesh},
+    obj_parser::parse_obj,
+};
+
+// Triángulos como tríos de vértices, girados para empezar por el menor sin cambiar el sentido,
+// y ordenados: dos mallas dibujan lo mismo si dan la misma lista
+fn triangle_set(mesh: &Mesh, range: std::ops::Range<usize>) -> Vec<[[u32; 8]; 3]> {
+    let mut triangles: Vec<[[u32; 8]; 3]> = mesh.indices[range]
+        .chunks_exact(3)
+        .map(|triangle| {
+            let corners: Vec<[u32; 8]> = triangle
+                .iter()
+                .map(|&index| {
+                    let vertex = &mesh.vertices[index as usize];
+                    let mut key = [0; 8];
+                    for (key, value) in key.iter_mut().zip(vertex.position.iter().chain(&vertex.normal).chain(&vertex.uv)) {
+                        *key = value.to_bits();
+                    }
+                    key
+                })
+                .collect();
+            let first = (0..3).min_by_key(|&k| corners[k]).unwrap();
+            [0, 1, 2].map(|k| corners[(first + k) % 3])
+        })
+        .collect();
+    triangles.sort_unstable();
+    triangles
+}
+
+#[test]
+fn acmr_of_simple_orders() {
+    // Tira de cuatro triángulos: cada uno después del primero añade un vértice
+    let strip = [0, 1, 2, 2, 1, 3, 2, 3, 4, 4, 3, 5];
+    assert_eq!(acmr(&strip, 16), 1.5);
+    // Sin caché no hay aciertos
+    assert_eq!(acmr(&strip, 0), 3.0);
+    assert_eq!(acmr(&[], 16), 0.0);
+
+    // Con una caché FIFO de 3 el vértice 0 sale antes de volver a usarse
+    let fan = [0, 1, 2, 0, 2, 3, 0, 3, 4];
+    assert_eq!(acmr(&fan, 3), 2.0);
+    assert_eq!(acmr(&fan, 4), 5.0 / 3.0);
+}
+
+#[test]
+fn optimization_improves_acmr() {
+    for (path, cache_size) in [("sphere.obj", 16), ("sphere.obj", 32), ("flower.obj", 16), ("flower.obj", 32)] {
+        let original = parse_obj(path).unwrap().mesh;
+        let mut optimized = original.clone();
+        optimized.optimize();
+
+        let (before, after) = (original.acmr(cache_size), optimized.acmr(cache_size));
+        assert!(after < before, "{} with cache {}: {} -> {}", path, cache_size, before, after);
+        // Con una malla de superficie continua el orden óptimo ronda 0.5-0.7
+        assert!(after < 0.8, "{} with cache {}: {}", path, cache_size, after);
+
+        // Los mismos triángulos, en el mismo sentido, y los vértices ordenados según se usan
+        assert_eq!(triangle_set(&original, 0..original.indices.len()), triangle_set(&optimized, 0..optimized.indices.len()));
+        let mut next = 0;
+        for &index in optimized.indices.iter() {
+            assert!(index <= next);
+            next = next.max(index + 1);
+        }
+    }
+}
+
+#[test]
+fn each_pass_keeps_the_triangles() {
+    let original = parse_obj("flower.obj").unwrap().mesh;
+    let expected = triangle_set(&original, 0..original.indices.len());
+    let passes: [fn(&mut Mesh); 3] = [Mesh::optimize_vertex_cache, Mesh::optimize_overdraw, Mesh::optimize_vertex_fetch];
+
+    let mut mesh = original.clone();
+    for pass in passes {
+        pass(&mut mesh);
+        assert_eq!(triangle_set(&mesh, 0..mesh.indices.len()), expected);
+        assert_eq!(mesh.vertices.len(), original.vertices.len());
+    }
+
+    // Reordenar grupos enteros no debe deshacer lo ganado con la caché
+    let mut cache_only = original.clone();
+    cache_only.optimize_vertex_cache();
+    assert!(mesh.acmr(32) <= cache_only.acmr(32) * 1.05);
+}
+
+#[test]
+fn model_optimization_keeps_submeshes() {
+    let original = parse_obj("person.obj").unwrap();
+    let mut model = original.clone();
+    model.optimize();
+
+    assert!(model.mesh.acmr(32) < original.mesh.acmr(32));
+    assert_eq!(model.submeshes, original.submeshes);
+    for submesh in original.submeshes.iter() {
+        assert_eq!(
+            triangle_set(&original.mesh, submesh.index_range()),
+            triangle_set(&model.mesh, submesh.index_range())
+        );
+    }
+
+    // Los colores acompañan a sus vértices al reordenarlos
+    let mut mesh = original.mesh.clone();
+    mesh.colors = Some(mesh.vertices.iter().map(|vertex| [vertex.position[0], vertex.position[1], vertex.position[2], 1.0]).collect());
+    mesh.optimize();
+    let colors = mesh.colors.as_ref().unwrap();
+    assert!(mesh.vertices.iter().zip(colors).all(|(vertex, color)| vertex.position[..] == color[..3]));
+}
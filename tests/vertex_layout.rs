@@ -0,0 +1,89 @@
+use graphics_playground::{
+    mesh::Mesh,
+    vertex_layout::{AttributeType, LayoutError, ShaderAttribute, VertexLayout, COLOR_LOCATION, TANGENT_LOCATION},
+};
+
+fn shader_attribute(name: &str, location: i32, kind: gl::types::GLenum) -> ShaderAttribute {
+    ShaderAttribute { name: name.to_string(), location, kind }
+}
+
+// Los atributos que leen los shaders del proyecto
+fn default_shader() -> Vec<ShaderAttribute> {
+    vec![
+        shader_attribute("position", 0, gl::FLOAT_VEC3),
+        shader_attribute("normal", 1, gl::FLOAT_VEC3),
+        shader_attribute("uv", 2, gl::FLOAT_VEC2),
+    ]
+}
+
+#[test]
+fn mesh_layout_strides_and_offsets() {
+    let mut mesh = Mesh::cube(1.0);
+    let layout = mesh.layout();
+    assert_eq!(layout.stride, 32);
+    assert_eq!(layout.attributes.iter().map(|attribute| attribute.offset).collect::<Vec<_>>(), [0, 12, 24]);
+    assert_eq!(mesh.interleaved().len(), mesh.vertices.len() * 8);
+    assert_eq!(layout.validate(&default_shader()), Ok(()));
+
+    mesh.compute_tangents();
+    mesh.colors = Some(vec![[1.0, 0.0, 0.0, 1.0]; mesh.vertices.len()]);
+    let layout = mesh.layout();
+    assert_eq!(layout.stride, 64);
+    let tangent = layout.attribute("tangent").unwrap();
+    assert_eq!((tangent.location, tangent.components, tangent.offset), (TANGENT_LOCATION, 4, 32));
+    let color = layout.attribute("color").unwrap();
+    assert_eq!((color.location, color.components, color.offset), (COLOR_LOCATION, 4, 48));
+
+    // El color del primer vértice queda al final de sus 16 floats
+    let data = mesh.interleaved();
+    assert_eq!(data.len(), mesh.vertices.len() * 16);
+    assert_eq!(data[12..16], [1.0, 0.0, 0.0, 1.0]);
+}
+
+#[test]
+fn duplicate_locations_and_missing_attributes() {
+    let layout = VertexLayout::for_vertex().with("weights", 2, 4, AttributeType::UnsignedByte, true);
+    assert_eq!(
+        layout.validate(&[]),
+        Err(LayoutError::DuplicateLocation { location: 2, names: ["uv".to_string(), "weights".to_string()] })
+    );
+
+    let mut shader = default_shader();
+    shader.push(shader_attribute("tangent", 3, gl::FLOAT_VEC4));
+    assert_eq!(
+        VertexLayout::for_vertex().validate(&shader),
+        Err(LayoutError::MissingAttribute { name: "tangent".to_string(), location: 3 })
+    );
+    // Los atributos que el shader no lee no molestan
+    assert_eq!(VertexLayout::for_vertex().validate(&shader[..1]), Ok(()));
+}
+
+#[test]
+fn component_counts() {
+    // Un vec4 acepta tres componentes (w = 1), un vec3 no acepta cuatro ni un vec2 tres
+    let shader = [shader_attribute("position", 0, gl::FLOAT_VEC4)];
+    assert_eq!(VertexLayout::for_vertex().validate(&shader), Ok(()));
+
+    let layout = VertexLayout::new().with("position", 0, 4, AttributeType::Float, false);
+    assert_eq!(
+        layout.validate(&[shader_attribute("position", 0, gl::FLOAT_VEC3)]),
+        Err(LayoutError::ComponentMismatch { name: "position".to_string(), location: 0, layout: 4, shader: 3 })
+    );
+    let layout = VertexLayout::new().with("position", 0, 3, AttributeType::Float, false);
+    assert_eq!(
+        layout.validate(&[shader_attribute("position", 0, gl::FLOAT_VEC2)]),
+        Err(LayoutError::ComponentMismatch { name: "position".to_string(), location: 0, layout: 3, shader: 2 })
+    );
+}
+
+#[test]
+fn integer_and_matrix_inputs_are_rejected() {
+    // VertexAttribPointer no alimenta entradas enteras ni matrices
+    let layout = VertexLayout::new().with("joints", 0, 4, AttributeType::UnsignedByte, false);
+    for kind in [gl::INT, gl::UNSIGNED_INT_VEC4, gl::INT_VEC2, gl::FLOAT_MAT4] {
+        assert_eq!(
+            layout.validate(&[shader_attribute("joints", 0, kind)]),
+            Err(LayoutError::UnsupportedShaderType { name: "joints".to_string(), kind })
+        );
+    }
+}